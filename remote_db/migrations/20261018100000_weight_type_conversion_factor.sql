@@ -0,0 +1,10 @@
+-- Factor that converts a quantity in this unit into kilograms
+-- NULL means the unit can't be converted to a weight (pieces, boxes...)
+ALTER TABLE weight_type ADD COLUMN conversion_factor DOUBLE PRECISION;
+
+UPDATE weight_type SET conversion_factor = 0.000001 WHERE lower(unit_short) = 'mg';
+UPDATE weight_type SET conversion_factor = 0.001 WHERE lower(unit_short) = 'g';
+UPDATE weight_type SET conversion_factor = 0.01 WHERE lower(unit_short) = 'dag';
+UPDATE weight_type SET conversion_factor = 1 WHERE lower(unit_short) = 'kg';
+UPDATE weight_type SET conversion_factor = 100 WHERE lower(unit_short) = 'q';
+UPDATE weight_type SET conversion_factor = 1000 WHERE lower(unit_short) = 't';
@@ -0,0 +1,7 @@
+CREATE TABLE mbe_group_print_header(
+    id_mbe_group INT REFERENCES mbe_group(id) ON DELETE CASCADE PRIMARY KEY,
+    company_name TEXT,
+    address TEXT,
+    logo BYTEA,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,55 @@
+ALTER TABLE dispatch_note_ident_tracker
+    ADD COLUMN reset_yearly BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN identifier_year INT,
+    ADD COLUMN number_width INT NOT NULL DEFAULT 4 CHECK (number_width BETWEEN 1 AND 12);
+
+INSERT INTO dispatch_note_ident_tracker (id_data_group)
+    SELECT id FROM data_group
+    ON CONFLICT (id_data_group) DO NOTHING;
+
+ALTER TABLE dispatch_note
+    ADD COLUMN identifier_year INT,
+    ADD COLUMN formatted_identifier TEXT;
+
+-- Duplicated identifiers were possible while the client picked them,
+-- later duplicates get fresh numbers past the highest identifier of their data group
+WITH occurrences AS (
+    SELECT
+        id,
+        d_group,
+        ROW_NUMBER() OVER (PARTITION BY d_group, numerical_identifier ORDER BY id) AS occurrence
+    FROM dispatch_note
+    WHERE numerical_identifier IS NOT NULL
+), renumbered AS (
+    SELECT
+        occurrences.id,
+        (SELECT MAX(numerical_identifier) FROM dispatch_note WHERE dispatch_note.d_group = occurrences.d_group)
+            + ROW_NUMBER() OVER (PARTITION BY occurrences.d_group ORDER BY occurrences.id) AS numerical_identifier
+    FROM occurrences
+    WHERE occurrences.occurrence > 1
+)
+UPDATE dispatch_note
+SET numerical_identifier = renumbered.numerical_identifier
+FROM renumbered
+WHERE dispatch_note.id = renumbered.id;
+
+UPDATE dispatch_note_ident_tracker
+SET identifier = GREATEST(dispatch_note_ident_tracker.identifier, highest.numerical_identifier + 1)
+FROM (
+    SELECT d_group, MAX(numerical_identifier) AS numerical_identifier
+    FROM dispatch_note
+    GROUP BY d_group
+) AS highest
+WHERE dispatch_note_ident_tracker.id_data_group = highest.d_group
+    AND highest.numerical_identifier IS NOT NULL;
+
+UPDATE dispatch_note
+SET formatted_identifier = LPAD(
+    numerical_identifier::TEXT,
+    GREATEST(4, LENGTH(numerical_identifier::TEXT)),
+    '0'
+)
+WHERE numerical_identifier IS NOT NULL;
+
+CREATE UNIQUE INDEX dispatch_note_identifier_unique
+    ON dispatch_note (d_group, COALESCE(identifier_year, 0), numerical_identifier);
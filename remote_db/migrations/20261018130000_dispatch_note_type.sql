@@ -0,0 +1,56 @@
+CREATE TABLE dispatch_note_type(
+    id SERIAL PRIMARY KEY,
+    id_mbe_group INT REFERENCES mbe_group(id) ON DELETE CASCADE NOT NULL,
+    name TEXT NOT NULL,
+    direction VARCHAR(8) NOT NULL DEFAULT 'out' CHECK (direction IN ('in', 'out')),
+    own_numbering BOOLEAN NOT NULL DEFAULT FALSE,
+    print_template TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (id_mbe_group, name)
+);
+
+-- Bare integer note types become named types of the mbe group using them
+INSERT INTO dispatch_note_type (id_mbe_group, name)
+    SELECT DISTINCT data_group.id_mbe_group, 'Type ' || dispatch_note.note_type
+    FROM dispatch_note
+    INNER JOIN data_group ON dispatch_note.d_group = data_group.id
+    WHERE dispatch_note.note_type IS NOT NULL;
+
+UPDATE dispatch_note
+SET note_type = dispatch_note_type.id
+FROM data_group, dispatch_note_type
+WHERE dispatch_note.d_group = data_group.id
+    AND dispatch_note_type.id_mbe_group = data_group.id_mbe_group
+    AND dispatch_note_type.name = 'Type ' || dispatch_note.note_type;
+
+ALTER TABLE dispatch_note
+    ADD CONSTRAINT dispatch_note_note_type_fkey
+    FOREIGN KEY (note_type) REFERENCES dispatch_note_type(id);
+CREATE INDEX ON dispatch_note(note_type);
+
+-- Trackers are keyed by data group and optionally by a type numbered separately
+ALTER TABLE dispatch_note_ident_tracker DROP CONSTRAINT dispatch_note_ident_tracker_pkey;
+ALTER TABLE dispatch_note_ident_tracker ADD COLUMN id SERIAL PRIMARY KEY;
+ALTER TABLE dispatch_note_ident_tracker ALTER COLUMN id_data_group SET NOT NULL;
+ALTER TABLE dispatch_note_ident_tracker
+    ADD COLUMN id_dispatch_note_type INT REFERENCES dispatch_note_type(id) ON DELETE CASCADE;
+CREATE UNIQUE INDEX dispatch_note_ident_tracker_data_group_unique
+    ON dispatch_note_ident_tracker(id_data_group)
+    WHERE id_dispatch_note_type IS NULL;
+ALTER TABLE dispatch_note_ident_tracker
+    ADD CONSTRAINT dispatch_note_ident_tracker_type_unique
+    UNIQUE (id_data_group, id_dispatch_note_type);
+
+ALTER TABLE dispatch_note
+    ADD COLUMN id_ident_tracker INT REFERENCES dispatch_note_ident_tracker(id) ON DELETE SET NULL;
+
+UPDATE dispatch_note
+SET id_ident_tracker = dispatch_note_ident_tracker.id
+FROM dispatch_note_ident_tracker
+WHERE dispatch_note_ident_tracker.id_data_group = dispatch_note.d_group
+    AND dispatch_note_ident_tracker.id_dispatch_note_type IS NULL
+    AND dispatch_note.numerical_identifier IS NOT NULL;
+
+DROP INDEX dispatch_note_identifier_unique;
+CREATE UNIQUE INDEX dispatch_note_identifier_unique
+    ON dispatch_note (id_ident_tracker, COALESCE(identifier_year, 0), numerical_identifier);
@@ -0,0 +1,12 @@
+-- Notes created before the lifecycle existed stay editable drafts
+ALTER TABLE dispatch_note
+    ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'draft'
+        CHECK (status IN ('draft', 'issued', 'cancelled')),
+    ADD COLUMN issued_at TIMESTAMPTZ,
+    ADD COLUMN cancelled_at TIMESTAMPTZ,
+    ADD COLUMN cancellation_reason TEXT,
+    ADD COLUMN corrects_dispatch_note INT REFERENCES dispatch_note(id),
+    ADD CONSTRAINT dispatch_note_cancellation_reason_check
+        CHECK (status <> 'cancelled' OR cancellation_reason IS NOT NULL);
+
+CREATE INDEX ON dispatch_note(corrects_dispatch_note);
@@ -0,0 +1,6 @@
+-- Counterparty of the dispatch note, existing notes have none
+ALTER TABLE dispatch_note
+    ADD COLUMN id_buyer INT REFERENCES buyer(id),
+    ADD COLUMN delivery_address TEXT;
+
+CREATE INDEX ON dispatch_note(id_buyer);
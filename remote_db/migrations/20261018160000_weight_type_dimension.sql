@@ -0,0 +1,20 @@
+-- Quantities can only be converted between units of the same dimension,
+-- `conversion_factor` now converts into the base unit of the dimension (kg, l or piece)
+ALTER TABLE weight_type
+    ADD COLUMN dimension VARCHAR(8) NOT NULL DEFAULT 'mass'
+        CHECK (dimension IN ('mass', 'volume', 'count')),
+    ADD CONSTRAINT weight_type_conversion_factor_check
+        CHECK (conversion_factor IS NULL OR conversion_factor > 0);
+
+UPDATE weight_type SET dimension = 'volume', conversion_factor = 0.001
+    WHERE conversion_factor IS NULL AND lower(unit_short) = 'ml';
+UPDATE weight_type SET dimension = 'volume', conversion_factor = 0.1
+    WHERE conversion_factor IS NULL AND lower(unit_short) = 'dl';
+UPDATE weight_type SET dimension = 'volume', conversion_factor = 1
+    WHERE conversion_factor IS NULL AND lower(unit_short) = 'l';
+UPDATE weight_type SET dimension = 'volume', conversion_factor = 100
+    WHERE conversion_factor IS NULL AND lower(unit_short) = 'hl';
+UPDATE weight_type SET dimension = 'volume', conversion_factor = 1000
+    WHERE conversion_factor IS NULL AND lower(unit_short) IN ('m3', 'm³');
+UPDATE weight_type SET dimension = 'count', conversion_factor = 1
+    WHERE conversion_factor IS NULL AND lower(unit_short) IN ('pcs', 'pc', 'kom');
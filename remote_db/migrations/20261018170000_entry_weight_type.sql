@@ -0,0 +1,45 @@
+-- Entry weights were always entered in kilograms, every mbe group with entries
+-- gets a kilogram weight type (unless it has one) to backfill its existing entries with
+CREATE TEMPORARY TABLE entry_weight_type_backfill AS
+SELECT DISTINCT data_group.id_mbe_group, mbe_group.owner
+FROM entry
+    INNER JOIN data_group ON data_group.id = entry.d_group
+    INNER JOIN mbe_group ON mbe_group.id = data_group.id_mbe_group
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM mbe_groups_weight_types
+        INNER JOIN weight_type ON weight_type.id = mbe_groups_weight_types.id_weight_type
+    WHERE mbe_groups_weight_types.id_mbe_group = data_group.id_mbe_group
+        AND weight_type.dimension = 'mass'
+        AND weight_type.conversion_factor = 1
+);
+
+ALTER TABLE entry_weight_type_backfill ADD COLUMN id_weight_type INT;
+UPDATE entry_weight_type_backfill
+    SET id_weight_type = nextval(pg_get_serial_sequence('weight_type', 'id'));
+
+INSERT INTO weight_type (id, unit_short, unit, conversion_factor, dimension)
+SELECT id_weight_type, 'kg', 'Kilogram', 1, 'mass' FROM entry_weight_type_backfill;
+
+INSERT INTO mbe_groups_weight_types (id_weight_type, id_created_by, id_mbe_group)
+SELECT id_weight_type, owner, id_mbe_group FROM entry_weight_type_backfill;
+
+DROP TABLE entry_weight_type_backfill;
+
+ALTER TABLE entry ADD COLUMN weight_type INT REFERENCES weight_type(id);
+
+UPDATE entry SET weight_type = (
+    SELECT weight_type.id
+    FROM weight_type
+        INNER JOIN mbe_groups_weight_types ON mbe_groups_weight_types.id_weight_type = weight_type.id
+        INNER JOIN data_group ON data_group.id_mbe_group = mbe_groups_weight_types.id_mbe_group
+    WHERE data_group.id = entry.d_group
+        AND weight_type.dimension = 'mass'
+        AND weight_type.conversion_factor = 1
+    ORDER BY lower(weight_type.unit_short) = 'kg' DESC, weight_type.id
+    LIMIT 1
+);
+
+ALTER TABLE entry ALTER COLUMN weight_type SET NOT NULL;
+
+CREATE INDEX ON entry(weight_type);
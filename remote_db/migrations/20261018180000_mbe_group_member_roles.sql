@@ -0,0 +1,13 @@
+-- Existing members keep full write access, the group owner becomes the single 'owner' member
+ALTER TABLE mbe_group_members
+    ADD COLUMN role VARCHAR(8) NOT NULL DEFAULT 'editor'
+        CHECK (role IN ('owner', 'admin', 'editor', 'viewer'));
+
+INSERT INTO mbe_group_members (id_mbe_user, id_mbe_group, role)
+    SELECT owner, id, 'owner' FROM mbe_group
+    ON CONFLICT (id_mbe_user, id_mbe_group) DO UPDATE SET role = 'owner';
+
+ALTER TABLE mbe_group_members ALTER COLUMN role SET DEFAULT 'viewer';
+
+CREATE UNIQUE INDEX mbe_group_members_owner_index ON mbe_group_members(id_mbe_group)
+    WHERE role = 'owner';
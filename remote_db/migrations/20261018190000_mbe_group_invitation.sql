@@ -0,0 +1,19 @@
+CREATE TABLE mbe_group_invitation(
+    id SERIAL PRIMARY KEY,
+    id_mbe_group INT REFERENCES mbe_group(id) ON DELETE CASCADE NOT NULL,
+    email TEXT NOT NULL,
+    role VARCHAR(8) NOT NULL CHECK (role IN ('admin', 'editor', 'viewer')),
+    -- Sha512 hash of the token sent to the invitee
+    token_hash VARCHAR(128) UNIQUE NOT NULL,
+    invited_by INT REFERENCES mbe_user(id) NOT NULL,
+    status VARCHAR(8) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    responded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX mbe_group_invitation_email_index ON mbe_group_invitation(email);
+
+CREATE UNIQUE INDEX mbe_group_invitation_pending_index ON mbe_group_invitation(id_mbe_group, email)
+    WHERE status = 'pending';
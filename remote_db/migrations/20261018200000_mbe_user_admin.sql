@@ -0,0 +1,2 @@
+-- Administrators of the whole instance, granted directly in the database
+ALTER TABLE mbe_user ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT FALSE;
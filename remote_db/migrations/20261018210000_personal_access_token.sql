@@ -0,0 +1,20 @@
+CREATE TABLE personal_access_token(
+    id SERIAL PRIMARY KEY,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE CASCADE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    -- Sha512 hash of the token, the token itself is only shown once
+    token_hash VARCHAR(128) UNIQUE NOT NULL,
+    access VARCHAR(8) NOT NULL CHECK (access IN ('read', 'write')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX personal_access_token_user_index ON personal_access_token(id_mbe_user);
+
+CREATE TABLE personal_access_token_mbe_groups(
+    id_personal_access_token INT REFERENCES personal_access_token(id) ON DELETE CASCADE NOT NULL,
+    id_mbe_group INT REFERENCES mbe_group(id) ON DELETE CASCADE NOT NULL,
+    PRIMARY KEY (id_personal_access_token, id_mbe_group)
+);
@@ -0,0 +1,4 @@
+-- Optional password login for installs without access to the login providers
+ALTER TABLE mbe_user ADD COLUMN password_hash TEXT;
+ALTER TABLE mbe_user ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE mbe_user ADD COLUMN locked_until TIMESTAMPTZ;
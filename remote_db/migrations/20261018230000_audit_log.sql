@@ -0,0 +1,17 @@
+-- Append-only record of every change made through the api
+CREATE TABLE audit_log(
+    id BIGSERIAL PRIMARY KEY,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL,
+    id_mbe_group INT REFERENCES mbe_group(id) ON DELETE CASCADE,
+    -- Not a foreign key so entries outlive deleted data groups
+    d_group INT,
+    entity_type VARCHAR(32) NOT NULL,
+    entity_id INT NOT NULL,
+    action VARCHAR(8) NOT NULL CHECK (action IN ('insert', 'update', 'delete')),
+    before JSONB,
+    after JSONB,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX audit_log_mbe_group_index ON audit_log(id_mbe_group, created_at DESC);
+CREATE INDEX audit_log_entity_index ON audit_log(entity_type, entity_id);
@@ -0,0 +1,14 @@
+-- Deleted rows are kept until they are purged after the retention period
+ALTER TABLE cell ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE culture ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE buyer ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE article ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE entry ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE dispatch_note ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX ON cell(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX ON culture(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX ON buyer(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX ON article(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX ON entry(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX ON dispatch_note(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,134 @@
+-- Every version of the data group entities, written by the api next to the audit log.
+-- Columns added to an entity table later have to be added to its history table as well,
+-- versions are copied by column name
+CREATE TABLE article_history(
+    LIKE article,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON article_history(id, valid_from);
+
+CREATE TABLE buyer_history(
+    LIKE buyer,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON buyer_history(id, valid_from);
+
+CREATE TABLE cell_history(
+    LIKE cell,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON cell_history(id, valid_from);
+
+CREATE TABLE culture_history(
+    LIKE culture,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON culture_history(id, valid_from);
+
+CREATE TABLE cell_culture_pair_history(
+    LIKE cell_culture_pair,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON cell_culture_pair_history(id, valid_from);
+
+CREATE TABLE data_group_history(
+    LIKE data_group,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON data_group_history(id, valid_from);
+
+CREATE TABLE dispatch_note_history(
+    LIKE dispatch_note,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON dispatch_note_history(id, valid_from);
+
+CREATE TABLE dispatch_note_article_history(
+    LIKE dispatch_note_article,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON dispatch_note_article_history(id, valid_from);
+
+CREATE TABLE entry_history(
+    LIKE entry,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    id_mbe_user INT REFERENCES mbe_user(id) ON DELETE SET NULL
+);
+CREATE INDEX ON entry_history(id, valid_from);
+
+-- Existing rows become the first version, deleted rows are closed when they were deleted
+INSERT INTO article_history SELECT article.*, article.created_at, article.deleted_at FROM article;
+INSERT INTO buyer_history SELECT buyer.*, buyer.created_at, buyer.deleted_at FROM buyer;
+INSERT INTO cell_history SELECT cell.*, cell.created_at, cell.deleted_at FROM cell;
+INSERT INTO culture_history SELECT culture.*, culture.created_at, culture.deleted_at FROM culture;
+INSERT INTO cell_culture_pair_history SELECT cell_culture_pair.*, cell_culture_pair.created_at, NULL::TIMESTAMPTZ FROM cell_culture_pair;
+INSERT INTO data_group_history SELECT data_group.*, data_group.created_at, NULL::TIMESTAMPTZ FROM data_group;
+INSERT INTO dispatch_note_history SELECT dispatch_note.*, dispatch_note.created_at, dispatch_note.deleted_at FROM dispatch_note;
+INSERT INTO dispatch_note_article_history SELECT dispatch_note_article.*, dispatch_note_article.created_at, NULL::TIMESTAMPTZ FROM dispatch_note_article;
+INSERT INTO entry_history SELECT entry.*, entry.created_at, entry.deleted_at FROM entry;
+
+-- Views named like the entity tables, `QueryDatabase::fetch` puts this schema first
+-- on the search path to read the state at `mbe.as_of`
+CREATE SCHEMA as_of;
+
+CREATE VIEW as_of.article AS
+    SELECT * FROM article_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.buyer AS
+    SELECT * FROM buyer_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.cell AS
+    SELECT * FROM cell_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.culture AS
+    SELECT * FROM culture_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.cell_culture_pair AS
+    SELECT * FROM cell_culture_pair_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.data_group AS
+    SELECT * FROM data_group_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.dispatch_note AS
+    SELECT * FROM dispatch_note_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.dispatch_note_article AS
+    SELECT * FROM dispatch_note_article_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
+
+CREATE VIEW as_of.entry AS
+    SELECT * FROM entry_history
+    WHERE valid_from <= current_setting('mbe.as_of')::TIMESTAMPTZ
+        AND (valid_to IS NULL OR valid_to > current_setting('mbe.as_of')::TIMESTAMPTZ);
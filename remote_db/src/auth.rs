@@ -3,19 +3,25 @@ use std::{borrow::Cow, env, error::Error, future::Future, pin::Pin};
 use actix_session::{Session, SessionExt};
 use actix_web::{
     get,
-    http::header::LOCATION,
+    http::header::{AUTHORIZATION, LOCATION},
     post,
     web::{Json, Query},
-    FromRequest, HttpResponse,
+    FromRequest, HttpRequest, HttpResponse,
 };
+use async_graphql::Enum;
 use log::error;
 use oauth2::{
     basic::BasicClient, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken,
     PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
 };
 use rand::{RngCore, SeedableRng};
-use redis::{aio::ConnectionLike, Cmd};
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use redis::{
+    aio::{ConnectionLike, ConnectionManager},
+    Cmd,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha512};
 
@@ -23,18 +29,24 @@ use crate::{
     http_response_errors::{AuthCallbackError, AuthError},
     load_env_var,
     redis_connection_manager::RedisConnectionManagerExt,
-    user_models::mbe_user,
+    registration::RegistrationPolicy,
+    user_models::{
+        mbe_group_invitation,
+        mbe_group_members::MbeGroupRole,
+        mbe_user, mbe_user_session,
+        personal_access_token::{self, TokenAccess},
+    },
     SeaOrmPool,
 };
 
-const CSRF_CACHE_EXPIRY: usize = 60 * 5;
+pub const CSRF_CACHE_EXPIRY: usize = 60 * 5;
 const TEMP_VERIFICATION_KEYS_CACHE_EXPIRY: usize = 60 * 5;
 
 type MbeUserId = i32;
 
 pub const SESSION_DATA_KEY: &str = "SESSION_DATA";
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, Enum)]
 #[serde(rename_all = "lowercase")]
 pub enum Platform {
     Tauri,
@@ -46,10 +58,17 @@ pub enum Platform {
 pub struct RedirectUriParams {
     #[serde(default)]
     pub platform: Platform,
+    /// Token of a group invitation accepted once the login succeeds
+    pub invitation: Option<String>,
+}
+
+/// Invitation tokens are cached next to the pkce verifier of the same login attempt
+pub fn invitation_cache_key(csrf_state: &str) -> String {
+    format!("invitation:{}", csrf_state)
 }
 
 impl Platform {
-    fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &str {
         match self {
             Platform::Tauri => "tauri",
             Platform::Web => "web",
@@ -57,17 +76,58 @@ impl Platform {
     }
 }
 
+/// Scope of the personal access token a request was authenticated with
+#[derive(Debug, Clone)]
+pub struct TokenScope {
+    /// Id of the personal access token
+    pub id: i32,
+    pub access: TokenAccess,
+    pub mbe_groups: Vec<i32>,
+}
+
+impl TokenScope {
+    /// Highest role the token can act with in the mbe group, `None` outside of its scope
+    pub fn role_limit(&self, id_mbe_group: i32) -> Option<MbeGroupRole> {
+        if !self.mbe_groups.contains(&id_mbe_group) {
+            return None;
+        }
+
+        Some(match self.access {
+            TokenAccess::Read => MbeGroupRole::Viewer,
+            TokenAccess::Write => MbeGroupRole::Editor,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
     pub user_id: i32,
+    /// Id in the session registry, missing for tokens and sessions from before the registry
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Set when authenticated with a personal access token instead of the session cookie
+    #[serde(skip)]
+    pub token: Option<TokenScope>,
 }
 
 impl SessionData {
-    fn new(user_id: i32) -> Self {
-        Self { user_id }
+    /// Checks if a personal access token restricts access to the mbe group
+    pub fn can_access_mbe_group(&self, id_mbe_group: i32) -> bool {
+        self.token
+            .as_ref()
+            .is_none_or(|token| token.mbe_groups.contains(&id_mbe_group))
     }
 }
 
+fn bearer_token(req: &actix_web::HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
 impl FromRequest for SessionData {
     type Error = actix_web::Error;
 
@@ -80,11 +140,35 @@ impl FromRequest for SessionData {
         let req = req.clone();
 
         Box::pin(async move {
+            if let Some(token) = bearer_token(&req) {
+                let db_pool = req
+                    .app_data::<SeaOrmPool>()
+                    .expect("An existing SeaOrmPool");
+                return Ok(personal_access_token::Entity::authenticate(db_pool, token)
+                    .await
+                    .map_err(AuthError::from)?
+                    .ok_or(AuthError::InvalidSession)?);
+            }
+
             let session = req.get_session();
-            Ok(session
+            let session_data = session
                 .get::<SessionData>(SESSION_DATA_KEY)
                 .map_err(|_e| AuthError::InvalidSession)?
-                .ok_or(AuthError::InvalidSession)?)
+                .ok_or(AuthError::InvalidSession)?;
+
+            let RedisConnectionManagerExt(mut redis_cache) = req
+                .app_data::<RedisConnectionManagerExt>()
+                .cloned()
+                .expect("An existing RedisConnectionManager");
+            if !mbe_user_session::touch(&mut redis_cache, &session_data, &req)
+                .await
+                .map_err(AuthError::from)?
+            {
+                session.purge();
+                return Err(AuthError::InvalidSession.into());
+            }
+
+            Ok(session_data)
         })
     }
 }
@@ -163,33 +247,49 @@ impl FromRequest for GlobalReqwestClient {
 const HEX_TABLE: &[u8; 16] = b"0123456789abcdef";
 const TEMP_CODE_LEN: usize = 2048;
 
-#[derive(Debug, Deserialize)]
-struct TemporaryVerificationCode {
-    code: String,
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut acc, byte| {
+            acc.push(HEX_TABLE[(byte >> 4) as usize] as char);
+            acc.push(HEX_TABLE[(byte & 0x0f) as usize] as char);
+            acc
+        })
 }
 
-impl TemporaryVerificationCode {
-    fn new() -> Result<Self, AuthCallbackError> {
-        let mut hasher = Sha512::new();
-        let mut rng = rand::rngs::StdRng::from_entropy();
-        let mut random_code_bytes = [0u8; TEMP_CODE_LEN];
+/// Random hex encoded secret, used for temporary codes and invitation links
+pub fn generate_token() -> Result<String, rand::Error> {
+    let mut rng = rand::rngs::StdRng::from_entropy();
+    let mut random_code_bytes = [0u8; TEMP_CODE_LEN];
+
+    rng.try_fill_bytes(&mut random_code_bytes)?;
+
+    Ok(to_hex(&Sha512::digest(random_code_bytes)))
+}
 
-        rng.try_fill_bytes(&mut random_code_bytes)?;
+/// Identifies a login session in the session registry, not a secret by itself
+pub fn generate_session_id() -> String {
+    let mut session_id_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut session_id_bytes);
 
-        hasher.update(random_code_bytes);
+    to_hex(&session_id_bytes)
+}
 
-        let hashed_code = hasher.finalize();
+/// Tokens are only stored hashed so a leaked database can not be used to redeem them
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha512::digest(token.as_bytes()))
+}
 
-        let code =
-            hashed_code
-                .iter()
-                .fold(String::with_capacity(TEMP_CODE_LEN * 2), |mut acc, byte| {
-                    acc.push(HEX_TABLE[(byte >> 4) as usize] as char);
-                    acc.push(HEX_TABLE[(byte & 0x0f) as usize] as char);
-                    acc
-                });
+#[derive(Debug, Deserialize)]
+struct TemporaryVerificationCode {
+    code: String,
+}
 
-        Ok(Self { code })
+impl TemporaryVerificationCode {
+    fn new() -> Result<Self, AuthCallbackError> {
+        Ok(Self {
+            code: generate_token()?,
+        })
     }
 
     fn get_code(&self) -> &String {
@@ -203,14 +303,14 @@ impl TemporaryVerificationCode {
 
 #[derive(Deserialize, Debug)]
 pub struct AuthCallbackParams {
-    code: Option<String>,
-    state: Option<String>,
-    error: Option<String>,
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
     #[serde(default)]
-    platform: Platform,
+    pub platform: Platform,
 }
 
-fn gen_redirect_url(
+pub fn gen_redirect_url(
     redirect_url: String,
     platform: &str,
 ) -> Result<RedirectUrl, oauth2::url::ParseError> {
@@ -232,12 +332,12 @@ macro_rules! impl_oauth_client {
         pub struct $name($type);
 
         impl $name {
-            pub fn new() -> $name {
-                $name(
+            /// `None` when the provider has no client id configured
+            pub fn from_env() -> Option<$name> {
+                let client_id = env::var($client_id_env).ok()?;
+                Some($name(
                     BasicClient::new(
-                        ClientId::new(
-                            load_env_var!($client_id_env)
-                        ),
+                        ClientId::new(client_id),
                         Some(ClientSecret::new(load_env_var!($client_secret_env))),
                         AuthUrl::new(load_env_var!($auth_endpoint)).expect("A valid auth url"),
                         Some(
@@ -248,7 +348,7 @@ macro_rules! impl_oauth_client {
                     .set_redirect_uri(
                         RedirectUrl::new(load_env_var!($redirect_uri)).expect("A valid redirect url"),
                     ),
-                )
+                ))
             }
         }
 
@@ -349,6 +449,16 @@ macro_rules! login_route {(
                 ))
                 .await?;
 
+            if let Some(invitation) = params.invitation {
+                redis_cache
+                    .req_packed_command(&redis::Cmd::set_ex(
+                        invitation_cache_key(csrf_token.secret()),
+                        invitation,
+                        CSRF_CACHE_EXPIRY,
+                    ))
+                    .await?;
+            }
+
             Ok(HttpResponse::SeeOther()
                 .insert_header((LOCATION, url.to_string()))
                 .finish())
@@ -388,6 +498,86 @@ login_route![
 //     "email"
 // ];
 
+/// Logs in the user with the email confirmed by the login provider,
+/// users are created on their first login if the registration policy allows it
+#[allow(clippy::too_many_arguments)]
+pub async fn complete_login(
+    email: String,
+    invitation: Option<String>,
+    platform: &Platform,
+    db_pool: &SeaOrmPool,
+    registration_policy: &RegistrationPolicy,
+    session: &Session,
+    redis_cache: &mut ConnectionManager,
+    req: &HttpRequest,
+) -> Result<HttpResponse, AuthCallbackError> {
+    let email = email.to_lowercase();
+
+    let transaction = db_pool.begin().await?;
+
+    let user = match mbe_user::Entity::find()
+        .filter(mbe_user::Column::Email.eq(email.as_str()))
+        .one(&transaction)
+        .await?
+    {
+        Some(user) => user,
+        None => {
+            if !registration_policy
+                .allows(&transaction, &email, invitation.as_deref())
+                .await?
+            {
+                return Err(AuthCallbackError::RegistrationNotAllowed);
+            }
+
+            mbe_user::ActiveModel {
+                email: ActiveValue::Set(email),
+                ..Default::default()
+            }
+            .insert(&transaction)
+            .await?
+        }
+    };
+
+    transaction.commit().await?;
+
+    // A stale invitation should not prevent logging in
+    if let Some(invitation) = invitation {
+        if let Err(e) =
+            mbe_group_invitation::Entity::accept_with_token(db_pool, &invitation, user.id).await
+        {
+            error!("Accepting group invitation failed: {}", e);
+        }
+    }
+
+    match platform {
+        Platform::Web => {
+            let session_data =
+                mbe_user_session::register(redis_cache, user.id, Platform::Web, req).await?;
+            session.renew();
+            session.insert(SESSION_DATA_KEY, session_data)?;
+
+            let callback_url =
+                env::var("CALLBACK_URL").expect("CALLBACK_URL environment variable must be set");
+
+            Ok(HttpResponse::SeeOther()
+                .insert_header((LOCATION, callback_url))
+                .finish())
+        }
+        Platform::Tauri => {
+            let temp_code = TemporaryVerificationCode::new()?;
+            Cmd::set_ex(
+                temp_code.get_code(),
+                user.id,
+                TEMP_VERIFICATION_KEYS_CACHE_EXPIRY,
+            )
+            .query_async::<_, ()>(redis_cache)
+            .await?;
+
+            Ok(HttpResponse::Ok().body(temp_code.get_code_owned()))
+        }
+    }
+}
+
 macro_rules! callback_route {(
         $(#[$attr:meta])*
         $name:ident,
@@ -397,6 +587,7 @@ macro_rules! callback_route {(
         $user_info_type:ty
         ) => {
         $(#[$attr])*
+        #[allow(clippy::too_many_arguments)]
         pub async fn $name(
             Query(params): Query<AuthCallbackParams>,
             RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
@@ -404,6 +595,8 @@ macro_rules! callback_route {(
             client: $client,
             db_pool: SeaOrmPool,
             session: Session,
+            registration_policy: RegistrationPolicy,
+            req: HttpRequest,
         ) -> Result<HttpResponse, AuthCallbackError> {
             let callback_url =
                 env::var("CALLBACK_URL").expect("CALLBACK_URL environment variable must be set");
@@ -422,6 +615,9 @@ macro_rules! callback_route {(
             if let (Some(csrf_state), Some(auth_code)) = (params.state, params.code) {
                 let pkce_verifier: Option<String> =
                     Cmd::get(&csrf_state).query_async(&mut redis_cache).await?;
+                let invitation: Option<String> = Cmd::get_del(invitation_cache_key(&csrf_state))
+                    .query_async(&mut redis_cache)
+                    .await?;
 
                 match Cmd::del(csrf_state)
                     .query_async::<_, i64>(&mut redis_cache)
@@ -459,36 +655,17 @@ macro_rules! callback_route {(
                     .json::<$user_info_type>()
                     .await?;
 
-                let transaction = db_pool.begin().await?;
-
-                let user = mbe_user::Entity::find()
-                    .filter(mbe_user::Column::Email.eq(response.get_email()?))
-                    .one(&transaction)
-                    .await?;
-
-                transaction.commit().await?;
-
-                match user {
-                    Some(user) => match params.platform {
-                        Platform::Web => {
-                            session.renew();
-                            session.insert(SESSION_DATA_KEY, SessionData::new(user.id))?;
-
-                            Ok(HttpResponse::SeeOther()
-                                .insert_header((LOCATION, callback_url))
-                                .finish())
-                        },
-                        Platform::Tauri => {
-                            let temp_code = TemporaryVerificationCode::new()?;
-                            Cmd::set_ex(temp_code.get_code(), user.id, TEMP_VERIFICATION_KEYS_CACHE_EXPIRY)
-                                .query_async(&mut redis_cache)
-                                .await?;
-
-                            Ok(HttpResponse::Ok().body(temp_code.get_code_owned()))
-                        }
-                    },
-                    None => Err(AuthCallbackError::UserNotFound),
-                }
+                complete_login(
+                    response.get_email()?,
+                    invitation,
+                    &params.platform,
+                    &db_pool,
+                    &registration_policy,
+                    &session,
+                    &mut redis_cache,
+                    &req,
+                )
+                .await
             } else {
                 Err(AuthCallbackError::MissingStateOrAuthCode)?
             }
@@ -533,7 +710,18 @@ callback_route![
 // ];
 
 #[get("/logout")]
-pub async fn logout(session: Session) -> Result<HttpResponse, AuthError> {
+pub async fn logout(
+    session: Session,
+    RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+) -> Result<HttpResponse, AuthError> {
+    if let Ok(Some(SessionData {
+        user_id,
+        session_id: Some(session_id),
+        ..
+    })) = session.get::<SessionData>(SESSION_DATA_KEY)
+    {
+        mbe_user_session::revoke(&mut redis_cache, user_id, &session_id).await?;
+    }
     session.purge();
     Ok(HttpResponse::Ok().finish())
 }
@@ -543,6 +731,7 @@ async fn manual_auth(
     session: Session,
     temp: Json<TemporaryVerificationCode>,
     RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+    req: HttpRequest,
 ) -> Result<HttpResponse, AuthError> {
     let id: Option<MbeUserId> = Cmd::get(temp.get_code())
         .query_async(&mut redis_cache)
@@ -562,10 +751,79 @@ async fn manual_auth(
 
     match id {
         Some(id) => {
+            let session_data =
+                mbe_user_session::register(&mut redis_cache, id, Platform::Tauri, &req).await?;
             session.renew();
-            session.insert(SESSION_DATA_KEY, SessionData::new(id))?;
+            session.insert(SESSION_DATA_KEY, session_data)?;
             Ok(HttpResponse::Ok().finish())
         }
         None => Err(AuthError::InvalidTempCode)?,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    fn token(access: TokenAccess) -> TokenScope {
+        TokenScope {
+            id: 1,
+            access,
+            mbe_groups: vec![2, 3],
+        }
+    }
+
+    #[test]
+    fn token_role_follows_access() {
+        assert_eq!(
+            token(TokenAccess::Read).role_limit(2),
+            Some(MbeGroupRole::Viewer)
+        );
+        assert_eq!(
+            token(TokenAccess::Write).role_limit(3),
+            Some(MbeGroupRole::Editor)
+        );
+    }
+
+    #[test]
+    fn token_has_no_role_outside_its_groups() {
+        assert_eq!(token(TokenAccess::Write).role_limit(4), None);
+    }
+
+    #[test]
+    fn only_tokens_restrict_mbe_groups() {
+        let session = SessionData {
+            user_id: 1,
+            session_id: None,
+            token: None,
+        };
+        assert!(session.can_access_mbe_group(4));
+
+        let session = SessionData {
+            token: Some(token(TokenAccess::Read)),
+            ..session
+        };
+        assert!(session.can_access_mbe_group(2));
+        assert!(!session.can_access_mbe_group(4));
+    }
+
+    #[test]
+    fn reads_bearer_token() {
+        let req = TestRequest::default()
+            .insert_header((AUTHORIZATION, "Bearer  secret "))
+            .to_http_request();
+        assert_eq!(bearer_token(&req), Some("secret"));
+
+        let req = TestRequest::default()
+            .insert_header((AUTHORIZATION, "Basic secret"))
+            .to_http_request();
+        assert_eq!(bearer_token(&req), None);
+
+        assert_eq!(
+            bearer_token(&TestRequest::default().to_http_request()),
+            None
+        );
+    }
+}
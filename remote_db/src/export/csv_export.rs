@@ -0,0 +1,290 @@
+use std::io;
+
+use actix_web::{
+    http::header,
+    post,
+    web::{Bytes, Json},
+    HttpResponse,
+};
+use async_graphql::InputType;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use log::error;
+use sea_orm::{
+    prelude::DateTimeWithTimeZone, ActiveEnum, ColumnTrait, FromQueryResult, QueryFilter,
+    SelectModel, Selector,
+};
+
+use crate::{
+    auth::SessionData,
+    http_response_errors::ExportError,
+    seaorm_models::{
+        dispatch_note::{
+            self, DispatchNoteFields, DispatchNoteFilterValue, DispatchNoteFlattened,
+            DispatchNoteStatus,
+        },
+        dispatch_note_article::{
+            self, DispatchNoteArticleFields, DispatchNoteArticleFlattened, DispatchNoteArticleIds,
+        },
+        entry::{self, EntryFields, EntryFlattened},
+        graphql_schema::{has_data_group_access, FetchOptions},
+        history::begin_as_of,
+        QueryDatabase,
+    },
+    SeaOrmPool,
+};
+
+const ROWS_PER_CHUNK: usize = 500;
+const CHUNKS_IN_FLIGHT: usize = 4;
+
+type CsvChunk = Result<Bytes, io::Error>;
+
+pub trait CsvRecord {
+    fn header() -> &'static [&'static str];
+    fn record(&self) -> Vec<String>;
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl CsvRecord for EntryFlattened {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "date",
+            "weight",
+            "unit_short",
+            "buyer_name",
+            "buyer_address",
+            "buyer_contact",
+            "cell_name",
+            "culture_name",
+            "created_at",
+        ]
+    }
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.date.to_string(),
+            optional(&self.weight),
+            self.unit_short.clone(),
+            optional(&self.name_buyer),
+            optional(&self.address_buyer),
+            optional(&self.contact_buyer),
+            self.name_cell.clone(),
+            self.name_culture.clone(),
+            self.created_at.to_rfc3339(),
+        ]
+    }
+}
+
+impl CsvRecord for DispatchNoteFlattened {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "numerical_identifier",
+            "formatted_identifier",
+            "note_type",
+            "status",
+            "issuing_date",
+            "cancellation_reason",
+            "buyer_name",
+            "delivery_address",
+            "created_at",
+        ]
+    }
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            optional(&self.numerical_identifier),
+            optional(&self.formatted_identifier),
+            optional(&self.note_type),
+            ActiveEnum::to_value(&self.status),
+            optional(&self.issuing_date),
+            optional(&self.cancellation_reason),
+            optional(&self.name_buyer),
+            optional(&self.delivery_address.as_ref().or(self.address_buyer.as_ref())),
+            self.created_at.to_rfc3339(),
+        ]
+    }
+}
+
+impl CsvRecord for DispatchNoteArticleFlattened {
+    fn header() -> &'static [&'static str] {
+        &[
+            "id",
+            "id_dispatch_note",
+            "dispatch_note_numerical_identifier",
+            "dispatch_note_formatted_identifier",
+            "dispatch_note_issuing_date",
+            "buyer_name",
+            "article_name",
+            "quantity",
+            "unit_short",
+            "unit",
+            "created_at",
+        ]
+    }
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.id_dispatch_note.to_string(),
+            optional(&self.numerical_identifier_dispatch_note),
+            optional(&self.formatted_identifier_dispatch_note),
+            optional(&self.issuing_date_dispatch_note),
+            optional(&self.name_buyer),
+            self.name_article.clone(),
+            self.quantity.to_string(),
+            self.unit_short.clone(),
+            self.unit.clone(),
+            self.created_at.to_rfc3339(),
+        ]
+    }
+}
+
+/// Parses the request body the same way GraphQL parses the `options` argument
+fn parse_fetch_options<T: InputType>(options: serde_json::Value) -> Result<T, ExportError> {
+    let value = async_graphql::Value::from_json(options)
+        .map_err(|e| ExportError::InvalidOptions(e.to_string()))?;
+
+    T::parse(Some(value)).map_err(|e| {
+        ExportError::InvalidOptions(e.into_server_error(Default::default()).message)
+    })
+}
+
+async fn write_csv<M>(
+    db: SeaOrmPool,
+    selector: Selector<SelectModel<M>>,
+    as_of: Option<DateTimeWithTimeZone>,
+    sender: &mut mpsc::Sender<CsvChunk>,
+) -> anyhow::Result<()>
+where
+    M: FromQueryResult + CsvRecord + Send + Sync + 'static,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(M::header())?;
+
+    let transaction = begin_as_of(db.get_ref(), as_of).await?;
+    let mut rows = selector.stream(&transaction).await?;
+    let mut buffered_rows = 0;
+    while let Some(row) = rows.next().await {
+        writer.write_record(row?.record())?;
+        buffered_rows += 1;
+
+        if buffered_rows == ROWS_PER_CHUNK {
+            sender.send(Ok(take_chunk(&mut writer)?)).await?;
+            buffered_rows = 0;
+        }
+    }
+    drop(rows);
+    transaction.commit().await?;
+
+    sender.send(Ok(take_chunk(&mut writer)?)).await?;
+
+    Ok(())
+}
+
+/// Bytes written so far, the writer is replaced with an empty one
+fn take_chunk(writer: &mut csv::Writer<Vec<u8>>) -> anyhow::Result<Bytes> {
+    let writer = std::mem::replace(writer, csv::Writer::from_writer(Vec::new()));
+    Ok(Bytes::from(writer.into_inner().map_err(|e| e.into_error())?))
+}
+
+/// Rows are fetched from a database cursor and sent to the client in chunks,
+/// at most `CHUNKS_IN_FLIGHT` chunks are kept in memory at once
+fn csv_response<M>(
+    db: SeaOrmPool,
+    selector: Selector<SelectModel<M>>,
+    as_of: Option<DateTimeWithTimeZone>,
+    file_name: &str,
+) -> HttpResponse
+where
+    M: FromQueryResult + CsvRecord + Send + Sync + 'static,
+{
+    let (mut sender, receiver) = mpsc::channel::<CsvChunk>(CHUNKS_IN_FLIGHT);
+
+    actix_web::rt::spawn(async move {
+        if let Err(e) = write_csv(db, selector, as_of, &mut sender).await {
+            error!("CSV export failed: {:#?}", e);
+            let _ = sender.send(Err(io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        ))
+        .streaming(receiver)
+}
+
+macro_rules! csv_export_route {(
+        $(#[$attr:meta])*
+        $name:ident,
+        $entity:ty,
+        $options:ty,
+        $file_name:literal
+        $(, $filter:expr)*
+        ) => {
+        $(#[$attr])*
+        pub async fn $name(
+            session_data: SessionData,
+            db_pool: SeaOrmPool,
+            Json(options): Json<serde_json::Value>,
+        ) -> Result<HttpResponse, ExportError> {
+            let options: $options = parse_fetch_options(options)?;
+
+            if !has_data_group_access(&db_pool, &session_data, options.d_group).await? {
+                return Err(ExportError::Unauthorized);
+            }
+
+            let as_of = options.as_of;
+            let mut query = <$entity>::get_query();
+            query = <$entity>::add_id_and_data_group_filters(query, &options);
+            query = <$entity>::add_ordering(query, options.ordering);
+            query = <$entity>::add_filters(query, options.filters);
+            $(query = query.filter($filter);)*
+
+            Ok(csv_response(
+                db_pool,
+                query.into_model::<<$entity as QueryDatabase>::FetchModel>(),
+                as_of,
+                $file_name,
+            ))
+        }
+    };
+}
+
+csv_export_route![
+    #[post("/export/entries.csv")]
+    export_entries,
+    entry::Entity,
+    FetchOptions<EntryFields>,
+    "entries.csv"
+];
+
+csv_export_route![
+    #[post("/export/dispatch-notes.csv")]
+    export_dispatch_notes,
+    dispatch_note::Entity,
+    FetchOptions<DispatchNoteFields, Option<i32>, DispatchNoteFilterValue>,
+    "dispatch_notes.csv"
+];
+
+csv_export_route![
+    #[post("/export/dispatch-note-articles.csv")]
+    export_dispatch_note_articles,
+    dispatch_note_article::Entity,
+    FetchOptions<
+        DispatchNoteArticleFields,
+        Option<DispatchNoteArticleIds>,
+        DispatchNoteFilterValue,
+    >,
+    "dispatch_note_articles.csv",
+    // Only issued notes were actually dispatched
+    dispatch_note::Column::Status.eq(DispatchNoteStatus::Issued)
+];
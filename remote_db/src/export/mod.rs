@@ -0,0 +1,7 @@
+mod csv_export;
+mod pdf_export;
+mod xlsx_export;
+
+pub use csv_export::{export_dispatch_note_articles, export_dispatch_notes, export_entries};
+pub use pdf_export::export_dispatch_note_pdf;
+pub use xlsx_export::export_data_group_xlsx;
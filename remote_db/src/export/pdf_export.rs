@@ -0,0 +1,410 @@
+use actix_web::{get, http::header, web::Query, HttpResponse};
+use printpdf::{
+    image_crate, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point,
+};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder, TransactionTrait};
+use serde::Deserialize;
+
+use crate::{
+    auth::SessionData,
+    http_response_errors::ExportError,
+    seaorm_models::{
+        data_group,
+        dispatch_note::{self, DispatchNote, DispatchNoteStatus},
+        dispatch_note_article::{self, DispatchNoteArticleFlattened},
+        dispatch_note_type,
+        graphql_schema::has_data_group_access,
+        QueryDatabase,
+    },
+    user_models::mbe_group_print_header,
+    SeaOrmPool,
+};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const ROW_HEIGHT: f32 = 7.0;
+const LOGO_MAX_WIDTH: f32 = 40.0;
+const LOGO_MAX_HEIGHT: f32 = 25.0;
+const LAYER_NAME: &str = "Dispatch note";
+
+/// DejaVu Sans covers the latin, cyrillic and greek scripts of the names and addresses
+const REGULAR_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+const BOLD_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+/// Left edges of the article table columns: row number, article, quantity and unit
+const COLUMNS: [f32; 4] = [MARGIN, MARGIN + 12.0, MARGIN + 115.0, MARGIN + 145.0];
+
+#[derive(Debug, Deserialize)]
+pub struct DispatchNotePdfParams {
+    id: i32,
+    /// Language of the labels, `en` or `hr`, defaults to `en`
+    lang: Option<String>,
+}
+
+/// Fixed texts printed on the dispatch note
+struct Labels {
+    title: &'static str,
+    issuing_date: &'static str,
+    buyer: &'static str,
+    delivery_address: &'static str,
+    cancelled: &'static str,
+    draft: &'static str,
+    columns: [&'static str; 4],
+}
+
+const EN_LABELS: Labels = Labels {
+    title: "Dispatch note",
+    issuing_date: "Issuing date:",
+    buyer: "Buyer:",
+    delivery_address: "Delivery address:",
+    cancelled: "CANCELLED:",
+    draft: "DRAFT - NOT ISSUED",
+    columns: ["No.", "Article", "Quantity", "Unit"],
+};
+
+const HR_LABELS: Labels = Labels {
+    title: "Otpremnica",
+    issuing_date: "Datum izdavanja:",
+    buyer: "Kupac:",
+    delivery_address: "Adresa dostave:",
+    cancelled: "STORNIRANO:",
+    draft: "NACRT - NIJE IZDANO",
+    columns: ["Rb.", "Artikl", "Količina", "Jedinica"],
+};
+
+impl Labels {
+    /// Unknown languages fall back to english
+    fn for_language(lang: Option<&str>) -> &'static Labels {
+        match lang {
+            Some("hr") => &HR_LABELS,
+            _ => &EN_LABELS,
+        }
+    }
+}
+
+struct DispatchNoteDocument {
+    header: Option<mbe_group_print_header::Model>,
+    dispatch_note: DispatchNote,
+    note_type: Option<dispatch_note_type::Model>,
+    articles: Vec<DispatchNoteArticleFlattened>,
+    labels: &'static Labels,
+}
+
+/// Keeps track of the current page and vertical position while writing top to bottom
+struct PdfWriter {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Printed at the bottom of every page
+    stamp: Option<&'static str>,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str, stamp: Option<&'static str>) -> Result<Self, ExportError> {
+        let (document, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), LAYER_NAME);
+        let regular = document.add_external_font(REGULAR_FONT)?;
+        let bold = document.add_external_font(BOLD_FONT)?;
+        let layer = document.get_page(page).get_layer(layer);
+
+        let writer = Self {
+            document,
+            layer,
+            regular,
+            bold,
+            stamp,
+            y: PAGE_HEIGHT - MARGIN,
+        };
+        writer.stamp_page();
+
+        Ok(writer)
+    }
+
+    fn stamp_page(&self) {
+        if let Some(stamp) = self.stamp {
+            self.layer
+                .use_text(stamp, 14.0, Mm(MARGIN), Mm(MARGIN / 2.0), &self.bold);
+        }
+    }
+
+    /// Moves down by `height`, starting a new page when the bottom margin would be crossed
+    fn advance(&mut self, height: f32) -> bool {
+        if self.y - height < MARGIN {
+            let (page, layer) = self
+                .document
+                .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), LAYER_NAME);
+            self.layer = self.document.get_page(page).get_layer(layer);
+            self.stamp_page();
+            self.y = PAGE_HEIGHT - MARGIN - height;
+            true
+        } else {
+            self.y -= height;
+            false
+        }
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    fn rule(&self, y: f32) {
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Draws the logo in the top left corner, scaled down to fit the logo box
+    /// and returns the horizontal offset of the text next to it
+    fn logo(&self, logo: &[u8]) -> Result<f32, ExportError> {
+        let image = image_crate::load_from_memory(logo)?;
+        let dpi = f32::max(
+            image.width() as f32 * 25.4 / LOGO_MAX_WIDTH,
+            image.height() as f32 * 25.4 / LOGO_MAX_HEIGHT,
+        );
+        let width = image.width() as f32 * 25.4 / dpi;
+        let height = image.height() as f32 * 25.4 / dpi;
+
+        Image::from_dynamic_image(&image).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(MARGIN)),
+                translate_y: Some(Mm(self.y - height)),
+                dpi: Some(dpi),
+                ..Default::default()
+            },
+        );
+
+        Ok(MARGIN + width + 5.0)
+    }
+
+    fn table_header(&self, labels: &Labels) {
+        for (x, title) in COLUMNS.iter().zip(labels.columns) {
+            self.text(title, 10.0, *x, true);
+        }
+        self.rule(self.y - 2.0);
+    }
+}
+
+impl DispatchNoteDocument {
+    /// Uses the print template of the notes type when it has one
+    fn title(&self) -> String {
+        let identifier = self
+            .dispatch_note
+            .formatted_identifier
+            .as_deref()
+            .unwrap_or_default();
+
+        self.note_type
+            .as_ref()
+            .and_then(|note_type| note_type.print_title(identifier))
+            .unwrap_or_else(|| {
+                format!("{} {}", self.labels.title, identifier)
+                    .trim_end()
+                    .to_string()
+            })
+    }
+
+    fn file_name(&self) -> String {
+        match &self.dispatch_note.formatted_identifier {
+            Some(identifier) => format!("dispatch_note_{}.pdf", identifier.replace('/', "-")),
+            None => format!("dispatch_note_{}.pdf", self.dispatch_note.id),
+        }
+    }
+
+    fn write_header(&self, writer: &mut PdfWriter) -> Result<(), ExportError> {
+        let header = match &self.header {
+            Some(header) => header,
+            None => return Ok(()),
+        };
+
+        let top = writer.y;
+        let text_x = match &header.logo {
+            Some(logo) => writer.logo(logo)?,
+            None => MARGIN,
+        };
+
+        writer.y -= 5.0;
+        if let Some(company_name) = &header.company_name {
+            writer.text(company_name, 14.0, text_x, true);
+            writer.y -= 6.0;
+        }
+        if let Some(address) = &header.address {
+            for line in address.lines() {
+                writer.text(line, 10.0, text_x, false);
+                writer.y -= 5.0;
+            }
+        }
+
+        writer.y = f32::min(writer.y, top - LOGO_MAX_HEIGHT);
+        writer.rule(writer.y);
+        writer.y -= 5.0;
+
+        Ok(())
+    }
+
+    /// Renders the dispatch note on A4 pages with the embedded DejaVu Sans font,
+    /// drafts are stamped on every page as they aren't valid dispatch notes yet
+    fn render(self) -> Result<Vec<u8>, ExportError> {
+        let stamp = match self.dispatch_note.status {
+            DispatchNoteStatus::Draft => Some(self.labels.draft),
+            DispatchNoteStatus::Issued | DispatchNoteStatus::Cancelled => None,
+        };
+        let mut writer = PdfWriter::new(&self.title(), stamp)?;
+
+        self.write_header(&mut writer)?;
+
+        writer.advance(8.0);
+        writer.text(&self.title(), 16.0, MARGIN, true);
+        if let Some(issuing_date) = self.dispatch_note.issuing_date {
+            writer.advance(ROW_HEIGHT);
+            writer.text(
+                &format!(
+                    "{} {}",
+                    self.labels.issuing_date,
+                    issuing_date.format("%d.%m.%Y.")
+                ),
+                10.0,
+                MARGIN,
+                false,
+            );
+        }
+        if let Some(buyer) = &self.dispatch_note.buyer {
+            writer.advance(ROW_HEIGHT);
+            writer.text(
+                &format!(
+                    "{} {}",
+                    self.labels.buyer,
+                    buyer.name.as_deref().unwrap_or_default()
+                ),
+                10.0,
+                MARGIN,
+                false,
+            );
+        }
+        if let Some(address) = self.dispatch_note.shipping_address() {
+            writer.advance(ROW_HEIGHT);
+            writer.text(self.labels.delivery_address, 10.0, MARGIN, false);
+            for line in address.lines() {
+                writer.advance(5.0);
+                writer.text(line, 10.0, MARGIN, false);
+            }
+        }
+        if let Some(reason) = &self.dispatch_note.cancellation_reason {
+            writer.advance(ROW_HEIGHT);
+            writer.text(
+                &format!("{} {}", self.labels.cancelled, reason),
+                10.0,
+                MARGIN,
+                true,
+            );
+        }
+
+        writer.advance(ROW_HEIGHT * 2.0);
+        writer.table_header(self.labels);
+
+        for (index, article) in self.articles.iter().enumerate() {
+            if writer.advance(ROW_HEIGHT) {
+                writer.table_header(self.labels);
+                writer.advance(ROW_HEIGHT);
+            }
+            writer.text(&format!("{}.", index + 1), 10.0, COLUMNS[0], false);
+            writer.text(&article.name_article, 10.0, COLUMNS[1], false);
+            writer.text(&article.quantity.to_string(), 10.0, COLUMNS[2], false);
+            writer.text(&article.unit_short, 10.0, COLUMNS[3], false);
+        }
+
+        Ok(writer.document.save_to_bytes()?)
+    }
+}
+
+#[get("/export/dispatch-note.pdf")]
+pub async fn export_dispatch_note_pdf(
+    session_data: SessionData,
+    db_pool: SeaOrmPool,
+    Query(params): Query<DispatchNotePdfParams>,
+) -> Result<HttpResponse, ExportError> {
+    let transaction = db_pool.begin().await?;
+
+    let dispatch_note = dispatch_note::Entity::find_by_id(params.id)
+        .filter(dispatch_note::Column::DeletedAt.is_null())
+        .one(&transaction)
+        .await?
+        .ok_or(ExportError::NotFound)?;
+
+    if !has_data_group_access(&db_pool, &session_data, dispatch_note.d_group).await? {
+        return Err(ExportError::Unauthorized);
+    }
+
+    let data_group = data_group::Entity::find_by_id(dispatch_note.d_group)
+        .one(&transaction)
+        .await?
+        .ok_or(ExportError::NotFound)?;
+
+    let print_header = mbe_group_print_header::Entity::find_by_id(data_group.id_mbe_group)
+        .one(&transaction)
+        .await?;
+
+    let note_type = dispatch_note
+        .find_related(dispatch_note_type::Entity)
+        .one(&transaction)
+        .await?;
+
+    let dispatch_note = dispatch_note::Entity::with_buyer(&transaction, dispatch_note).await?;
+
+    let articles = dispatch_note_article::Entity::get_query()
+        .filter(dispatch_note_article::Column::IdDispatchNote.eq(dispatch_note.id))
+        .order_by_asc(dispatch_note_article::Column::Id)
+        .into_model::<DispatchNoteArticleFlattened>()
+        .all(&transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    let document = DispatchNoteDocument {
+        header: print_header,
+        dispatch_note,
+        note_type,
+        articles,
+        labels: Labels::for_language(params.lang.as_deref()),
+    };
+    let file_name = document.file_name();
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"{}\"", file_name),
+        ))
+        .body(document.render()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_text_outside_of_windows_1252() {
+        let writer = PdfWriter::new("Otpremnica", Some(HR_LABELS.draft)).unwrap();
+        writer.text("Količina Ђорђе Ωmega", 10.0, MARGIN, false);
+        writer.text("Šećer", 10.0, MARGIN, true);
+
+        assert!(!writer.document.save_to_bytes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_languages_get_english_labels() {
+        assert_eq!(Labels::for_language(Some("hr")).title, "Otpremnica");
+        assert_eq!(Labels::for_language(Some("de")).title, "Dispatch note");
+        assert_eq!(Labels::for_language(None).title, "Dispatch note");
+    }
+}
@@ -0,0 +1,361 @@
+use actix_web::{get, http::header, web::Query, HttpResponse};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use sea_orm::{
+    entity::prelude::{Date, DateTimeWithTimeZone},
+    ActiveEnum, ColumnTrait, QueryFilter, TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::{
+    auth::SessionData,
+    http_response_errors::ExportError,
+    seaorm_models::{
+        article, buyer, cell,
+        cell_culture_pair::{self, CellCulturePairFlattened},
+        culture,
+        dispatch_note::{self, DispatchNoteFlattened, DispatchNoteStatus},
+        dispatch_note_article::{self, DispatchNoteArticleFlattened},
+        entry::{self, EntryFlattened},
+        graphql_schema::has_data_group_access,
+        GetEntityDataGroupColumnTrait, QueryDatabase,
+    },
+    SeaOrmPool,
+};
+
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+#[derive(Debug, Deserialize)]
+pub struct DataGroupExportParams {
+    d_group: i32,
+}
+
+pub enum XlsxCell {
+    Text(Option<String>),
+    Number(Option<f64>),
+    Date(Option<Date>),
+    DateTime(DateTimeWithTimeZone),
+}
+
+pub trait XlsxSheet {
+    const SHEET_NAME: &'static str;
+
+    fn header() -> &'static [&'static str];
+    fn cells(self) -> Vec<XlsxCell>;
+}
+
+impl XlsxSheet for cell::Model {
+    const SHEET_NAME: &'static str = "Cells";
+
+    fn header() -> &'static [&'static str] {
+        &["Id", "Name", "Description", "Created at"]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Text(Some(self.name)),
+            XlsxCell::Text(self.description),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for culture::Model {
+    const SHEET_NAME: &'static str = "Cultures";
+
+    fn header() -> &'static [&'static str] {
+        &["Id", "Name", "Description", "Created at"]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Text(Some(self.name)),
+            XlsxCell::Text(self.description),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for CellCulturePairFlattened {
+    const SHEET_NAME: &'static str = "Cell culture pairs";
+
+    fn header() -> &'static [&'static str] {
+        &["Id", "Cell", "Culture", "Created at"]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Text(Some(self.name_cell)),
+            XlsxCell::Text(Some(self.name_culture)),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for buyer::Model {
+    const SHEET_NAME: &'static str = "Buyers";
+
+    fn header() -> &'static [&'static str] {
+        &["Id", "Name", "Address", "Contact", "Created at"]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Text(self.name),
+            XlsxCell::Text(self.address),
+            XlsxCell::Text(self.contact),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for EntryFlattened {
+    const SHEET_NAME: &'static str = "Entries";
+
+    fn header() -> &'static [&'static str] {
+        &[
+            "Id",
+            "Date",
+            "Weight",
+            "Unit",
+            "Buyer",
+            "Cell",
+            "Culture",
+            "Created at",
+        ]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Date(Some(self.date)),
+            XlsxCell::Number(self.weight),
+            XlsxCell::Text(Some(self.unit_short)),
+            XlsxCell::Text(self.name_buyer),
+            XlsxCell::Text(Some(self.name_cell)),
+            XlsxCell::Text(Some(self.name_culture)),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for article::Model {
+    const SHEET_NAME: &'static str = "Articles";
+
+    fn header() -> &'static [&'static str] {
+        &["Id", "Name", "Description", "Created at"]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Text(Some(self.name)),
+            XlsxCell::Text(self.description),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for DispatchNoteFlattened {
+    const SHEET_NAME: &'static str = "Dispatch notes";
+
+    fn header() -> &'static [&'static str] {
+        &[
+            "Id",
+            "Numerical identifier",
+            "Identifier",
+            "Note type",
+            "Status",
+            "Issuing date",
+            "Cancellation reason",
+            "Buyer",
+            "Delivery address",
+            "Created at",
+        ]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Number(self.numerical_identifier.map(f64::from)),
+            XlsxCell::Text(self.formatted_identifier),
+            XlsxCell::Number(self.note_type.map(f64::from)),
+            XlsxCell::Text(Some(self.status.to_value())),
+            XlsxCell::Date(self.issuing_date),
+            XlsxCell::Text(self.cancellation_reason),
+            XlsxCell::Text(self.name_buyer),
+            XlsxCell::Text(self.delivery_address.or(self.address_buyer)),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+impl XlsxSheet for DispatchNoteArticleFlattened {
+    const SHEET_NAME: &'static str = "Dispatched articles";
+
+    fn header() -> &'static [&'static str] {
+        &[
+            "Id",
+            "Dispatch note",
+            "Dispatch note identifier",
+            "Issuing date",
+            "Buyer",
+            "Article",
+            "Quantity",
+            "Unit",
+            "Created at",
+        ]
+    }
+
+    fn cells(self) -> Vec<XlsxCell> {
+        vec![
+            XlsxCell::Number(Some(self.id.into())),
+            XlsxCell::Number(self.numerical_identifier_dispatch_note.map(f64::from)),
+            XlsxCell::Text(self.formatted_identifier_dispatch_note),
+            XlsxCell::Date(self.issuing_date_dispatch_note),
+            XlsxCell::Text(self.name_buyer),
+            XlsxCell::Text(Some(self.name_article)),
+            XlsxCell::Number(Some(self.quantity)),
+            XlsxCell::Text(Some(self.unit_short)),
+            XlsxCell::DateTime(self.created_at),
+        ]
+    }
+}
+
+struct SheetFormats {
+    header: Format,
+    date: Format,
+    date_time: Format,
+}
+
+impl Default for SheetFormats {
+    fn default() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            date: Format::new().set_num_format("yyyy-mm-dd"),
+            date_time: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+        }
+    }
+}
+
+fn write_sheet<T: XlsxSheet>(
+    workbook: &mut Workbook,
+    formats: &SheetFormats,
+    rows: Vec<T>,
+) -> Result<(), XlsxError> {
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(T::SHEET_NAME)?;
+
+    for (col, title) in T::header().iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *title, &formats.header)?;
+    }
+
+    for (row, record) in rows.into_iter().enumerate() {
+        let row = row as u32 + 1;
+        for (col, cell) in record.cells().into_iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                XlsxCell::Text(Some(text)) => {
+                    worksheet.write_string(row, col, text)?;
+                }
+                XlsxCell::Number(Some(number)) => {
+                    worksheet.write_number(row, col, number)?;
+                }
+                XlsxCell::Date(Some(date)) => {
+                    worksheet.write_datetime_with_format(row, col, date, &formats.date)?;
+                }
+                XlsxCell::DateTime(date_time) => {
+                    worksheet.write_datetime_with_format(
+                        row,
+                        col,
+                        date_time.naive_local(),
+                        &formats.date_time,
+                    )?;
+                }
+                XlsxCell::Text(None) | XlsxCell::Number(None) | XlsxCell::Date(None) => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! add_data_group_sheet {
+    (
+        $workbook:expr,
+        $formats:expr,
+        $transaction:expr,
+        $entity:ty,
+        $d_group:expr
+        $(, $filter:expr)*
+    ) => {
+        write_sheet(
+            $workbook,
+            $formats,
+            <$entity>::get_query()
+                .filter(<$entity>::get_data_group_column().eq($d_group))
+                $(.filter($filter))*
+                .into_model::<<$entity as QueryDatabase>::FetchModel>()
+                .all($transaction)
+                .await?,
+        )?;
+    };
+}
+
+#[get("/export/data-group.xlsx")]
+pub async fn export_data_group_xlsx(
+    session_data: SessionData,
+    db_pool: SeaOrmPool,
+    Query(params): Query<DataGroupExportParams>,
+) -> Result<HttpResponse, ExportError> {
+    if !has_data_group_access(&db_pool, &session_data, params.d_group).await? {
+        return Err(ExportError::Unauthorized);
+    }
+
+    let mut workbook = Workbook::new();
+    let formats = SheetFormats::default();
+
+    let transaction = db_pool.begin().await?;
+
+    add_data_group_sheet!(&mut workbook, &formats, &transaction, cell::Entity, params.d_group);
+    add_data_group_sheet!(&mut workbook, &formats, &transaction, culture::Entity, params.d_group);
+    add_data_group_sheet!(
+        &mut workbook,
+        &formats,
+        &transaction,
+        cell_culture_pair::Entity,
+        params.d_group
+    );
+    add_data_group_sheet!(&mut workbook, &formats, &transaction, buyer::Entity, params.d_group);
+    add_data_group_sheet!(&mut workbook, &formats, &transaction, entry::Entity, params.d_group);
+    add_data_group_sheet!(&mut workbook, &formats, &transaction, article::Entity, params.d_group);
+    add_data_group_sheet!(
+        &mut workbook,
+        &formats,
+        &transaction,
+        dispatch_note::Entity,
+        params.d_group
+    );
+    add_data_group_sheet!(
+        &mut workbook,
+        &formats,
+        &transaction,
+        dispatch_note_article::Entity,
+        params.d_group,
+        // Only issued notes were actually dispatched
+        dispatch_note::Column::Status.eq(DispatchNoteStatus::Issued)
+    );
+
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(XLSX_CONTENT_TYPE)
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"data_group_{}.xlsx\"", params.d_group),
+        ))
+        .body(workbook.save_to_buffer()?))
+}
@@ -8,6 +8,7 @@ use reqwest::StatusCode;
 use serde::Serialize;
 use thiserror::Error;
 
+#[allow(dead_code)]
 #[derive(Error, Debug)]
 pub enum AuthError {
     #[error(transparent)]
@@ -43,6 +44,16 @@ pub enum AuthError {
     Unauthorized,
     #[error("Invalid temporary code")]
     InvalidTempCode,
+    #[error("Unknown login provider")]
+    UnknownProvider,
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+    #[error("Too many failed login attempts")]
+    AccountLocked,
+    #[error("Password is too short")]
+    WeakPassword,
+    #[error("Password hashing failed")]
+    PasswordHashError,
 }
 
 #[derive(Serialize, Debug)]
@@ -57,6 +68,10 @@ impl ResponseError for AuthError {
             AuthError::MissingStateOrAuthCode => StatusCode::BAD_REQUEST,
             AuthError::UserNotFound => StatusCode::BAD_REQUEST,
             AuthError::InvalidSession => StatusCode::BAD_REQUEST,
+            AuthError::UnknownProvider => StatusCode::NOT_FOUND,
+            AuthError::WeakPassword => StatusCode::BAD_REQUEST,
+            AuthError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            AuthError::AccountLocked => StatusCode::TOO_MANY_REQUESTS,
             AuthError::SessionGetError(..) | AuthError::Unauthorized => StatusCode::UNAUTHORIZED,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -80,6 +95,11 @@ impl ResponseError for AuthError {
                 AuthError::MissingEmailInResponse => {
                     "Login service didn't return an e-mail address."
                 }
+                AuthError::UnknownProvider => "Unknown login provider.",
+                AuthError::PasswordHashError => "Password hashing failed.",
+                AuthError::InvalidCredentials => "Invalid e-mail or password.",
+                AuthError::AccountLocked => "Too many failed login attempts, try again later.",
+                AuthError::WeakPassword => "Password is too short.",
             },
         })
     }
@@ -112,8 +132,16 @@ pub enum AuthCallbackError {
     MissingStateOrAuthCode,
     #[error("Missing email in response")]
     MissingEmailInResponse,
-    #[error("User with supplied email not found.")]
-    UserNotFound,
+    #[error("Registration is not allowed for the supplied email.")]
+    RegistrationNotAllowed,
+    #[error(transparent)]
+    ClaimsVerificationError(#[from] openidconnect::ClaimsVerificationError),
+    #[error("Missing id token in response")]
+    MissingIdToken,
+    #[error("Email is not verified")]
+    EmailNotVerified,
+    #[error("Unknown login provider")]
+    UnknownProvider,
 }
 
 impl ResponseError for AuthCallbackError {
@@ -141,10 +169,60 @@ impl ResponseError for AuthCallbackError {
                         AuthCallbackError::RandError(..) => "key_gen_failed",
                         AuthCallbackError::MissingStateOrAuthCode
                         | AuthCallbackError::MissingEmailInResponse => "bad_request",
-                        AuthCallbackError::UserNotFound => "user_not_found",
+                        AuthCallbackError::RegistrationNotAllowed => "registration_not_allowed",
+                        AuthCallbackError::ClaimsVerificationError(..)
+                        | AuthCallbackError::MissingIdToken => "invalid_id_token",
+                        AuthCallbackError::EmailNotVerified => "email_not_verified",
+                        AuthCallbackError::UnknownProvider => "unknown_provider",
                     }
                 ),
             ))
             .finish()
     }
 }
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error(transparent)]
+    SeaOrmDbError(#[from] sea_orm::DbErr),
+    #[error(transparent)]
+    XlsxError(#[from] rust_xlsxwriter::XlsxError),
+    #[error(transparent)]
+    PdfError(#[from] printpdf::Error),
+    #[error(transparent)]
+    ImageError(#[from] printpdf::image_crate::ImageError),
+    #[error("Invalid export options: {0}")]
+    InvalidOptions(String),
+    #[error("Unauthorized access.")]
+    Unauthorized,
+    #[error("Requested entity not found.")]
+    NotFound,
+}
+
+impl ResponseError for ExportError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ExportError::InvalidOptions(..) => StatusCode::BAD_REQUEST,
+            ExportError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ExportError::NotFound => StatusCode::NOT_FOUND,
+            ExportError::SeaOrmDbError(..)
+            | ExportError::XlsxError(..)
+            | ExportError::PdfError(..)
+            | ExportError::ImageError(..) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+    fn error_response(&self) -> HttpResponse {
+        error!("{:#?}", self);
+        HttpResponse::build(self.status_code()).json(OAuthErrorResponse {
+            error: match self {
+                ExportError::SeaOrmDbError(..) => "Db error.",
+                ExportError::XlsxError(..) => "Workbook generation failed.",
+                ExportError::PdfError(..) => "Pdf generation failed.",
+                ExportError::ImageError(..) => "Logo could not be decoded.",
+                ExportError::InvalidOptions(..) => "Bad request.",
+                ExportError::Unauthorized => "Unauthorized.",
+                ExportError::NotFound => "Not found.",
+            },
+        })
+    }
+}
@@ -14,10 +14,10 @@ use actix_web::{
     middleware::Logger,
     post,
     web::{self, Data},
-    App, HttpResponse, HttpServer,
+    App, HttpRequest, HttpResponse, HttpServer,
 };
-use async_graphql::{http::GraphiQLSource, EmptySubscription, Schema};
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{http::GraphiQLSource, Schema};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use auth::{
     login_callback_github, login_callback_google,
     login_callback_microsoft, login_github, login_google, login_microsoft, logout,
@@ -25,23 +25,42 @@ use auth::{
     OAuthClientMicrosoft,
 };
 use dotenvy::dotenv;
+use export::{
+    export_data_group_xlsx, export_dispatch_note_articles, export_dispatch_note_pdf,
+    export_dispatch_notes, export_entries,
+};
 use http_response_errors::AuthError;
+use oidc::{login_callback_oidc, login_oidc, OidcProviders};
+use password_auth::{dummy_password_hash, login_password, reset_password};
 
-use redis_connection_manager::create_redis_connection_manager;
+use redis_connection_manager::{create_redis_connection_manager, RedisConnectionManagerExt};
+use registration::RegistrationPolicy;
 use sea_orm::{
     ColumnTrait, ConnectOptions, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
 };
-use seaorm_models::graphql_schema::{MutationRoot, QueryRoot};
+use seaorm_models::{
+    data_group_events::DataGroupEvents,
+    graphql_schema::{MutationRoot, QueryRoot, SubscriptionRoot},
+    trash::spawn_purge_task,
+    weight_type::WeightTypeCache,
+    PageSizeLimits,
+};
 
 use crate::auth::SessionData;
 
 mod auth;
+mod export;
 mod http_response_errors;
+mod oidc;
+mod password_auth;
 mod redis_connection_manager;
+mod registration;
 mod seaorm_models;
+#[cfg(test)]
+mod test_database;
 mod user_models;
 
-pub type GQLSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+pub type GQLSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
 pub type SeaOrmPool = Data<DatabaseConnection>;
 
 const MONTH: i64 = 60 * 60 * 24 * 30;
@@ -50,7 +69,12 @@ const MONTH: i64 = 60 * 60 * 24 * 30;
 async fn graphql_playground(_session: Session) -> actix_web::Result<HttpResponse> {
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(GraphiQLSource::build().endpoint("/graphiql").finish()))
+        .body(
+            GraphiQLSource::build()
+                .endpoint("/graphiql")
+                .subscription_endpoint("/graphiql/ws")
+                .finish(),
+        ))
 }
 
 #[post("/graphiql")]
@@ -62,10 +86,26 @@ async fn index(
     session_data: SessionData,
 ) -> GraphQLResponse {
     let req = req.into_inner();
-    let req = req.data(session_data);
+    let req = req.data(session_data).data(WeightTypeCache::default());
     schema.execute(req).await.into()
 }
 
+/// graphql-ws endpoint for subscriptions, the session is checked when the socket is opened
+/// and again by the subscriptions before they send an event
+#[get("/graphiql/ws")]
+async fn index_ws(
+    schema: web::Data<GQLSchema>,
+    req: HttpRequest,
+    payload: web::Payload,
+    session_data: SessionData,
+) -> actix_web::Result<HttpResponse> {
+    let mut data = async_graphql::Data::default();
+    data.insert(session_data);
+    GraphQLSubscription::new(Schema::clone(&*schema))
+        .with_data(data)
+        .start(&req, payload)
+}
+
 #[get("/schema")]
 async fn get_schema(schema: web::Data<GQLSchema>) -> Result<String, AuthError> {
     Ok(schema.sdl())
@@ -92,42 +132,58 @@ async fn me(
 }
 
 #[cfg(debug_assertions)]
-fn build_schema(sea_orm_pool: SeaOrmPool) -> GQLSchema {
+fn build_schema(
+    sea_orm_pool: SeaOrmPool,
+    redis_connection_manager: RedisConnectionManagerExt,
+    data_group_events: DataGroupEvents,
+) -> GQLSchema {
     Schema::build(
         QueryRoot::default(),
         MutationRoot::default(),
-        EmptySubscription,
+        SubscriptionRoot::default(),
     )
     .data(sea_orm_pool)
+    .data(redis_connection_manager)
+    .data(data_group_events)
     .extension(async_graphql::extensions::Logger)
     .finish()
 }
 
 #[cfg(not(debug_assertions))]
-fn build_schema(sea_orm_pool: SeaOrmPool) -> GQLSchema {
+fn build_schema(
+    sea_orm_pool: SeaOrmPool,
+    redis_connection_manager: RedisConnectionManagerExt,
+    data_group_events: DataGroupEvents,
+) -> GQLSchema {
     Schema::build(
         QueryRoot::default(),
         MutationRoot::default(),
-        EmptySubscription,
+        SubscriptionRoot::default(),
     )
     .data(sea_orm_pool)
+    .data(redis_connection_manager)
+    .data(data_group_events)
     .extension(async_graphql::extensions::Logger)
     .disable_introspection()
     .finish()
 }
 
+/// Built-in login providers are optional, once the client id of one is set
+/// the rest of its variables must be set too
 macro_rules! oauth_client_env_check {
     ($name:literal) => {
-        env::var(concat!("OAUTH_CLIENT_ID_", $name)).expect(concat!(
-            "OAUTH_CLIENT_ID_",
-            $name,
-            " env variable to be present"
-        ));
-        env::var(concat!("OAUTH_CLIENT_SECRET_", $name)).expect(concat!(
-            "OAUTH_CLIENT_SECRET_",
-            $name,
-            " env variable to be present"
-        ));
+        if env::var(concat!("OAUTH_CLIENT_ID_", $name)).is_ok() {
+            for key in [
+                concat!("OAUTH_CLIENT_SECRET_", $name),
+                concat!($name, "_AUTH_ENDPOINT"),
+                concat!($name, "_TOKEN_ENDPOINT"),
+                concat!($name, "_USER_INFO_ENDPOINT"),
+                concat!($name, "_REDIRECT_URL"),
+            ] {
+                env::var(key)
+                    .unwrap_or_else(|_| panic!("{} environment variable must be set", key));
+            }
+        }
     };
 }
 
@@ -151,33 +207,17 @@ fn load_env() {
     // .env
     load_env_var!("DATABASE_URL");
     load_env_var!("REDIS_CONNECTION");
+    load_env_var!("SESSION_SECRET_KEY");
+
+    // .env + .env.endpoints + .env.dev | .env.prod
     oauth_client_env_check!("GOOGLE");
     oauth_client_env_check!("MICROSOFT");
     oauth_client_env_check!("GITHUB");
     // oauth_client_env_check!("FACEBOOK");
-    load_env_var!("SESSION_SECRET_KEY");
-
-    // .env.endpoints
-    load_env_var!("GOOGLE_AUTH_ENDPOINT");
-    load_env_var!("GOOGLE_TOKEN_ENDPOINT");
-    load_env_var!("GOOGLE_USER_INFO_ENDPOINT");
-    load_env_var!("MICROSOFT_AUTH_ENDPOINT");
-    load_env_var!("MICROSOFT_TOKEN_ENDPOINT");
-    load_env_var!("MICROSOFT_USER_INFO_ENDPOINT");
-    load_env_var!("GITHUB_AUTH_ENDPOINT");
-    load_env_var!("GITHUB_TOKEN_ENDPOINT");
-    load_env_var!("GITHUB_USER_INFO_ENDPOINT");
-    // load_env_var!("FACEBOOK_AUTH_ENDPOINT");
-    // load_env_var!("FACEBOOK_TOKEN_ENDPOINT");
-    // load_env_var!("FACEBOOK_USER_INFO_ENDPOINT");
 
     // .env.dev | .env.prod
     load_env_var!("LOGIN_URL");
     load_env_var!("CALLBACK_URL");
-    load_env_var!("GOOGLE_REDIRECT_URL");
-    load_env_var!("MICROSOFT_REDIRECT_URL");
-    load_env_var!("GITHUB_REDIRECT_URL");
-    // load_env_var!("FACEBOOK_REDIRECT_URL");
 }
 
 #[actix_web::main]
@@ -186,6 +226,11 @@ async fn main() -> std::io::Result<()> {
 
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    // Invalid limits should fail on startup instead of on the first query
+    PageSizeLimits::get();
+    // Hashed upfront so the first login of an unknown email isn't slower than the rest
+    dummy_password_hash();
+
     let mut seaorm_connection_options =
         ConnectOptions::new(env::var("DATABASE_URL").expect("DATABASE_URL must be set"));
     seaorm_connection_options.max_connections(5);
@@ -196,15 +241,24 @@ async fn main() -> std::io::Result<()> {
             .expect("Database connection"),
     );
 
-    let schema = build_schema(sea_orm_pool.clone());
-
     // TODO: do status checks (redis::cmd("PING")...)
     let redis_csrf_cache = create_redis_connection_manager().await;
 
-    let oauth_client_google = OAuthClientGoogle::new();
-    let oauth_client_microsoft = OAuthClientMicrosoft::new();
-    let oauth_client_github = OAuthClientGithub::new();
-    // let oauth_client_facebook = OAuthClientFacebook::new();
+    let data_group_events = DataGroupEvents::default();
+    data_group_events.spawn_listener();
+
+    let schema = build_schema(
+        sea_orm_pool.clone(),
+        redis_csrf_cache.clone(),
+        data_group_events,
+    );
+
+    spawn_purge_task(sea_orm_pool.clone());
+
+    let oauth_client_google = OAuthClientGoogle::from_env();
+    let oauth_client_microsoft = OAuthClientMicrosoft::from_env();
+    let oauth_client_github = OAuthClientGithub::from_env();
+    // let oauth_client_facebook = OAuthClientFacebook::from_env();
 
     let session_secret_key = Key::from(
         env::var("SESSION_SECRET_KEY")
@@ -218,6 +272,8 @@ async fn main() -> std::io::Result<()> {
         .expect("Valid redis connection");
 
     let global_reqwest_client = GlobalReqwestClient::new();
+    let registration_policy = RegistrationPolicy::from_env();
+    let oidc_providers = Data::new(OidcProviders::from_env().await);
 
     HttpServer::new(move || {
         App::new()
@@ -274,22 +330,45 @@ async fn main() -> std::io::Result<()> {
             .app_data(Data::new(schema.clone()))
             .app_data(sea_orm_pool.clone())
             .app_data(redis_csrf_cache.clone())
-            .app_data(oauth_client_google.clone())
-            .app_data(oauth_client_microsoft.clone())
-            .app_data(oauth_client_github.clone())
-            // .app_data(oauth_client_facebook.clone())
             .app_data(global_reqwest_client.clone())
-            .service(login_google)
-            .service(login_callback_google)
-            .service(login_microsoft)
-            .service(login_callback_microsoft)
-            .service(login_github)
-            .service(login_callback_github)
-            // .service(login_facebook)
-            // .service(login_callback_facebook)
+            .app_data(registration_policy.clone())
+            .app_data(oidc_providers.clone())
+            .configure(|cfg| {
+                // Routes of the built-in providers are only registered when they are configured
+                if let Some(client) = &oauth_client_google {
+                    cfg.app_data(client.clone())
+                        .service(login_google)
+                        .service(login_callback_google);
+                }
+                if let Some(client) = &oauth_client_microsoft {
+                    cfg.app_data(client.clone())
+                        .service(login_microsoft)
+                        .service(login_callback_microsoft);
+                }
+                if let Some(client) = &oauth_client_github {
+                    cfg.app_data(client.clone())
+                        .service(login_github)
+                        .service(login_callback_github);
+                }
+                // if let Some(client) = &oauth_client_facebook {
+                //     cfg.app_data(client.clone())
+                //         .service(login_facebook)
+                //         .service(login_callback_facebook);
+                // }
+            })
+            .service(login_oidc)
+            .service(login_callback_oidc)
+            .service(login_password)
+            .service(reset_password)
             .service(graphql_playground)
             .service(index)
+            .service(index_ws)
             .service(get_schema)
+            .service(export_entries)
+            .service(export_dispatch_notes)
+            .service(export_dispatch_note_articles)
+            .service(export_data_group_xlsx)
+            .service(export_dispatch_note_pdf)
             .service(me)
             .service(logout)
             .service(manual_auth)
@@ -0,0 +1,546 @@
+use std::{borrow::Cow, collections::HashMap, env};
+
+use actix_session::Session;
+use actix_web::{
+    get,
+    http::header::LOCATION,
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use log::{error, info};
+use openidconnect::{
+    core::{CoreClient, CoreProviderMetadata, CoreResponseType},
+    reqwest::async_http_client,
+    AuthenticationFlow, AuthorizationCode, ClientId, ClientSecret, CsrfToken, IssuerUrl, Nonce,
+    PkceCodeChallenge, PkceCodeVerifier, Scope, TokenResponse,
+};
+use redis::Cmd;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{
+        complete_login, gen_redirect_url, invitation_cache_key, AuthCallbackParams, Platform,
+        RedirectUriParams, CSRF_CACHE_EXPIRY,
+    },
+    http_response_errors::{AuthCallbackError, AuthError},
+    redis_connection_manager::RedisConnectionManagerExt,
+    registration::RegistrationPolicy,
+    SeaOrmPool,
+};
+
+#[derive(Clone, Debug)]
+pub struct OidcProvider {
+    client: CoreClient,
+    redirect_url: String,
+    /// Accept emails from id tokens without the `email_verified` claim
+    trust_email: bool,
+}
+
+/// OpenID Connect providers by name, used in the `/login-oidc/{provider}` route
+#[derive(Clone, Debug, Default)]
+pub struct OidcProviders(HashMap<String, OidcProvider>);
+
+fn provider_env_var(name: &str, suffix: &str) -> String {
+    let key = format!("OIDC_{}_{}", name.to_uppercase().replace('-', "_"), suffix);
+    env::var(&key).unwrap_or_else(|_| panic!("{} environment variable must be set", key))
+}
+
+/// Optional `OIDC_{NAME}_TRUST_EMAIL` flag, off unless set to `true`
+fn provider_trusts_email(name: &str) -> bool {
+    let key = format!("OIDC_{}_TRUST_EMAIL", name.to_uppercase().replace('-', "_"));
+    match env::var(&key) {
+        Ok(trust_email) => trust_email
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be true or false", key)),
+        Err(_) => false,
+    }
+}
+
+impl OidcProviders {
+    /// Reads the comma separated provider names from `OIDC_PROVIDERS`, every provider is configured
+    /// with `OIDC_{NAME}_ISSUER`, `OIDC_{NAME}_CLIENT_ID`, `OIDC_{NAME}_CLIENT_SECRET`
+    /// and `OIDC_{NAME}_REDIRECT_URL`. Endpoints and signing keys are discovered from the issuer.
+    /// `OIDC_{NAME}_TRUST_EMAIL=true` accepts emails from providers that don't send `email_verified`
+    pub async fn from_env() -> Self {
+        let mut providers = HashMap::new();
+
+        for name in env::var("OIDC_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+        {
+            let provider = OidcProvider::discover(
+                provider_env_var(&name, "ISSUER"),
+                provider_env_var(&name, "CLIENT_ID"),
+                provider_env_var(&name, "CLIENT_SECRET"),
+                provider_env_var(&name, "REDIRECT_URL"),
+                provider_trusts_email(&name),
+            )
+            .await
+            .unwrap_or_else(|e| panic!("OpenID Connect discovery for {} failed: {}", name, e));
+
+            info!("Configured OpenID Connect provider {}", name);
+            providers.insert(name, provider);
+        }
+
+        OidcProviders(providers)
+    }
+
+    fn get(&self, name: &str) -> Option<&OidcProvider> {
+        self.0.get(name)
+    }
+}
+
+/// Cached under the csrf state until the provider redirects back
+#[derive(Serialize, Deserialize)]
+struct OidcLoginState {
+    pkce_verifier: String,
+    nonce: String,
+}
+
+impl OidcProvider {
+    /// Discovers the endpoints and signing keys of the provider from its issuer
+    pub async fn discover(
+        issuer: String,
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+        trust_email: bool,
+    ) -> anyhow::Result<Self> {
+        let provider_metadata =
+            CoreProviderMetadata::discover_async(IssuerUrl::new(issuer)?, async_http_client)
+                .await?;
+
+        let client = CoreClient::from_provider_metadata(
+            provider_metadata,
+            ClientId::new(client_id),
+            Some(ClientSecret::new(client_secret)),
+        );
+
+        Ok(OidcProvider {
+            client,
+            redirect_url,
+            trust_email,
+        })
+    }
+
+    /// Url the user is redirected to for logging in and the state kept until they come back
+    fn authorize_url(&self, platform: &Platform) -> (Url, CsrfToken, OidcLoginState) {
+        let (pkce_challange, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let redirect_url = gen_redirect_url(self.redirect_url.clone(), platform.as_str())
+            .expect("Redirect URL should always be valid.");
+
+        let (url, csrf_token, nonce) = self
+            .client
+            .authorize_url(
+                AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .set_pkce_challenge(pkce_challange)
+            .add_scope(Scope::new("email".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .set_redirect_uri(Cow::Borrowed(&redirect_url))
+            .url();
+
+        let login_state = OidcLoginState {
+            pkce_verifier: pkce_verifier.secret().to_string(),
+            nonce: nonce.secret().to_string(),
+        };
+
+        (url, csrf_token, login_state)
+    }
+
+    /// Exchanges the authorization code and returns the email from the validated id token
+    async fn verified_email(
+        &self,
+        auth_code: String,
+        login_state: OidcLoginState,
+        platform: &Platform,
+    ) -> Result<String, AuthCallbackError> {
+        let redirect_url = gen_redirect_url(self.redirect_url.clone(), platform.as_str())
+            .expect("Redirect URL should always be valid.");
+
+        let token = self
+            .client
+            .exchange_code(AuthorizationCode::new(auth_code))
+            .set_pkce_verifier(PkceCodeVerifier::new(login_state.pkce_verifier))
+            .set_redirect_uri(Cow::Borrowed(&redirect_url))
+            .request_async(async_http_client)
+            .await?;
+
+        let claims = token
+            .id_token()
+            .ok_or(AuthCallbackError::MissingIdToken)?
+            .claims(
+                &self.client.id_token_verifier(),
+                &Nonce::new(login_state.nonce),
+            )?;
+
+        // Without `email_verified` the email is only accepted from explicitly trusted providers
+        let email_verified = claims.email_verified().unwrap_or(self.trust_email);
+        if !email_verified {
+            return Err(AuthCallbackError::EmailNotVerified);
+        }
+        Ok(claims
+            .email()
+            .ok_or(AuthCallbackError::MissingEmailInResponse)?
+            .to_string())
+    }
+}
+
+#[get("/login-oidc/{provider}")]
+pub async fn login_oidc(
+    RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+    Query(params): Query<RedirectUriParams>,
+    provider: Path<String>,
+    providers: Data<OidcProviders>,
+) -> Result<HttpResponse, AuthError> {
+    let provider = providers.get(&provider).ok_or(AuthError::UnknownProvider)?;
+
+    let (url, csrf_token, login_state) = provider.authorize_url(&params.platform);
+    let login_state = serde_json::to_string(&login_state).expect("Serializable login state");
+
+    Cmd::set_ex(csrf_token.secret(), login_state, CSRF_CACHE_EXPIRY)
+        .query_async::<_, ()>(&mut redis_cache)
+        .await?;
+
+    if let Some(invitation) = params.invitation {
+        Cmd::set_ex(
+            invitation_cache_key(csrf_token.secret()),
+            invitation,
+            CSRF_CACHE_EXPIRY,
+        )
+        .query_async::<_, ()>(&mut redis_cache)
+        .await?;
+    }
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((LOCATION, url.to_string()))
+        .finish())
+}
+
+#[get("/callback-oidc/{provider}")]
+#[allow(clippy::too_many_arguments)]
+pub async fn login_callback_oidc(
+    Query(params): Query<AuthCallbackParams>,
+    RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+    provider: Path<String>,
+    providers: Data<OidcProviders>,
+    db_pool: SeaOrmPool,
+    session: Session,
+    registration_policy: RegistrationPolicy,
+    req: HttpRequest,
+) -> Result<HttpResponse, AuthCallbackError> {
+    if params.error.is_some() {
+        let callback_url =
+            env::var("CALLBACK_URL").expect("CALLBACK_URL environment variable must be set");
+
+        return Ok(HttpResponse::SeeOther()
+            .insert_header((LOCATION, callback_url))
+            .finish());
+    }
+
+    let provider = providers
+        .get(&provider)
+        .ok_or(AuthCallbackError::UnknownProvider)?;
+
+    let (csrf_state, auth_code) = match (params.state, params.code) {
+        (Some(csrf_state), Some(auth_code)) => (csrf_state, auth_code),
+        _ => return Err(AuthCallbackError::MissingStateOrAuthCode),
+    };
+
+    let login_state: Option<String> = Cmd::get_del(&csrf_state)
+        .query_async(&mut redis_cache)
+        .await?;
+    let invitation: Option<String> = Cmd::get_del(invitation_cache_key(&csrf_state))
+        .query_async(&mut redis_cache)
+        .await?;
+
+    let login_state: OidcLoginState = match login_state.map(|s| serde_json::from_str(&s)) {
+        Some(Ok(login_state)) => login_state,
+        Some(Err(e)) => {
+            error!("Invalid OpenID Connect login state: {}", e);
+            return Err(AuthCallbackError::InvalidPkceVerifier);
+        }
+        None => return Err(AuthCallbackError::InvalidPkceVerifier),
+    };
+
+    let email = provider
+        .verified_email(auth_code, login_state, &params.platform)
+        .await?;
+
+    complete_login(
+        email,
+        invitation,
+        &params.platform,
+        &db_pool,
+        &registration_policy,
+        &session,
+        &mut redis_cache,
+        &req,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{web, App, HttpServer};
+    use chrono::{Duration, Utc};
+    use openidconnect::{
+        core::{
+            CoreIdToken, CoreIdTokenClaims, CoreIdTokenFields, CoreJsonWebKeySet,
+            CoreJwsSigningAlgorithm, CoreRsaPrivateSigningKey, CoreSubjectIdentifierType,
+            CoreTokenResponse, CoreTokenType,
+        },
+        AccessToken, Audience, AuthUrl, EmptyAdditionalClaims, EmptyAdditionalProviderMetadata,
+        EmptyExtraTokenFields, EndUserEmail, JsonWebKeyId, JsonWebKeySetUrl, PrivateSigningKey,
+        ResponseTypes, StandardClaims, SubjectIdentifier, TokenUrl,
+    };
+
+    use super::*;
+
+    const SIGNING_KEY: &str = include_str!("../test_data/oidc_signing_key.pem");
+    const CLIENT_ID: &str = "mbe-client";
+    const NONCE: &str = "expected-nonce";
+
+    /// Claims the mock issuer puts into every id token it hands out
+    #[derive(Clone)]
+    struct IssuedClaims {
+        issuer: String,
+        audience: String,
+        nonce: String,
+        email_verified: Option<bool>,
+    }
+
+    impl IssuedClaims {
+        fn valid() -> Self {
+            Self {
+                issuer: String::new(),
+                audience: CLIENT_ID.to_string(),
+                nonce: NONCE.to_string(),
+                email_verified: Some(true),
+            }
+        }
+    }
+
+    fn signing_key() -> CoreRsaPrivateSigningKey {
+        CoreRsaPrivateSigningKey::from_pem(SIGNING_KEY, Some(JsonWebKeyId::new("test".into())))
+            .expect("Valid test signing key")
+    }
+
+    async fn discovery(claims: web::Data<IssuedClaims>) -> HttpResponse {
+        let issuer = &claims.issuer;
+        let metadata = CoreProviderMetadata::new(
+            IssuerUrl::new(issuer.clone()).unwrap(),
+            AuthUrl::new(format!("{}/authorize", issuer)).unwrap(),
+            JsonWebKeySetUrl::new(format!("{}/jwks", issuer)).unwrap(),
+            vec![ResponseTypes::new(vec![CoreResponseType::Code])],
+            vec![CoreSubjectIdentifierType::Public],
+            vec![CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256],
+            EmptyAdditionalProviderMetadata {},
+        )
+        .set_token_endpoint(Some(TokenUrl::new(format!("{}/token", issuer)).unwrap()));
+        HttpResponse::Ok().json(metadata)
+    }
+
+    async fn jwks() -> HttpResponse {
+        HttpResponse::Ok().json(CoreJsonWebKeySet::new(vec![
+            signing_key().as_verification_key()
+        ]))
+    }
+
+    async fn token(claims: web::Data<IssuedClaims>) -> HttpResponse {
+        let id_token_claims = CoreIdTokenClaims::new(
+            IssuerUrl::new(claims.issuer.clone()).unwrap(),
+            vec![Audience::new(claims.audience.clone())],
+            Utc::now() + Duration::minutes(5),
+            Utc::now(),
+            StandardClaims::new(SubjectIdentifier::new("subject".to_string()))
+                .set_email(Some(EndUserEmail::new("user@example.com".to_string())))
+                .set_email_verified(claims.email_verified),
+            EmptyAdditionalClaims {},
+        )
+        .set_nonce(Some(Nonce::new(claims.nonce.clone())));
+        let id_token = CoreIdToken::new(
+            id_token_claims,
+            &signing_key(),
+            CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256,
+            None,
+            None,
+        )
+        .unwrap();
+
+        HttpResponse::Ok().json(CoreTokenResponse::new(
+            AccessToken::new("access-token".to_string()),
+            CoreTokenType::Bearer,
+            CoreIdTokenFields::new(Some(id_token), EmptyExtraTokenFields {}),
+        ))
+    }
+
+    /// Starts an issuer on a random local port and returns its url
+    fn start_issuer(claims: IssuedClaims) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let claims = web::Data::new(IssuedClaims {
+            issuer: issuer.clone(),
+            ..claims
+        });
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(claims.clone())
+                .route(
+                    "/.well-known/openid-configuration",
+                    web::get().to(discovery),
+                )
+                .route("/jwks", web::get().to(jwks))
+                .route("/token", web::post().to(token))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_web::rt::spawn(server);
+
+        issuer
+    }
+
+    async fn discover(issuer: &str, trust_email: bool) -> OidcProvider {
+        OidcProvider::discover(
+            issuer.to_string(),
+            CLIENT_ID.to_string(),
+            "secret".to_string(),
+            "http://localhost/callback-oidc/test".to_string(),
+            trust_email,
+        )
+        .await
+        .expect("Discovery from the mock issuer")
+    }
+
+    async fn login(claims: IssuedClaims) -> Result<String, AuthCallbackError> {
+        login_with(claims, false).await
+    }
+
+    async fn login_with(
+        claims: IssuedClaims,
+        trust_email: bool,
+    ) -> Result<String, AuthCallbackError> {
+        let provider = discover(&start_issuer(claims), trust_email).await;
+        let (_, _, login_state) = provider.authorize_url(&Platform::Web);
+        provider
+            .verified_email(
+                "code".to_string(),
+                OidcLoginState {
+                    nonce: NONCE.to_string(),
+                    ..login_state
+                },
+                &Platform::Web,
+            )
+            .await
+    }
+
+    #[actix_web::test]
+    async fn redirects_to_discovered_authorize_endpoint() {
+        let issuer = start_issuer(IssuedClaims::valid());
+        let provider = discover(&issuer, false).await;
+
+        let (url, csrf_token, login_state) = provider.authorize_url(&Platform::Tauri);
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(
+            url.as_str().split('?').next(),
+            Some(format!("{}/authorize", issuer).as_str())
+        );
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], CLIENT_ID);
+        assert_eq!(query["state"], *csrf_token.secret());
+        assert_eq!(query["nonce"], login_state.nonce);
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(
+            query["redirect_uri"],
+            "http://localhost/callback-oidc/test?platform=tauri"
+        );
+        assert!(query["scope"].split(' ').any(|scope| scope == "email"));
+    }
+
+    #[actix_web::test]
+    async fn accepts_valid_id_token() {
+        assert_eq!(
+            login(IssuedClaims::valid()).await.unwrap(),
+            "user@example.com"
+        );
+    }
+
+    #[actix_web::test]
+    async fn rejects_id_token_without_email_verified() {
+        let claims = IssuedClaims {
+            email_verified: None,
+            ..IssuedClaims::valid()
+        };
+        assert!(matches!(
+            login(claims).await,
+            Err(AuthCallbackError::EmailNotVerified)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn trusted_provider_accepts_id_token_without_email_verified() {
+        let claims = IssuedClaims {
+            email_verified: None,
+            ..IssuedClaims::valid()
+        };
+        assert_eq!(login_with(claims, true).await.unwrap(), "user@example.com");
+    }
+
+    #[actix_web::test]
+    async fn trusted_provider_rejects_unverified_email() {
+        let claims = IssuedClaims {
+            email_verified: Some(false),
+            ..IssuedClaims::valid()
+        };
+        assert!(matches!(
+            login_with(claims, true).await,
+            Err(AuthCallbackError::EmailNotVerified)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn rejects_id_token_with_other_nonce() {
+        let claims = IssuedClaims {
+            nonce: "other-nonce".to_string(),
+            ..IssuedClaims::valid()
+        };
+        assert!(matches!(
+            login(claims).await,
+            Err(AuthCallbackError::ClaimsVerificationError(..))
+        ));
+    }
+
+    #[actix_web::test]
+    async fn rejects_id_token_for_other_audience() {
+        let claims = IssuedClaims {
+            audience: "other-client".to_string(),
+            ..IssuedClaims::valid()
+        };
+        assert!(matches!(
+            login(claims).await,
+            Err(AuthCallbackError::ClaimsVerificationError(..))
+        ));
+    }
+
+    #[actix_web::test]
+    async fn rejects_unverified_email() {
+        let claims = IssuedClaims {
+            email_verified: Some(false),
+            ..IssuedClaims::valid()
+        };
+        assert!(matches!(
+            login(claims).await,
+            Err(AuthCallbackError::EmailNotVerified)
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::OnceLock;
+
+use actix_session::Session;
+use actix_web::{post, web::Json, HttpRequest, HttpResponse};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{Duration, Local};
+use redis::Cmd;
+use sea_orm::{
+    entity::prelude::DateTimeWithTimeZone, sea_query::Expr, ActiveModelTrait, ActiveValue,
+    ColumnTrait, EntityTrait, QueryFilter, QuerySelect, TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::{
+    auth::{Platform, SESSION_DATA_KEY},
+    http_response_errors::AuthError,
+    redis_connection_manager::RedisConnectionManagerExt,
+    user_models::{mbe_user, mbe_user_session},
+    SeaOrmPool,
+};
+
+pub const MIN_PASSWORD_LENGTH: usize = 10;
+/// Reset codes are handed out by an administrator, so they stay valid for a day
+pub const PASSWORD_RESET_CODE_EXPIRY: usize = 60 * 60 * 24;
+const MAX_FAILED_LOGIN_ATTEMPTS: i32 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+
+pub fn password_reset_cache_key(code: &str) -> String {
+    format!("password-reset:{}", code)
+}
+
+pub fn hash_password(password: &str) -> Result<String, AuthError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(AuthError::WeakPassword);
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| AuthError::PasswordHashError)
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Checked instead of a real hash for unknown emails and users without a password,
+/// so they take as long to reject as a wrong password
+pub fn dummy_password_hash() -> &'static str {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_PASSWORD_HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(b"dummy-password", &salt)
+            .expect("Hashable dummy password")
+            .to_string()
+    })
+}
+
+#[derive(Deserialize)]
+pub struct PasswordLoginParams {
+    email: String,
+    password: String,
+    #[serde(default)]
+    platform: Platform,
+}
+
+/// Logs in with the same session as the login providers, the account is locked
+/// for a while after too many failed attempts
+#[post("/login-password")]
+pub async fn login_password(
+    session: Session,
+    db_pool: SeaOrmPool,
+    RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+    Json(params): Json<PasswordLoginParams>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AuthError> {
+    let transaction = db_pool.begin().await?;
+
+    let user = mbe_user::Entity::find()
+        .filter(mbe_user::Column::Email.eq(params.email.trim().to_lowercase()))
+        .lock_exclusive()
+        .one(&transaction)
+        .await?;
+    let Some(user) = user else {
+        verify_password(&params.password, dummy_password_hash());
+        return Err(AuthError::InvalidCredentials);
+    };
+
+    let now: DateTimeWithTimeZone = Local::now().into();
+    if user
+        .locked_until
+        .is_some_and(|locked_until| locked_until > now)
+    {
+        return Err(AuthError::AccountLocked);
+    }
+
+    let Some(password_hash) = user.password_hash.as_deref() else {
+        verify_password(&params.password, dummy_password_hash());
+        return Err(AuthError::InvalidCredentials);
+    };
+    let verified = verify_password(&params.password, password_hash);
+
+    let user_id = user.id;
+    let failed_login_attempts = user.failed_login_attempts;
+    let mut model: mbe_user::ActiveModel = user.into();
+    if verified {
+        model.failed_login_attempts = ActiveValue::Set(0);
+        model.locked_until = ActiveValue::Set(None);
+    } else if failed_login_attempts + 1 >= MAX_FAILED_LOGIN_ATTEMPTS {
+        model.failed_login_attempts = ActiveValue::Set(0);
+        model.locked_until = ActiveValue::Set(Some(now + Duration::minutes(LOCKOUT_MINUTES)));
+    } else {
+        model.failed_login_attempts = ActiveValue::Set(failed_login_attempts + 1);
+    }
+    model.update(&transaction).await?;
+
+    transaction.commit().await?;
+
+    if !verified {
+        return Err(AuthError::InvalidCredentials);
+    }
+
+    let session_data =
+        mbe_user_session::register(&mut redis_cache, user_id, params.platform, &req).await?;
+    session.renew();
+    session.insert(SESSION_DATA_KEY, session_data)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetParams {
+    code: String,
+    password: String,
+}
+
+/// Sets a new password with a one-time code from `createPasswordResetCode`,
+/// also lifts an active lockout and logs out existing sessions
+#[post("/password-reset")]
+pub async fn reset_password(
+    RedisConnectionManagerExt(mut redis_cache): RedisConnectionManagerExt,
+    db_pool: SeaOrmPool,
+    Json(params): Json<PasswordResetParams>,
+) -> Result<HttpResponse, AuthError> {
+    // Checked before the code is used up so a rejected password can be retried
+    let password_hash = hash_password(&params.password)?;
+
+    let user_id: Option<i32> = Cmd::get_del(password_reset_cache_key(&params.code))
+        .query_async(&mut redis_cache)
+        .await?;
+    let user_id = user_id.ok_or(AuthError::InvalidTempCode)?;
+
+    let transaction = db_pool.begin().await?;
+
+    mbe_user::Entity::update_many()
+        .col_expr(mbe_user::Column::PasswordHash, Expr::value(password_hash))
+        .col_expr(mbe_user::Column::FailedLoginAttempts, Expr::value(0))
+        .col_expr(
+            mbe_user::Column::LockedUntil,
+            Expr::value(Option::<DateTimeWithTimeZone>::None),
+        )
+        .filter(mbe_user::Column::Id.eq(user_id))
+        .exec(&transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    mbe_user_session::revoke_all(&mut redis_cache, user_id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
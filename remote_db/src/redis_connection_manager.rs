@@ -6,7 +6,7 @@ use redis::aio::ConnectionManager;
 #[derive(Clone)]
 pub struct RedisConnectionManagerExt(pub ConnectionManager);
 
-fn create_redis_client() -> redis::Client {
+pub fn create_redis_client() -> redis::Client {
     let connection_string =
         env::var("REDIS_CONNECTION").expect("REDIS_CONNECTION env variable to be present");
     redis::Client::open(connection_string).expect("Available redis database")
@@ -0,0 +1,113 @@
+use std::{env, future::Future, pin::Pin};
+
+use actix_web::FromRequest;
+use sea_orm::{ConnectionTrait, DbErr};
+
+use crate::{load_env_var, user_models::mbe_group_invitation};
+
+/// Decides which users are created on their first login,
+/// users with an open group invitation can always sign up
+#[derive(Clone, Debug)]
+pub enum RegistrationPolicy {
+    Open,
+    InviteOnly,
+    /// Emails of the listed domains can sign up, e.g. `example.com`
+    AllowedDomains(Vec<String>),
+}
+
+impl RegistrationPolicy {
+    /// Reads `REGISTRATION_POLICY` (`open`, `invite-only` or `domains`),
+    /// the `domains` policy takes a comma separated list from `REGISTRATION_ALLOWED_DOMAINS`
+    pub fn from_env() -> Self {
+        match env::var("REGISTRATION_POLICY").as_deref() {
+            Ok("open") => RegistrationPolicy::Open,
+            Ok("invite-only") | Err(env::VarError::NotPresent) => RegistrationPolicy::InviteOnly,
+            Ok("domains") => RegistrationPolicy::AllowedDomains(
+                load_env_var!("REGISTRATION_ALLOWED_DOMAINS")
+                    .split(',')
+                    .map(|domain| domain.trim().trim_start_matches('@').to_lowercase())
+                    .filter(|domain| !domain.is_empty())
+                    .collect(),
+            ),
+            _ => panic!("REGISTRATION_POLICY must be one of open, invite-only or domains"),
+        }
+    }
+
+    /// Checks if the email can sign up without an invitation
+    fn allows_uninvited(&self, email: &str) -> bool {
+        match self {
+            RegistrationPolicy::Open => true,
+            RegistrationPolicy::InviteOnly => false,
+            RegistrationPolicy::AllowedDomains(domains) => email
+                .rsplit_once('@')
+                .is_some_and(|(_, domain)| domains.contains(&domain.to_lowercase())),
+        }
+    }
+
+    pub async fn allows<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        email: &str,
+        invitation: Option<&str>,
+    ) -> Result<bool, DbErr> {
+        if self.allows_uninvited(email) {
+            Ok(true)
+        } else {
+            mbe_group_invitation::Entity::is_invited(db, email, invitation).await
+        }
+    }
+}
+
+impl FromRequest for RegistrationPolicy {
+    type Error = actix_web::Error;
+
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            Ok(req
+                .app_data::<Self>()
+                .cloned()
+                .expect("An existing RegistrationPolicy"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_allows_everyone() {
+        assert!(RegistrationPolicy::Open.allows_uninvited("user@example.com"));
+    }
+
+    #[test]
+    fn invite_only_allows_no_one_uninvited() {
+        assert!(!RegistrationPolicy::InviteOnly.allows_uninvited("user@example.com"));
+    }
+
+    #[test]
+    fn allowed_domains_match_the_whole_domain() {
+        let policy = RegistrationPolicy::AllowedDomains(vec!["example.com".to_string()]);
+
+        assert!(policy.allows_uninvited("user@example.com"));
+        assert!(policy.allows_uninvited("User@Example.COM"));
+        assert!(!policy.allows_uninvited("user@sub.example.com"));
+        assert!(!policy.allows_uninvited("user@example.com.evil.org"));
+        assert!(!policy.allows_uninvited("example.com"));
+    }
+
+    #[test]
+    fn allowed_domains_use_the_last_at_sign() {
+        let policy = RegistrationPolicy::AllowedDomains(vec!["example.com".to_string()]);
+
+        assert!(!policy.allows_uninvited("user@example.com@other.org"));
+        assert!(policy.allows_uninvited("\"user@other.org\"@example.com"));
+    }
+}
@@ -1,24 +1,29 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
-    ActiveValue, DatabaseTransaction, DeleteResult, TransactionTrait,
+    ActiveValue, DatabaseTransaction, DeleteResult, Order, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
-    common_add_id_and_data_group_filters, common_add_ordering,
+    audit_log::{log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    common_add_id_and_data_group_filters, common_add_ordering, common_get_ordering_column,
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions, QueryResults,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EntityAccessGuard, EveryGuard,
+        FetchOptions, Filter, OrderingOptions, QueryResults, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase, RowsDeleted,
+    trash::{check_not_deleted, restore, soft_delete, RestoreOptions},
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    RowsDeleted,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "article")]
@@ -31,6 +36,8 @@ pub struct Model {
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub d_group: i32,
+    /// Set while the article is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -125,11 +132,16 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
+    fn get_deleted_at_column() -> Option<Column> {
+        Some(Column::DeletedAt)
+    }
+
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        Ok(Self::delete_by_id(options.id).exec(transaction).await?)
+        soft_delete::<Self>(transaction, session_data, options.id).await
     }
 
     fn add_ordering(
@@ -139,6 +151,12 @@ impl QueryDatabase for Entity {
         common_add_ordering(query, ordering_options)
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        common_get_ordering_column::<Self, _>(ordering_options)
+    }
+
     fn add_id_and_data_group_filters(
         query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -157,10 +175,11 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             name: options.name.map_or(ActiveValue::NotSet, ActiveValue::Set),
@@ -169,28 +188,32 @@ impl QueryDatabase for Entity {
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::update(model).exec(&transaction).await?;
-        transaction.commit().await?;
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Article not found!"))?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             name: ActiveValue::Set(options.name),
             description: ActiveValue::Set(options.description),
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
-        transaction.commit().await?;
+        log_insert(transaction, session_data, &res).await?;
+
         Ok(res)
     }
 }
@@ -209,6 +232,24 @@ impl ArticleQuery {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         Entity::fetch(db, options).await
     }
+
+    /// Cursor paged `articles`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn articles_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: FetchOptions<ArticleFields>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
+        })
+        .await
+    }
 }
 
 #[derive(Default)]
@@ -216,14 +257,24 @@ pub struct ArticleMutation;
 
 #[Object]
 impl ArticleMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_article(
         &self,
         ctx: &Context<'_>,
         options: ArticleInsertOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Article,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -233,7 +284,17 @@ impl ArticleMutation {
         options: ArticleUpdateOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Article,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -243,7 +304,119 @@ impl ArticleMutation {
         options: DeleteOptions,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::Article,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<ArticleInsertOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Article,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<ArticleUpdateOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Article,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::Article,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn restore_article(&self, ctx: &Context<'_>, options: RestoreOptions) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let transaction = db.begin().await?;
+        let res = restore::<Entity>(&transaction, session_data, options.id).await?;
+        transaction.commit().await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Article,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 }
 
@@ -258,3 +431,22 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        check_not_deleted(self.deleted_at)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::Article;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
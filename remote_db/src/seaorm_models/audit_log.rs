@@ -0,0 +1,287 @@
+use anyhow::Result;
+use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, SimpleObject};
+use sea_orm::{entity::prelude::*, ActiveValue, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::SessionData, user_models::mbe_group_members::MbeGroupRole, SeaOrmPool};
+
+use super::{
+    calculate_page_size,
+    graphql_schema::{MbeGroupAccessGuard, QueryResults},
+    history::record_version,
+    Page, PageSize,
+};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "insert")]
+    Insert,
+    #[sea_orm(string_value = "update")]
+    Update,
+    #[sea_orm(string_value = "delete")]
+    Delete,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+pub enum AuditEntityType {
+    #[sea_orm(string_value = "article")]
+    Article,
+    #[sea_orm(string_value = "buyer")]
+    Buyer,
+    #[sea_orm(string_value = "cell")]
+    Cell,
+    #[sea_orm(string_value = "cell_culture_pair")]
+    CellCulturePair,
+    #[sea_orm(string_value = "culture")]
+    Culture,
+    #[sea_orm(string_value = "data_group")]
+    DataGroup,
+    #[sea_orm(string_value = "dispatch_note")]
+    DispatchNote,
+    #[sea_orm(string_value = "dispatch_note_article")]
+    DispatchNoteArticle,
+    #[sea_orm(string_value = "dispatch_note_ident_tracker")]
+    DispatchNoteIdentTracker,
+    #[sea_orm(string_value = "dispatch_note_type")]
+    DispatchNoteType,
+    #[sea_orm(string_value = "entry")]
+    Entry,
+    #[sea_orm(string_value = "weight_type")]
+    WeightType,
+    #[sea_orm(string_value = "mbe_group")]
+    MbeGroup,
+    #[sea_orm(string_value = "mbe_group_member")]
+    MbeGroupMember,
+    #[sea_orm(string_value = "mbe_group_invitation")]
+    MbeGroupInvitation,
+    #[sea_orm(string_value = "mbe_group_print_header")]
+    MbeGroupPrintHeader,
+    #[sea_orm(string_value = "mbe_user")]
+    MbeUser,
+    #[sea_orm(string_value = "personal_access_token")]
+    PersonalAccessToken,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "audit_log")]
+#[graphql(name = "AuditLog", complex)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub id_mbe_user: Option<i32>,
+    pub id_mbe_group: Option<i32>,
+    pub d_group: Option<i32>,
+    pub entity_type: AuditEntityType,
+    pub entity_id: i32,
+    pub action: AuditAction,
+    #[graphql(skip)]
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub before: Option<Json>,
+    #[graphql(skip)]
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub after: Option<Json>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    /// Snapshot of the entity before the change, missing for inserts
+    async fn before(&self) -> Option<async_graphql::Json<Json>> {
+        self.before.clone().map(async_graphql::Json)
+    }
+
+    /// Snapshot of the entity after the change, missing for deletes
+    async fn after(&self) -> Option<async_graphql::Json<Json>> {
+        self.after.clone().map(async_graphql::Json)
+    }
+}
+
+/// Models that are recorded in the audit log, snapshots are their serialized form
+pub trait Auditable: Serialize + Sync {
+    const ENTITY_TYPE: AuditEntityType;
+
+    fn audit_id(&self) -> i32;
+
+    /// Data group the entity belongs to, the mbe group is looked up through it
+    fn audit_data_group(&self) -> Option<i32> {
+        None
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        None
+    }
+}
+
+async fn record<C: ConnectionTrait, M: Auditable>(
+    db: &C,
+    id_mbe_user: Option<i32>,
+    action: AuditAction,
+    model: &M,
+    before: Option<&M>,
+    after: Option<&M>,
+) -> Result<(), DbErr> {
+    let d_group = model.audit_data_group();
+    let id_mbe_group = match (model.audit_mbe_group(), d_group) {
+        (Some(id_mbe_group), _) => Some(id_mbe_group),
+        (None, Some(d_group)) => super::data_group::Entity::find_by_id(d_group)
+            .one(db)
+            .await?
+            .map(|data_group| data_group.id_mbe_group),
+        (None, None) => None,
+    };
+    let snapshot = |model: &M| serde_json::to_value(model).expect("Serializable model");
+
+    ActiveModel {
+        id_mbe_user: ActiveValue::Set(id_mbe_user),
+        id_mbe_group: ActiveValue::Set(id_mbe_group),
+        d_group: ActiveValue::Set(d_group),
+        entity_type: ActiveValue::Set(M::ENTITY_TYPE),
+        entity_id: ActiveValue::Set(model.audit_id()),
+        action: ActiveValue::Set(action),
+        before: ActiveValue::Set(before.map(snapshot)),
+        after: ActiveValue::Set(after.map(snapshot)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    record_version(db, M::ENTITY_TYPE, model.audit_id(), action, id_mbe_user).await?;
+
+    Ok(())
+}
+
+/// Records an inserted entity, must be called in the transaction of the insert.
+/// Versioned entities are read back from their table, so changes are logged after they are made
+pub async fn log_insert<C: ConnectionTrait, M: Auditable>(
+    db: &C,
+    session_data: &SessionData,
+    after: &M,
+) -> Result<(), DbErr> {
+    record(
+        db,
+        Some(session_data.user_id),
+        AuditAction::Insert,
+        after,
+        None,
+        Some(after),
+    )
+    .await
+}
+
+pub async fn log_update<C: ConnectionTrait, M: Auditable>(
+    db: &C,
+    session_data: &SessionData,
+    before: &M,
+    after: &M,
+) -> Result<(), DbErr> {
+    record(
+        db,
+        Some(session_data.user_id),
+        AuditAction::Update,
+        after,
+        Some(before),
+        Some(after),
+    )
+    .await
+}
+
+pub async fn log_delete<C: ConnectionTrait, M: Auditable>(
+    db: &C,
+    session_data: &SessionData,
+    before: &M,
+) -> Result<(), DbErr> {
+    record(
+        db,
+        Some(session_data.user_id),
+        AuditAction::Delete,
+        before,
+        Some(before),
+        None,
+    )
+    .await
+}
+
+/// Records an entity permanently removed by the purge task, the entry has no author
+pub async fn log_purge<C: ConnectionTrait, M: Auditable>(db: &C, before: &M) -> Result<(), DbErr> {
+    record(db, None, AuditAction::Delete, before, Some(before), None).await
+}
+
+#[derive(InputObject)]
+struct AuditLogOptions {
+    id_mbe_group: i32,
+    d_group: Option<i32>,
+    entity_type: Option<AuditEntityType>,
+    entity_id: Option<i32>,
+    action: Option<AuditAction>,
+    id_mbe_user: Option<i32>,
+    from: Option<DateTimeWithTimeZone>,
+    to: Option<DateTimeWithTimeZone>,
+    page_size: Option<u64>,
+    page: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct AuditLogQuery;
+
+#[Object]
+impl AuditLogQuery {
+    /// Newest changes first
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn audit_log(
+        &self,
+        ctx: &Context<'_>,
+        options: AuditLogOptions,
+    ) -> Result<QueryResults<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let page_size = PageSize(calculate_page_size(options.page_size));
+        let page: Page = options.page.into();
+
+        let mut query = Entity::find().filter(Column::IdMbeGroup.eq(options.id_mbe_group));
+        if let Some(d_group) = options.d_group {
+            query = query.filter(Column::DGroup.eq(d_group));
+        }
+        if let Some(entity_type) = options.entity_type {
+            query = query.filter(Column::EntityType.eq(entity_type));
+        }
+        if let Some(entity_id) = options.entity_id {
+            query = query.filter(Column::EntityId.eq(entity_id));
+        }
+        if let Some(action) = options.action {
+            query = query.filter(Column::Action.eq(action));
+        }
+        if let Some(id_mbe_user) = options.id_mbe_user {
+            query = query.filter(Column::IdMbeUser.eq(id_mbe_user));
+        }
+        if let Some(from) = options.from {
+            query = query.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = options.to {
+            query = query.filter(Column::CreatedAt.lt(to));
+        }
+
+        let transaction = db.begin().await?;
+
+        let paginator = query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .paginate(&transaction, page_size.0);
+        let res = paginator.fetch_page(page.index).await?;
+        let num_items_and_pages = paginator.num_items_and_pages().await?;
+
+        transaction.commit().await?;
+
+        Ok((res, num_items_and_pages, page, page_size).into())
+    }
+}
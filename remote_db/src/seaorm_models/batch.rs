@@ -0,0 +1,35 @@
+use async_graphql::{value, ErrorExtensions};
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    /// Items that failed by their index in the batch, the whole batch was rolled back
+    #[error("{} of the batch items failed, nothing was saved!", .0.len())]
+    Items(Vec<(usize, anyhow::Error)>),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<DbErr> for BatchError {
+    fn from(e: DbErr) -> Self {
+        BatchError::Other(e.into())
+    }
+}
+
+impl ErrorExtensions for BatchError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            if let BatchError::Items(items) = self {
+                extensions.set("code", "BATCH_FAILED");
+                extensions.set(
+                    "items",
+                    items
+                        .iter()
+                        .map(|(index, e)| value!({ "index": index, "message": e.to_string() }))
+                        .collect::<Vec<_>>(),
+                );
+            }
+        })
+    }
+}
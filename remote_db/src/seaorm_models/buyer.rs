@@ -1,24 +1,28 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
-    ActiveValue, DatabaseTransaction, DeleteResult, TransactionTrait,
+    ActiveValue, DatabaseTransaction, DeleteResult, Order, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
-    common_add_id_and_data_group_filters, common_add_ordering,
+    audit_log::{log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    common_add_id_and_data_group_filters, common_add_ordering, common_get_ordering_column,
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EntityAccessGuard, EveryGuard,
+        FetchOptions, Filter, OrderingOptions, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase, QueryResults,
-    RowsDeleted,
+    trash::{check_not_deleted, restore, soft_delete, RestoreOptions},
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    QueryResults, RowsDeleted,
 };
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
@@ -35,6 +39,8 @@ pub struct Model {
     pub contact: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub d_group: i32,
+    /// Set while the buyer is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -49,6 +55,8 @@ pub enum Relation {
     DataGroup,
     #[sea_orm(has_many = "super::entry::Entity")]
     Entry,
+    #[sea_orm(has_many = "super::dispatch_note::Entity")]
+    DispatchNote,
 }
 
 impl Related<super::data_group::Entity> for Entity {
@@ -63,8 +71,30 @@ impl Related<super::entry::Entity> for Entity {
     }
 }
 
+impl Related<super::dispatch_note::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DispatchNote.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Entity {
+    /// Finds the buyer only if it belongs to the data group
+    pub async fn find_for_data_group(
+        transaction: &DatabaseTransaction,
+        id: i32,
+        d_group: i32,
+    ) -> Result<Model> {
+        Entity::find_by_id(id)
+            .filter(Column::DGroup.eq(d_group))
+            .filter(Column::DeletedAt.is_null())
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Buyer not found in the data group!"))
+    }
+}
+
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
 pub enum BuyerFields {
     Id,
@@ -133,11 +163,16 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
+    fn get_deleted_at_column() -> Option<Column> {
+        Some(Column::DeletedAt)
+    }
+
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        Ok(Self::delete_by_id(options.id).exec(transaction).await?)
+        soft_delete::<Self>(transaction, session_data, options.id).await
     }
 
     fn add_ordering(
@@ -147,6 +182,12 @@ impl QueryDatabase for Entity {
         common_add_ordering(query, ordering_options)
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        common_get_ordering_column::<Self, _>(ordering_options)
+    }
+
     fn add_id_and_data_group_filters(
         query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -165,10 +206,11 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             name: options
@@ -182,17 +224,21 @@ impl QueryDatabase for Entity {
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::update(model).exec(&transaction).await?;
-        transaction.commit().await?;
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Buyer not found!"))?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             name: ActiveValue::Set(Some(options.name)),
             address: ActiveValue::Set(options.address),
@@ -200,11 +246,11 @@ impl QueryDatabase for Entity {
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
-        transaction.commit().await?;
+        log_insert(transaction, session_data, &res).await?;
+
         Ok(res)
     }
 }
@@ -223,6 +269,24 @@ impl BuyerQuery {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         Entity::fetch(db, options).await
     }
+
+    /// Cursor paged `buyers`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn buyers_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: FetchOptions<BuyerFields>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
+        })
+        .await
+    }
 }
 
 #[derive(Default)]
@@ -230,22 +294,154 @@ pub struct BuyerMutation;
 
 #[Object]
 impl BuyerMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_buyer(&self, ctx: &Context<'_>, options: BuyerInsertOptions) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Buyer,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn update_buyer(&self, ctx: &Context<'_>, options: BuyerUpdateOptions) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Buyer,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn delete_buyer(&self, ctx: &Context<'_>, options: DeleteOptions) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::Buyer,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_buyers(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<BuyerInsertOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Buyer,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_buyers(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<BuyerUpdateOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Buyer,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_buyers(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::Buyer,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn restore_buyer(&self, ctx: &Context<'_>, options: RestoreOptions) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let transaction = db.begin().await?;
+        let res = restore::<Entity>(&transaction, session_data, options.id).await?;
+        transaction.commit().await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Buyer,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 }
 
@@ -260,3 +456,22 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        check_not_deleted(self.deleted_at)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::Buyer;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
@@ -1,24 +1,30 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func, Query},
-    ActiveValue, Condition, DatabaseTransaction, DeleteResult, TransactionTrait,
+    ActiveValue, Condition, DatabaseTransaction, DeleteResult, Order, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
+    audit_log::{log_insert, log_update, AuditAction, AuditEntityType, Auditable},
     calculate_page_size, common_add_id_and_data_group_filters, common_add_ordering,
+    common_get_ordering_column,
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EntityAccessGuard, EveryGuard,
+        FetchOptions, Filter, OrderingOptions, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, Page, PageSize,
-    QueryDatabase, QueryResults, RowsDeleted,
+    history::begin_as_of,
+    trash::{check_not_deleted, restore, soft_delete, RestoreOptions},
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, Page,
+    PageSize, QueryDatabase, QueryResults, RowsDeleted,
 };
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
@@ -32,6 +38,8 @@ pub struct Model {
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub d_group: i32,
+    /// Set while the cell is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -132,6 +140,10 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
+    fn get_deleted_at_column() -> Option<Column> {
+        Some(Column::DeletedAt)
+    }
+
     fn add_id_and_data_group_filters(
         query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -141,15 +153,17 @@ impl QueryDatabase for Entity {
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        Ok(Self::delete_by_id(options.id).exec(transaction).await?)
+        soft_delete::<Self>(transaction, session_data, options.id).await
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             name: options.name.map_or(ActiveValue::NotSet, ActiveValue::Set),
@@ -158,28 +172,32 @@ impl QueryDatabase for Entity {
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::update(model).exec(&transaction).await?;
-        transaction.commit().await?;
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Cell not found!"))?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             name: ActiveValue::Set(options.name),
             description: ActiveValue::Set(options.description),
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
-        transaction.commit().await?;
+        log_insert(transaction, session_data, &res).await?;
+
         Ok(res)
     }
 
@@ -200,6 +218,12 @@ impl QueryDatabase for Entity {
     ) -> Select<Self> {
         common_add_ordering(query, ordering_options)
     }
+
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        common_get_ordering_column::<Self, _>(ordering_options)
+    }
 }
 
 #[derive(Default)]
@@ -217,6 +241,24 @@ impl CellQuery {
         Entity::fetch(db, options).await
     }
 
+    /// Cursor paged `cells`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn cells_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: FetchOptions<CellFields>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
+        })
+        .await
+    }
+
     #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
     async fn paired_cells(
         &self,
@@ -228,7 +270,7 @@ impl CellQuery {
         let page_size = PageSize(calculate_page_size(options.page_size));
         let page: Page = options.page.into();
 
-        let mut query = Entity::find();
+        let mut query = Entity::get_query();
         query = query.filter(Entity::get_data_group_column().eq(options.d_group));
         query = Entity::add_ordering(query, options.ordering);
         query = Entity::add_filters(query, options.filters);
@@ -246,7 +288,7 @@ impl CellQuery {
             );
         }
 
-        let transaction = db.begin().await?;
+        let transaction = begin_as_of(db, options.as_of).await?;
 
         let paginator = Entity::paginate_query(query, &transaction, page_size);
         let res = paginator.fetch_page(page.index).await?;
@@ -267,7 +309,7 @@ impl CellQuery {
         let page_size = PageSize(calculate_page_size(options.page_size));
         let page: Page = options.page.into();
 
-        let mut query = Entity::find();
+        let mut query = Entity::get_query();
         query = query.filter(Entity::get_data_group_column().eq(options.d_group));
         query = Entity::add_ordering(query, options.ordering);
         query = Entity::add_filters(query, options.filters);
@@ -285,7 +327,7 @@ impl CellQuery {
             ),
         );
 
-        let transaction = db.begin().await?;
+        let transaction = begin_as_of(db, options.as_of).await?;
 
         let paginator = Entity::paginate_query(query, &transaction, page_size);
         let res = paginator.fetch_page(page.index).await?;
@@ -301,22 +343,140 @@ pub struct CellMutation;
 
 #[Object]
 impl CellMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_cell(&self, ctx: &Context<'_>, options: CellInsertOptions) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Cell,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn update_cell(&self, ctx: &Context<'_>, options: CellUpdateOptions) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Cell,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn delete_cell(&self, ctx: &Context<'_>, options: DeleteOptions) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(ctx, d_group, AuditEntityType::Cell, AuditAction::Delete, id).await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_cells(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<CellInsertOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Cell,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_cells(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<CellUpdateOptions>,
+    ) -> async_graphql::Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::Cell,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_cells(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(ctx, d_group, AuditEntityType::Cell, AuditAction::Delete, id).await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn restore_cell(&self, ctx: &Context<'_>, options: RestoreOptions) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let transaction = db.begin().await?;
+        let res = restore::<Entity>(&transaction, session_data, options.id).await?;
+        transaction.commit().await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::Cell,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 }
 
@@ -331,3 +491,22 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        check_not_deleted(self.deleted_at)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::Cell;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
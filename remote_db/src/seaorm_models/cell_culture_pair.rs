@@ -1,23 +1,26 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
 
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
     ActiveValue, DatabaseTransaction, DeleteResult, FromQueryResult, Order, QueryOrder,
-    QuerySelect, TransactionTrait,
+    QuerySelect,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
+    audit_log::{log_delete, log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions, Pagination,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EveryGuard, FetchOptions, Filter,
+        OrderingOptions, Pagination, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase, QueryResults,
-    QueryResultsHelperType, RowsDeleted,
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    QueryResults, QueryResultsHelperType, RowsDeleted,
 };
 
 use anyhow::{anyhow, Result};
@@ -89,6 +92,29 @@ impl Related<super::entry::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Entity {
+    /// Finds the pair of the cell and culture only if it belongs to the data group
+    /// and neither the cell nor the culture is in the trash
+    pub async fn find_for_data_group(
+        transaction: &DatabaseTransaction,
+        id_cell: i32,
+        id_culture: i32,
+        d_group: i32,
+    ) -> Result<Model> {
+        Entity::find()
+            .inner_join(super::cell::Entity)
+            .inner_join(super::culture::Entity)
+            .filter(super::cell::Column::DeletedAt.is_null())
+            .filter(super::culture::Column::DeletedAt.is_null())
+            .filter(Column::IdCell.eq(id_cell))
+            .filter(Column::IdCulture.eq(id_culture))
+            .filter(Column::DGroup.eq(d_group))
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("CellCulturePair with provided ids must exist!"))
+    }
+}
+
 #[derive(Debug, Enum, Clone, Copy, PartialEq, Eq, DeriveColumn)]
 pub enum CellCulturePairFields {
     CellName,
@@ -128,6 +154,7 @@ pub struct CellCulturePairUpdateOptions {
 pub struct CellCulturePairFlattened {
     pub id: i32,
     pub created_at: DateTimeWithTimeZone,
+    #[allow(dead_code)]
     pub d_group: i32,
 
     pub id_cell: i32,
@@ -135,12 +162,14 @@ pub struct CellCulturePairFlattened {
     pub description_cell: Option<String>,
     pub created_at_cell: DateTimeWithTimeZone,
     pub d_group_cell: i32,
+    pub deleted_at_cell: Option<DateTimeWithTimeZone>,
 
     pub id_culture: i32,
     pub name_culture: String,
     pub description_culture: Option<String>,
     pub created_at_culture: DateTimeWithTimeZone,
     pub d_group_culture: i32,
+    pub deleted_at_culture: Option<DateTimeWithTimeZone>,
 
     pub id_d_group: i32,
     pub name_d_group: String,
@@ -158,12 +187,6 @@ pub struct CellCulturePair {
     pub d_group: super::data_group::Model,
 }
 
-#[derive(Debug, SimpleObject)]
-pub struct AllCellCulturePairs {
-    results: Vec<CellCulturePair>,
-    total: u64,
-}
-
 impl From<QueryResultsHelperType<CellCulturePairFlattened>> for QueryResults<CellCulturePair> {
     fn from(inp: QueryResultsHelperType<CellCulturePairFlattened>) -> Self {
         let (results, items_and_page_number, page, page_size) = inp;
@@ -179,6 +202,7 @@ impl From<QueryResultsHelperType<CellCulturePairFlattened>> for QueryResults<Cel
                         description: flat.description_cell,
                         created_at: flat.created_at_cell,
                         d_group: flat.d_group_cell,
+                        deleted_at: flat.deleted_at_cell,
                     },
                     culture: super::culture::Model {
                         id: flat.id_culture,
@@ -186,6 +210,7 @@ impl From<QueryResultsHelperType<CellCulturePairFlattened>> for QueryResults<Cel
                         description: flat.description_culture,
                         created_at: flat.created_at_culture,
                         d_group: flat.d_group_culture,
+                        deleted_at: flat.deleted_at_culture,
                     },
                     d_group: super::data_group::Model {
                         id: flat.id_d_group,
@@ -196,12 +221,7 @@ impl From<QueryResultsHelperType<CellCulturePairFlattened>> for QueryResults<Cel
                     },
                 })
                 .collect(),
-            pagination: Pagination {
-                page: page.page,
-                page_size: page_size.0,
-                total_items: items_and_page_number.number_of_items,
-                total_pages: items_and_page_number.number_of_pages,
-            },
+            pagination: Pagination::new(page, page_size, items_and_page_number),
         }
     }
 }
@@ -226,6 +246,7 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
+    /// Pairs of deleted cells or cultures are hidden with them
     fn get_query() -> Select<Self> {
         Entity::find()
             .inner_join(super::cell::Entity)
@@ -236,11 +257,13 @@ impl QueryDatabase for Entity {
             .column_as(super::cell::Column::Description, "description_cell")
             .column_as(super::cell::Column::CreatedAt, "created_at_cell")
             .column_as(super::cell::Column::DGroup, "d_group_cell")
+            .column_as(super::cell::Column::DeletedAt, "deleted_at_cell")
             .column_as(super::culture::Column::Id, "id_culture")
             .column_as(super::culture::Column::Name, "name_culture")
             .column_as(super::culture::Column::Description, "description_culture")
             .column_as(super::culture::Column::CreatedAt, "created_at_culture")
             .column_as(super::culture::Column::DGroup, "d_group_culture")
+            .column_as(super::culture::Column::DeletedAt, "deleted_at_culture")
             .column_as(super::data_group::Column::Id, "id_d_group")
             .column_as(super::data_group::Column::Name, "name_d_group")
             .column_as(
@@ -249,12 +272,18 @@ impl QueryDatabase for Entity {
             )
             .column_as(super::data_group::Column::CreatedAt, "created_at_d_group")
             .column_as(super::data_group::Column::IdMbeGroup, "id_mbe_group")
+            .filter(super::cell::Column::DeletedAt.is_null())
+            .filter(super::culture::Column::DeletedAt.is_null())
     }
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
+        if let Some(model) = Self::find_by_id(options.id).one(transaction).await? {
+            log_delete(transaction, session_data, &model).await?;
+        }
         Ok(Self::delete_by_id(options.id).exec(transaction).await?)
     }
 
@@ -285,6 +314,29 @@ impl QueryDatabase for Entity {
         query
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        match ordering_options {
+            Some(options) => {
+                let column = match options.order_by {
+                    Self::InputFields::CellName => OrderingColumn::new(super::cell::Column::Name),
+                    Self::InputFields::CellDescription => {
+                        OrderingColumn::new(super::cell::Column::Description)
+                    }
+                    Self::InputFields::CultureName => {
+                        OrderingColumn::new(super::culture::Column::Name)
+                    }
+                    Self::InputFields::CultureDescription => {
+                        OrderingColumn::new(super::culture::Column::Description)
+                    }
+                };
+                (column, options.order.into())
+            }
+            None => (OrderingColumn::new(super::cell::Column::Name), Order::Asc),
+        }
+    }
+
     fn add_id_and_data_group_filters(
         mut query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -333,11 +385,22 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
-        let transaction = db.begin().await?;
+    ) -> Result<Model> {
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Cell culture pair not found!"))?;
+        check_in_data_group(
+            transaction,
+            options.id_cell,
+            options.id_culture,
+            before.d_group,
+        )
+        .await?;
 
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
@@ -345,75 +408,41 @@ impl QueryDatabase for Entity {
                 .id_cell
                 .map_or(ActiveValue::NotSet, ActiveValue::Set),
             id_culture: options
-                .id_cell
+                .id_culture
                 .map_or(ActiveValue::NotSet, ActiveValue::Set),
             ..Default::default()
         };
-        let res = Entity::update(model).exec(&transaction).await?;
-
-        transaction.commit().await?;
-
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<CellCulturePairFields, Option<CellCulturePairIds>> {
-                id: Some(CellCulturePairIds {
-                    id_cell: res.id_cell,
-                    id_culture: res.id_culture,
-                    d_group: res.d_group,
-                }),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
-        )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Updated CellCulturePair not found"))?
-        .1)
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
+
+        Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
+        check_in_data_group(
+            transaction,
+            Some(options.id_cell),
+            Some(options.id_culture),
+            options.d_group,
+        )
+        .await?;
+
         let model = ActiveModel {
             id_cell: ActiveValue::Set(options.id_cell),
             id_culture: ActiveValue::Set(options.id_culture),
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
-        transaction.commit().await?;
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<CellCulturePairFields, Option<CellCulturePairIds>> {
-                id: Some(CellCulturePairIds {
-                    id_cell: res.id_cell,
-                    id_culture: res.id_culture,
-                    d_group: res.d_group,
-                }),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
-        )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Inserted CellCulturePair not found"))?
-        .1)
+        log_insert(transaction, session_data, &res).await?;
+
+        Ok(res)
     }
 }
 
@@ -432,60 +461,22 @@ impl CellCulturePairQuery {
         Entity::fetch(db, options).await
     }
 
+    /// Cursor paged `cellCulturePairs`, ignores `page` and `pageSize` of the options
     #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
-    async fn all_cell_culture_pairs(
+    async fn cell_culture_pairs_connection(
         &self,
         ctx: &Context<'_>,
         options: FetchOptions<CellCulturePairFields, Option<CellCulturePairIds>>,
-    ) -> Result<AllCellCulturePairs> {
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<CellCulturePair>> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-
-        let mut query = Entity::get_query();
-
-        query = Entity::add_id_and_data_group_filters(query, &options);
-        query = Entity::add_ordering(query, options.ordering);
-        query = Entity::add_filters(query, options.filters);
-
-        let transaction = db.begin().await?;
-
-        let res = query
-            .into_model::<<Entity as QueryDatabase>::FetchModel>()
-            .all(&transaction)
-            .await?;
-
-        transaction.commit().await?;
-
-        Ok(AllCellCulturePairs {
-            total: res.len() as u64,
-            results: res
-                .into_iter()
-                .map(|flat| CellCulturePair {
-                    id: flat.id,
-                    created_at: flat.created_at,
-                    cell: super::cell::Model {
-                        id: flat.id_cell,
-                        name: flat.name_cell,
-                        description: flat.description_cell,
-                        created_at: flat.created_at_cell,
-                        d_group: flat.d_group_cell,
-                    },
-                    culture: super::culture::Model {
-                        id: flat.id_culture,
-                        name: flat.name_culture,
-                        description: flat.description_culture,
-                        created_at: flat.created_at_culture,
-                        d_group: flat.d_group_culture,
-                    },
-                    d_group: super::data_group::Model {
-                        id: flat.id_d_group,
-                        name: flat.name_d_group,
-                        description: flat.description_d_group,
-                        created_at: flat.created_at_d_group,
-                        id_mbe_group: flat.id_mbe_group,
-                    },
-                })
-                .collect(),
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
         })
+        .await
     }
 }
 
@@ -494,14 +485,24 @@ pub struct CellCulturePairMutation;
 
 #[Object]
 impl CellCulturePairMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_cell_culture_pair(
         &self,
         ctx: &Context<'_>,
         options: CellCulturePairIds,
     ) -> Result<CellCulturePair> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::CellCulturePair,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -511,7 +512,17 @@ impl CellCulturePairMutation {
         options: CellCulturePairUpdateOptions,
     ) -> Result<CellCulturePair> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::CellCulturePair,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -521,7 +532,101 @@ impl CellCulturePairMutation {
         options: DeleteOptions,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::CellCulturePair,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_cell_culture_pairs(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<CellCulturePairIds>,
+    ) -> async_graphql::Result<Vec<CellCulturePair>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::CellCulturePair,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_cell_culture_pairs(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<CellCulturePairUpdateOptions>,
+    ) -> async_graphql::Result<Vec<CellCulturePair>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::CellCulturePair,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_cell_culture_pairs(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::CellCulturePair,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
     }
 }
 
@@ -536,3 +641,43 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+impl CheckEditable for Model {}
+
+/// Cells and cultures can only be paired inside their own data group
+async fn check_in_data_group(
+    transaction: &DatabaseTransaction,
+    id_cell: Option<i32>,
+    id_culture: Option<i32>,
+    d_group: i32,
+) -> Result<()> {
+    if let Some(id_cell) = id_cell {
+        super::cell::Entity::find_by_id(id_cell)
+            .filter(super::cell::Column::DGroup.eq(d_group))
+            .filter(super::cell::Column::DeletedAt.is_null())
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Cell not found in the data group!"))?;
+    }
+    if let Some(id_culture) = id_culture {
+        super::culture::Entity::find_by_id(id_culture)
+            .filter(super::culture::Column::DGroup.eq(d_group))
+            .filter(super::culture::Column::DeletedAt.is_null())
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Culture not found in the data group!"))?;
+    }
+    Ok(())
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::CellCulturePair;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
@@ -0,0 +1,326 @@
+use anyhow::Result;
+use std::future::Future;
+
+use async_graphql::{
+    connection::{self, Connection, CursorType},
+    Context, OutputType, SimpleObject,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sea_orm::{
+    sea_query::{Alias, DynIden, Expr, SeaRc, SimpleExpr},
+    ColumnTrait, ColumnType, DbErr, FromQueryResult, Order, QueryResult,
+};
+use serde::{Deserialize, Serialize};
+
+/// Aliases the cursor is selected under next to the columns of `FetchModel`
+pub const CURSOR_VALUE: &str = "cursor_value";
+pub const CURSOR_ID: &str = "cursor_id";
+
+/// Position of a row in the results, the value of the ordering column
+/// as text and the id breaking ties between equal values
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cursor {
+    value: Option<String>,
+    id: i32,
+}
+
+impl CursorType for Cursor {
+    type Error = anyhow::Error;
+
+    fn decode_cursor(s: &str) -> Result<Self> {
+        Ok(serde_json::from_slice(&URL_SAFE_NO_PAD.decode(s)?)?)
+    }
+
+    fn encode_cursor(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("Cursor must serialize"))
+    }
+}
+
+impl Cursor {
+    /// Rows ordered after the cursor, nulls are ordered as the largest values
+    /// same as postgres does by default
+    fn after(&self, column: &OrderingColumn, id: SimpleExpr, order: &Order) -> SimpleExpr {
+        match order {
+            Order::Desc => self.lesser(column, id),
+            _ => self.greater(column, id),
+        }
+    }
+
+    /// Rows ordered before the cursor
+    fn before(&self, column: &OrderingColumn, id: SimpleExpr, order: &Order) -> SimpleExpr {
+        match order {
+            Order::Desc => self.greater(column, id),
+            _ => self.lesser(column, id),
+        }
+    }
+
+    fn greater(&self, column: &OrderingColumn, id: SimpleExpr) -> SimpleExpr {
+        let expr = || Expr::expr(column.expr.clone());
+        match &self.value {
+            Some(value) => expr()
+                .is_null()
+                .or(expr().gt(column.value(value)))
+                .or(expr()
+                    .eq(column.value(value))
+                    .and(Expr::expr(id).gt(self.id))),
+            None => expr().is_null().and(Expr::expr(id).gt(self.id)),
+        }
+    }
+
+    fn lesser(&self, column: &OrderingColumn, id: SimpleExpr) -> SimpleExpr {
+        let expr = || Expr::expr(column.expr.clone());
+        match &self.value {
+            Some(value) => expr().lt(column.value(value)).or(expr()
+                .eq(column.value(value))
+                .and(Expr::expr(id).lt(self.id))),
+            None => expr().is_not_null().or(Expr::expr(id).lt(self.id)),
+        }
+    }
+}
+
+/// Column the results are ordered by, connections page through it with the id as a tiebreaker
+pub struct OrderingColumn {
+    expr: SimpleExpr,
+    type_name: DynIden,
+}
+
+impl OrderingColumn {
+    pub fn new<C: ColumnTrait>(column: C) -> Self {
+        let type_name: DynIden = match column.def().get_column_type() {
+            ColumnType::Enum { name, .. } => name.clone(),
+            ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger => SeaRc::new(Alias::new("bigint")),
+            ColumnType::Float | ColumnType::Double => SeaRc::new(Alias::new("float8")),
+            ColumnType::Decimal(_) => SeaRc::new(Alias::new("numeric")),
+            ColumnType::Date => SeaRc::new(Alias::new("date")),
+            ColumnType::DateTime | ColumnType::Timestamp => SeaRc::new(Alias::new("timestamp")),
+            ColumnType::TimestampWithTimeZone => SeaRc::new(Alias::new("timestamptz")),
+            ColumnType::Boolean => SeaRc::new(Alias::new("boolean")),
+            _ => SeaRc::new(Alias::new("text")),
+        };
+
+        Self {
+            expr: Expr::col(column.as_column_ref()).into(),
+            type_name,
+        }
+    }
+
+    pub fn expr(&self) -> SimpleExpr {
+        self.expr.clone()
+    }
+
+    /// Text representation of the value stored in the cursor
+    pub fn as_text(&self) -> SimpleExpr {
+        Expr::expr(self.expr.clone()).cast_as(Alias::new("text"))
+    }
+
+    /// Cursor value cast back to the type of the column so it is compared the same way it is ordered
+    fn value(&self, value: &str) -> SimpleExpr {
+        Expr::val(value).cast_as(self.type_name.clone())
+    }
+}
+
+/// Arguments of a connection field, already validated by `async_graphql::connection::query`
+pub struct ConnectionArgs {
+    pub after: Option<Cursor>,
+    pub before: Option<Cursor>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+    /// The total count is skipped unless the client selected it
+    pub with_total_count: bool,
+}
+
+impl ConnectionArgs {
+    /// Conditions limiting the rows to the ones between `after` and `before`
+    pub fn conditions(
+        &self,
+        column: &OrderingColumn,
+        id: SimpleExpr,
+        order: &Order,
+    ) -> Vec<SimpleExpr> {
+        let after = self
+            .after
+            .as_ref()
+            .map(|cursor| cursor.after(column, id.clone(), order));
+        let before = self
+            .before
+            .as_ref()
+            .map(|cursor| cursor.before(column, id.clone(), order));
+        after.into_iter().chain(before).collect()
+    }
+
+    /// Pages from the end of the results when only `last` is given
+    pub fn is_backward(&self) -> bool {
+        self.first.is_none() && self.last.is_some()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ConnectionFields {
+    /// Only counted when selected, counting needs another query over every matching row
+    pub total_count: Option<u64>,
+    /// Requested `first` or `last` after it was clamped to the limits configured on the server
+    pub effective_page_size: u64,
+}
+
+pub type QueryConnection<T> = Connection<Cursor, T, ConnectionFields>;
+
+/// Row of `FetchModel` together with its cursor
+pub struct CursorRow<M> {
+    pub model: M,
+    pub cursor: Cursor,
+}
+
+impl<M> FromQueryResult for CursorRow<M>
+where
+    M: FromQueryResult,
+{
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            model: M::from_query_result(res, pre)?,
+            cursor: Cursor {
+                value: res.try_get(pre, CURSOR_VALUE)?,
+                id: res.try_get(pre, CURSOR_ID)?,
+            },
+        })
+    }
+}
+
+pub fn reverse(order: &Order) -> Order {
+    match order {
+        Order::Desc => Order::Asc,
+        _ => Order::Desc,
+    }
+}
+
+/// Negative page sizes are an error instead of being read as a missing `first` or `last`
+fn check_page_size(name: &str, size: Option<i32>) -> async_graphql::Result<()> {
+    match size {
+        Some(size) if size < 0 => Err(format!("\"{}\" must not be negative", name).into()),
+        _ => Ok(()),
+    }
+}
+
+/// Validates the arguments of a connection field and decodes its cursors before fetching the page
+pub async fn query_connection<T, F, R>(
+    ctx: &Context<'_>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    fetch: F,
+) -> async_graphql::Result<QueryConnection<T>>
+where
+    T: OutputType,
+    F: FnOnce(ConnectionArgs) -> R,
+    R: Future<Output = Result<QueryConnection<T>>>,
+{
+    check_page_size("first", first)?;
+    check_page_size("last", last)?;
+
+    let with_total_count = ctx.look_ahead().field("totalCount").exists();
+    connection::query(after, before, first, last, |after, before, first, last| {
+        fetch(ConnectionArgs {
+            after,
+            before,
+            first,
+            last,
+            with_total_count,
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sea_query::{PostgresQueryBuilder, Query};
+
+    use super::*;
+    use crate::seaorm_models::entry;
+
+    fn cursor(value: Option<&str>, id: i32) -> Cursor {
+        Cursor {
+            value: value.map(str::to_string),
+            id,
+        }
+    }
+
+    fn condition(expr: SimpleExpr) -> String {
+        Query::select()
+            .expr(Expr::val(1))
+            .and_where(expr)
+            .to_string(PostgresQueryBuilder)
+    }
+
+    fn id() -> SimpleExpr {
+        Expr::col(entry::Column::Id).into()
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let encoded = cursor(Some("2.5"), 7).encode_cursor();
+        let decoded = Cursor::decode_cursor(&encoded).unwrap();
+
+        assert_eq!(decoded.value.as_deref(), Some("2.5"));
+        assert_eq!(decoded.id, 7);
+    }
+
+    #[test]
+    fn rejects_negative_page_size() {
+        assert!(check_page_size("first", Some(-1)).is_err());
+        assert!(check_page_size("last", Some(0)).is_ok());
+        assert!(check_page_size("last", None).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_cursor() {
+        assert!(Cursor::decode_cursor("not a cursor").is_err());
+        assert!(Cursor::decode_cursor(&URL_SAFE_NO_PAD.encode("{}")).is_err());
+    }
+
+    #[test]
+    fn after_value_in_ascending_order() {
+        let column = OrderingColumn::new(entry::Column::Weight);
+        let after = cursor(Some("2.5"), 7).after(&column, id(), &Order::Asc);
+
+        assert_eq!(
+            condition(after),
+            r#"SELECT 1 WHERE ("entry"."weight" IS NULL) OR ("entry"."weight" > CAST('2.5' AS float8)) OR (("entry"."weight" = CAST('2.5' AS float8)) AND ("id" > 7))"#
+        );
+    }
+
+    #[test]
+    fn after_value_in_descending_order() {
+        let column = OrderingColumn::new(entry::Column::Weight);
+        let after = cursor(Some("2.5"), 7).after(&column, id(), &Order::Desc);
+
+        assert_eq!(
+            condition(after),
+            r#"SELECT 1 WHERE ("entry"."weight" < CAST('2.5' AS float8)) OR (("entry"."weight" = CAST('2.5' AS float8)) AND ("id" < 7))"#
+        );
+    }
+
+    #[test]
+    fn after_null_in_ascending_order() {
+        let column = OrderingColumn::new(entry::Column::Weight);
+        let after = cursor(None, 7).after(&column, id(), &Order::Asc);
+
+        assert_eq!(
+            condition(after),
+            r#"SELECT 1 WHERE ("entry"."weight" IS NULL) AND ("id" > 7)"#
+        );
+    }
+
+    #[test]
+    fn before_null_in_ascending_order() {
+        let column = OrderingColumn::new(entry::Column::Weight);
+        let before = cursor(None, 7).before(&column, id(), &Order::Asc);
+
+        assert_eq!(
+            condition(before),
+            r#"SELECT 1 WHERE ("entry"."weight" IS NOT NULL) OR ("id" < 7)"#
+        );
+    }
+}
@@ -3,24 +3,31 @@ use async_trait::async_trait;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
-    ActiveValue, DatabaseTransaction, DeleteResult, JoinType, QueryOrder, QuerySelect,
+    ActiveValue, DatabaseTransaction, DeleteResult, JoinType, Order, QueryOrder, QuerySelect,
     TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    user_models::{mbe_group, mbe_group_members},
+    auth::SessionData,
+    user_models::{
+        mbe_group,
+        mbe_group_members::{self, MbeGroupRole},
+    },
     SeaOrmPool,
 };
 
 use super::{
-    common_add_id_and_data_group_filters, common_add_ordering,
+    audit_log::{log_delete, log_insert, log_update, AuditEntityType, Auditable},
+    common_add_id_and_data_group_filters, common_add_ordering, common_get_ordering_column,
+    connection::OrderingColumn,
     graphql_schema::{
-        extract_session, DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions,
+        extract_session, DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter,
+        MbeGroupAccessGuard, OrderingOptions,
     },
     GetEntityDataGroupColumnTrait, GetEntityId, QueryDatabase, QueryResults, RowsDeleted,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "data_group")]
@@ -185,8 +192,12 @@ impl QueryDatabase for Entity {
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
+        if let Some(model) = Self::find_by_id(options.id).one(transaction).await? {
+            log_delete(transaction, session_data, &model).await?;
+        }
         Ok(Self::delete_by_id(options.id).exec(transaction).await?)
     }
 
@@ -197,10 +208,11 @@ impl QueryDatabase for Entity {
         common_add_id_and_data_group_filters(query, fetch_options)
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             name: options.name.map_or(ActiveValue::NotSet, ActiveValue::Set),
@@ -209,34 +221,36 @@ impl QueryDatabase for Entity {
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::update(model).exec(&transaction).await?;
-        transaction.commit().await?;
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Data group not found!"))?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             name: ActiveValue::Set(options.name),
             description: ActiveValue::Set(options.description),
             id_mbe_group: ActiveValue::Set(options.id_mbe_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-
-        let res = model.insert(&transaction).await?;
+        let res = model.insert(transaction).await?;
+        log_insert(transaction, session_data, &res).await?;
         super::dispatch_note_ident_tracker::ActiveModel {
             id_data_group: ActiveValue::Set(res.id),
             ..Default::default()
         }
-        .insert(&transaction)
+        .insert(transaction)
         .await?;
 
-        transaction.commit().await?;
         Ok(res)
     }
 
@@ -257,6 +271,12 @@ impl QueryDatabase for Entity {
     ) -> Select<Self> {
         common_add_ordering(query, ordering_options)
     }
+
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        common_get_ordering_column::<Self, _>(ordering_options)
+    }
 }
 
 #[derive(Default)]
@@ -271,13 +291,18 @@ impl DataGroupQuery {
 
         let transaction = db.begin().await?;
 
-        let res = Entity::find()
+        let mut query = Entity::find()
             .inner_join(mbe_group::Entity)
             .join(
                 JoinType::InnerJoin,
                 mbe_group_members::Relation::MbeGroup.def().rev(),
             )
-            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
+            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id));
+        if let Some(token) = &session_data.token {
+            query = query.filter(Column::IdMbeGroup.is_in(token.mbe_groups.clone()));
+        }
+
+        let res = query
             .order_by_asc(Column::Id)
             .into_model::<<Entity as QueryDatabase>::FetchModel>()
             .all(&transaction)
@@ -294,35 +319,37 @@ pub struct DataGroupMutation;
 
 #[Object]
 impl DataGroupMutation {
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
     async fn insert_data_group(
         &self,
         ctx: &Context<'_>,
         options: DataGroupInsertOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        Entity::insert_entity(db, session_data, options).await
     }
 
-    // TODO: Update accordingy for group use
-    #[graphql(guard = "DataGroupAccessGuard::new(options.id)")]
+    #[graphql(guard = "DataGroupAccessGuard::with_role(options.id, MbeGroupRole::Admin)")]
     async fn update_data_group(
         &self,
         ctx: &Context<'_>,
         options: DataGroupUpdateOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        Entity::update_entity(db, session_data, options).await
     }
 
-    // TODO: Update accordingy for group use
-    #[graphql(guard = "DataGroupAccessGuard::new(options.id)")]
+    #[graphql(guard = "DataGroupAccessGuard::with_role(options.id, MbeGroupRole::Admin)")]
     async fn delete_data_group(
         &self,
         ctx: &Context<'_>,
         options: DeleteOptions,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        Entity::delete_entity(db, session_data, options).await
     }
 }
 
@@ -331,3 +358,19 @@ impl GetEntityId<Column> for Entity {
         Column::Id
     }
 }
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::DataGroup;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.id)
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id_mbe_group)
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use async_graphql::{Context, SimpleObject, Subscription};
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    Stream, StreamExt,
+};
+use log::{error, warn};
+use redis::Cmd;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::SessionData,
+    redis_connection_manager::{create_redis_client, RedisConnectionManagerExt},
+    user_models::{mbe_user_session, personal_access_token},
+    SeaOrmPool,
+};
+
+use super::{
+    audit_log::{AuditAction, AuditEntityType},
+    graphql_schema::{extract_session, has_data_group_access, DataGroupAccessGuard},
+    GetEntityDataGroupId, GetEntityId,
+};
+
+/// Every instance of the API listens on this channel so events reach
+/// subscribers no matter which instance handled the mutation
+const CHANNEL: &str = "mbe:data_group_events";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct DataGroupEvent {
+    pub d_group: i32,
+    pub entity: AuditEntityType,
+    /// Restored entities are reported as inserted
+    pub action: AuditAction,
+    pub id: i32,
+    /// Lets clients skip changes they made themselves
+    pub id_mbe_user: i32,
+}
+
+struct Subscriber {
+    d_group: i32,
+    sender: UnboundedSender<DataGroupEvent>,
+}
+
+/// Subscribers connected to this instance of the API
+#[derive(Clone, Default)]
+pub struct DataGroupEvents {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl DataGroupEvents {
+    fn subscribe(&self, d_group: i32) -> impl Stream<Item = DataGroupEvent> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.subscribers
+            .lock()
+            .expect("Subscribers lock")
+            .push(Subscriber { d_group, sender });
+        receiver
+    }
+
+    /// Hands the event to subscribers of its data group, closed subscriptions are dropped
+    fn dispatch(&self, event: DataGroupEvent) {
+        self.subscribers
+            .lock()
+            .expect("Subscribers lock")
+            .retain(|subscriber| {
+                if subscriber.d_group != event.d_group {
+                    return !subscriber.sender.is_closed();
+                }
+                subscriber.sender.unbounded_send(event.clone()).is_ok()
+            });
+    }
+
+    async fn listen(&self) -> Result<()> {
+        let mut pubsub = create_redis_client()
+            .get_async_connection()
+            .await?
+            .into_pubsub();
+        pubsub.subscribe(CHANNEL).await?;
+
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            let payload: String = message.get_payload()?;
+            match serde_json::from_str(&payload) {
+                Ok(event) => self.dispatch(event),
+                Err(e) => warn!("Ignoring malformed data group event: {:#?}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forwards events published by any instance to local subscribers,
+    /// reconnects when the redis connection is lost
+    pub fn spawn_listener(&self) {
+        let events = self.clone();
+
+        actix_web::rt::spawn(async move {
+            loop {
+                if let Err(e) = events.listen().await {
+                    error!("Listening for data group events failed: {:#?}", e);
+                }
+                actix_web::rt::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+}
+
+/// Data group of the entity, looked up before it is deleted so the delete can be published
+pub async fn data_group_of<E>(db: &DatabaseConnection, id: i32) -> Result<Option<i32>>
+where
+    E: EntityTrait + GetEntityId<<E as EntityTrait>::Column>,
+    E::Model: GetEntityDataGroupId,
+{
+    Ok(E::find()
+        .filter(E::get_id_column().eq(id))
+        .one(db)
+        .await?
+        .map(|model| model.get_data_group_id()))
+}
+
+/// Publishes a committed change, failures are logged and never fail the mutation
+pub async fn publish(
+    ctx: &Context<'_>,
+    d_group: Option<i32>,
+    entity: AuditEntityType,
+    action: AuditAction,
+    id: i32,
+) {
+    let (d_group, session_data) = match (d_group, extract_session(ctx)) {
+        (Some(d_group), Ok(session_data)) => (d_group, session_data),
+        _ => return,
+    };
+    let event = DataGroupEvent {
+        d_group,
+        entity,
+        action,
+        id,
+        id_mbe_user: session_data.user_id,
+    };
+
+    let RedisConnectionManagerExt(mut redis) = ctx
+        .data::<RedisConnectionManagerExt>()
+        .expect("RedisConnectionManager must exist")
+        .clone();
+    let payload = match serde_json::to_string(&event) {
+        Ok(payload) => payload,
+        Err(e) => {
+            error!("Serializing data group event failed: {:#?}", e);
+            return;
+        }
+    };
+    if let Err(e) = Cmd::publish(CHANNEL, payload)
+        .query_async::<_, i64>(&mut redis)
+        .await
+    {
+        error!("Publishing data group event failed: {:#?}", e);
+    }
+}
+
+/// Subscriptions outlive the request that started them, so the session and access to the
+/// data group are checked again before each event is sent
+async fn still_authorized(
+    db: &DatabaseConnection,
+    mut redis: RedisConnectionManagerExt,
+    session_data: &SessionData,
+    d_group: i32,
+) -> Result<bool> {
+    let session_valid = match &session_data.token {
+        Some(token) => personal_access_token::Entity::is_active(db, token.id).await?,
+        None => mbe_user_session::is_registered(&mut redis.0, session_data).await?,
+    };
+
+    Ok(session_valid && has_data_group_access(db, session_data, d_group).await?)
+}
+
+#[derive(Default)]
+pub struct DataGroupEventsSubscription;
+
+#[Subscription]
+impl DataGroupEventsSubscription {
+    /// Entries, dispatch notes and master data inserted, updated or deleted in the data group.
+    /// Ends once the session is revoked or access to the data group is lost
+    #[graphql(guard = "DataGroupAccessGuard::new(d_group)")]
+    async fn data_group_events(
+        &self,
+        ctx: &Context<'_>,
+        d_group: i32,
+        entities: Option<Vec<AuditEntityType>>,
+    ) -> async_graphql::Result<impl Stream<Item = DataGroupEvent>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist").clone();
+        let redis = ctx
+            .data::<RedisConnectionManagerExt>()
+            .expect("RedisConnectionManager must exist")
+            .clone();
+        let session_data = extract_session(ctx)?.clone();
+
+        Ok(ctx
+            .data::<DataGroupEvents>()
+            .expect("DataGroupEvents must exist")
+            .subscribe(d_group)
+            .filter(move |event| {
+                let wanted = entities
+                    .as_ref()
+                    .is_none_or(|entities| entities.contains(&event.entity));
+                async move { wanted }
+            })
+            .take_while(move |_| {
+                let db = db.clone();
+                let redis = redis.clone();
+                let session_data = session_data.clone();
+                async move {
+                    still_authorized(&db, redis, &session_data, d_group)
+                        .await
+                        .unwrap_or_else(|e| {
+                            error!("Checking data group event subscriber failed: {:#?}", e);
+                            false
+                        })
+                }
+            }))
+    }
+}
@@ -1,37 +1,80 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{
+    Context, Enum, ErrorExtensions, InputObject, MaybeUndefined, Object, SimpleObject,
+};
 use async_trait::async_trait;
 use log::error;
 use sea_orm::{
-    entity::prelude::*, sea_query::Expr, ActiveValue, DatabaseTransaction, DeleteResult,
-    TransactionTrait,
+    entity::prelude::*,
+    sea_query::{Expr, Func, Query},
+    ActiveValue, DatabaseTransaction, DeleteResult, FromQueryResult, Order, PaginatorTrait,
+    QueryOrder, QuerySelect, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
-    common_add_id_and_data_group_filters, common_add_ordering,
+    audit_log::{log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    common_add_id_and_data_group_filters, common_add_ordering, common_get_ordering_column,
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
+    dispatch_note_type::DispatchNoteDirection,
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EntityAccessGuard, EveryGuard,
+        FetchOptions, Filter, OrderingOptions, Pagination, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase, QueryResults,
-    RowsDeleted,
+    trash::{check_not_deleted, restore, soft_delete, RestoreOptions},
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    QueryResults, QueryResultsHelperType, RowsDeleted,
 };
 
 use anyhow::{anyhow, Result};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum DispatchNoteStatus {
+    /// Editable, articles can be added and removed
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    /// Handed out, the note and its articles can no longer change
+    #[sea_orm(string_value = "issued")]
+    Issued,
+    /// Voided issued note, keeps its identifier
+    #[sea_orm(string_value = "cancelled")]
+    Cancelled,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "dispatch_note")]
-#[graphql(name = "DispatchNote")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub note_type: Option<i32>,
+    /// Assigned by the server from the data groups `dispatch_note_ident_tracker`
+    /// once the note is issued
     pub numerical_identifier: Option<i32>,
     pub issuing_date: Option<Date>,
     pub created_at: DateTimeWithTimeZone,
     pub d_group: i32,
+    /// Year the identifier was assigned in when numbering resets yearly
+    pub identifier_year: Option<i32>,
+    /// Identifier as printed, e.g. `2026/0042`
+    pub formatted_identifier: Option<String>,
+    /// Counter the identifier was taken from
+    pub id_ident_tracker: Option<i32>,
+    pub status: DispatchNoteStatus,
+    pub issued_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    pub cancellation_reason: Option<String>,
+    /// Issued or cancelled note this note corrects
+    pub corrects_dispatch_note: Option<i32>,
+    pub id_buyer: Option<i32>,
+    /// Overrides the buyers address on the printed note
+    pub delivery_address: Option<String>,
+    /// Set while the note is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -46,6 +89,22 @@ pub enum Relation {
     DataGroup,
     #[sea_orm(has_many = "super::dispatch_note_article::Entity")]
     DispatchNoteArticle,
+    #[sea_orm(
+        belongs_to = "super::dispatch_note_type::Entity",
+        from = "Column::NoteType",
+        to = "super::dispatch_note_type::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    DispatchNoteType,
+    #[sea_orm(
+        belongs_to = "super::buyer::Entity",
+        from = "Column::IdBuyer",
+        to = "super::buyer::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Buyer,
 }
 
 impl Related<super::data_group::Entity> for Entity {
@@ -60,6 +119,18 @@ impl Related<super::dispatch_note_article::Entity> for Entity {
     }
 }
 
+impl Related<super::dispatch_note_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DispatchNoteType.def()
+    }
+}
+
+impl Related<super::buyer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Buyer.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
@@ -68,8 +139,15 @@ pub enum DispatchNoteFields {
     NoteType,
     NumericalIdentifier,
     IssuingDate,
+    /// Direction of the notes type, filter value is `in` or `out`
+    Direction,
+    /// Filter value is `draft`, `issued` or `cancelled`
+    Status,
+    IdBuyer,
+    BuyerName,
 }
 
+/// Direction and buyer name are filtered and ordered through the type and buyer of the note
 impl From<DispatchNoteFields> for Column {
     fn from(fields: DispatchNoteFields) -> Self {
         match fields {
@@ -77,6 +155,10 @@ impl From<DispatchNoteFields> for Column {
             DispatchNoteFields::NoteType => Column::NoteType,
             DispatchNoteFields::NumericalIdentifier => Column::NumericalIdentifier,
             DispatchNoteFields::IssuingDate => Column::IssuingDate,
+            DispatchNoteFields::Direction => Column::NoteType,
+            DispatchNoteFields::Status => Column::Status,
+            DispatchNoteFields::IdBuyer => Column::IdBuyer,
+            DispatchNoteFields::BuyerName => Column::IdBuyer,
         }
     }
 }
@@ -94,20 +176,195 @@ impl GetEntityDataGroupColumnTrait<Column> for Entity {
     }
 }
 
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct DispatchNoteFlattened {
+    pub id: i32,
+    pub note_type: Option<i32>,
+    pub numerical_identifier: Option<i32>,
+    pub issuing_date: Option<Date>,
+    pub created_at: DateTimeWithTimeZone,
+    pub d_group: i32,
+    pub identifier_year: Option<i32>,
+    pub formatted_identifier: Option<String>,
+    pub id_ident_tracker: Option<i32>,
+    pub status: DispatchNoteStatus,
+    pub issued_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    pub cancellation_reason: Option<String>,
+    pub corrects_dispatch_note: Option<i32>,
+    pub id_buyer: Option<i32>,
+    pub delivery_address: Option<String>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+
+    pub name_buyer: Option<String>,
+    pub address_buyer: Option<String>,
+    pub contact_buyer: Option<String>,
+    pub created_at_buyer: Option<DateTimeWithTimeZone>,
+    pub d_group_buyer: Option<i32>,
+    pub deleted_at_buyer: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct DispatchNote {
+    pub id: i32,
+    pub note_type: Option<i32>,
+    /// Assigned by the server from the data groups `dispatch_note_ident_tracker`
+    /// once the note is issued
+    pub numerical_identifier: Option<i32>,
+    pub issuing_date: Option<Date>,
+    pub created_at: DateTimeWithTimeZone,
+    pub d_group: i32,
+    /// Year the identifier was assigned in when numbering resets yearly
+    pub identifier_year: Option<i32>,
+    /// Identifier as printed, e.g. `2026/0042`
+    pub formatted_identifier: Option<String>,
+    pub status: DispatchNoteStatus,
+    pub issued_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    pub cancellation_reason: Option<String>,
+    /// Issued or cancelled note this note corrects
+    pub corrects_dispatch_note: Option<i32>,
+    pub buyer: Option<super::buyer::Model>,
+    /// Overrides the buyers address on the printed note
+    pub delivery_address: Option<String>,
+    /// Set while the note is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl DispatchNote {
+    pub fn new(model: Model, buyer: Option<super::buyer::Model>) -> Self {
+        Self {
+            id: model.id,
+            note_type: model.note_type,
+            numerical_identifier: model.numerical_identifier,
+            issuing_date: model.issuing_date,
+            created_at: model.created_at,
+            d_group: model.d_group,
+            identifier_year: model.identifier_year,
+            formatted_identifier: model.formatted_identifier,
+            status: model.status,
+            issued_at: model.issued_at,
+            cancelled_at: model.cancelled_at,
+            cancellation_reason: model.cancellation_reason,
+            corrects_dispatch_note: model.corrects_dispatch_note,
+            buyer,
+            delivery_address: model.delivery_address,
+            deleted_at: model.deleted_at,
+        }
+    }
+
+    /// Address the goods are delivered to
+    pub fn shipping_address(&self) -> Option<&str> {
+        self.delivery_address.as_deref().or_else(|| {
+            self.buyer
+                .as_ref()
+                .and_then(|buyer| buyer.address.as_deref())
+        })
+    }
+}
+
+/// Builds the buyer from left joined buyer columns, `None` when the note has no buyer
+pub fn joined_buyer(
+    id: Option<i32>,
+    name: Option<String>,
+    address: Option<String>,
+    contact: Option<String>,
+    created_at: Option<DateTimeWithTimeZone>,
+    d_group: Option<i32>,
+    deleted_at: Option<DateTimeWithTimeZone>,
+) -> Option<super::buyer::Model> {
+    Some(super::buyer::Model {
+        id: id?,
+        name,
+        address,
+        contact,
+        created_at: created_at?,
+        d_group: d_group?,
+        deleted_at,
+    })
+}
+
+impl From<DispatchNoteFlattened> for DispatchNote {
+    fn from(flat: DispatchNoteFlattened) -> Self {
+        let buyer = joined_buyer(
+            flat.id_buyer,
+            flat.name_buyer,
+            flat.address_buyer,
+            flat.contact_buyer,
+            flat.created_at_buyer,
+            flat.d_group_buyer,
+            flat.deleted_at_buyer,
+        );
+
+        DispatchNote::new(
+            Model {
+                id: flat.id,
+                note_type: flat.note_type,
+                numerical_identifier: flat.numerical_identifier,
+                issuing_date: flat.issuing_date,
+                created_at: flat.created_at,
+                d_group: flat.d_group,
+                identifier_year: flat.identifier_year,
+                formatted_identifier: flat.formatted_identifier,
+                id_ident_tracker: flat.id_ident_tracker,
+                status: flat.status,
+                issued_at: flat.issued_at,
+                cancelled_at: flat.cancelled_at,
+                cancellation_reason: flat.cancellation_reason,
+                corrects_dispatch_note: flat.corrects_dispatch_note,
+                id_buyer: flat.id_buyer,
+                delivery_address: flat.delivery_address,
+                deleted_at: flat.deleted_at,
+            },
+            buyer,
+        )
+    }
+}
+
+impl From<QueryResultsHelperType<DispatchNoteFlattened>> for QueryResults<DispatchNote> {
+    fn from(inp: QueryResultsHelperType<DispatchNoteFlattened>) -> Self {
+        let (results, items_and_page_number, page, page_size) = inp;
+        Self {
+            results: results.into_iter().map(DispatchNote::from).collect(),
+            pagination: Pagination::new(page, page_size, items_and_page_number),
+        }
+    }
+}
+
 #[derive(InputObject)]
 pub struct DispatchNoteUpdateOptions {
     pub id: i32,
     pub note_type: Option<i32>,
-    pub numerical_identifier: Option<i32>,
     pub issuing_date: Option<DateTimeWithTimeZone>,
+    /// Null removes the buyer from the note
+    pub id_buyer: MaybeUndefined<i32>,
+    /// Empty address removes the override
+    pub delivery_address: Option<String>,
 }
 
 #[derive(InputObject, Serialize, Deserialize)]
 pub struct DispatchNoteInsertOptions {
     pub note_type: Option<i32>,
-    pub numerical_identifier: Option<i32>,
     pub issuing_date: Option<DateTimeWithTimeZone>,
     pub d_group: i32,
+    /// Issued or cancelled note of the same data group this note corrects,
+    /// the correction takes over its buyer unless another one is given
+    pub corrects_dispatch_note: Option<i32>,
+    pub id_buyer: Option<i32>,
+    pub delivery_address: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct DispatchNoteIssueOptions {
+    pub id: i32,
+    /// Defaults to the notes issuing date or today when the note has none
+    pub issuing_date: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(InputObject)]
+pub struct DispatchNoteCancelOptions {
+    pub id: i32,
+    pub reason: String,
 }
 
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
@@ -162,11 +419,11 @@ pub struct DispatchNoteFilterValue {
 
 #[async_trait]
 impl QueryDatabase for Entity {
-    type InnerQueryResultType = Model;
+    type InnerQueryResultType = DispatchNote;
 
     type QueryResultType = QueryResults<Self::InnerQueryResultType>;
 
-    type FetchModel = Model;
+    type FetchModel = DispatchNoteFlattened;
 
     type InsertOptions = DispatchNoteInsertOptions;
 
@@ -180,18 +437,75 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = DispatchNoteFilterValue;
 
+    fn get_deleted_at_column() -> Option<Column> {
+        Some(Column::DeletedAt)
+    }
+
+    fn get_query_with_deleted() -> Select<Self> {
+        // The type is only joined for ordering by its direction
+        Entity::find()
+            .left_join(super::dispatch_note_type::Entity)
+            .left_join(super::buyer::Entity)
+            .column_as(super::buyer::Column::Name, "name_buyer")
+            .column_as(super::buyer::Column::Address, "address_buyer")
+            .column_as(super::buyer::Column::Contact, "contact_buyer")
+            .column_as(super::buyer::Column::CreatedAt, "created_at_buyer")
+            .column_as(super::buyer::Column::DGroup, "d_group_buyer")
+            .column_as(super::buyer::Column::DeletedAt, "deleted_at_buyer")
+    }
+
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        Ok(Self::delete_by_id(options.id).exec(transaction).await?)
+        let dispatch_note = Entity::find_locked(transaction, options.id).await?;
+        dispatch_note.check_editable(transaction).await?;
+
+        soft_delete::<Self>(transaction, session_data, dispatch_note.id).await
     }
 
     fn add_ordering(
         query: Select<Self>,
         ordering_options: Option<OrderingOptions<Self::InputFields>>,
     ) -> Select<Self> {
-        common_add_ordering(query, ordering_options)
+        match ordering_options {
+            Some(OrderingOptions {
+                order,
+                order_by: DispatchNoteFields::BuyerName,
+            }) => query
+                .order_by(super::buyer::Column::Name, order.into())
+                .order_by(Column::Id, Order::Asc),
+            Some(OrderingOptions {
+                order,
+                order_by: DispatchNoteFields::Direction,
+            }) => query
+                .order_by(super::dispatch_note_type::Column::Direction, order.into())
+                .order_by(Column::Id, Order::Asc),
+            ordering_options => common_add_ordering(query, ordering_options),
+        }
+    }
+
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        match ordering_options {
+            Some(OrderingOptions {
+                order,
+                order_by: DispatchNoteFields::BuyerName,
+            }) => (
+                OrderingColumn::new(super::buyer::Column::Name),
+                (*order).into(),
+            ),
+            Some(OrderingOptions {
+                order,
+                order_by: DispatchNoteFields::Direction,
+            }) => (
+                OrderingColumn::new(super::dispatch_note_type::Column::Direction),
+                (*order).into(),
+            ),
+            ordering_options => common_get_ordering_column::<Self, _>(ordering_options),
+        }
     }
 
     fn add_id_and_data_group_filters(
@@ -235,7 +549,9 @@ impl QueryDatabase for Entity {
                     query.filter(column.between(val.0, val.1))
                 }
             }
-            DispatchNoteFields::NoteType | DispatchNoteFields::NumericalIdentifier => {
+            DispatchNoteFields::NoteType
+            | DispatchNoteFields::NumericalIdentifier
+            | DispatchNoteFields::IdBuyer => {
                 let val = match filter.value.value.get_number() {
                     Ok(val) => val,
                     Err(e) => {
@@ -255,65 +571,176 @@ impl QueryDatabase for Entity {
                 }
                 query
             }
+            DispatchNoteFields::Direction => {
+                let direction = match filter.value.value.trim().to_lowercase().as_str() {
+                    "in" => DispatchNoteDirection::In,
+                    "out" => DispatchNoteDirection::Out,
+                    _ => {
+                        error!(
+                            "Failed to parse filter direction value: {}",
+                            filter.value.value
+                        );
+                        return query;
+                    }
+                };
+                query.filter(
+                    column.in_subquery(
+                        Query::select()
+                            .column(super::dispatch_note_type::Column::Id)
+                            .from(super::dispatch_note_type::Entity)
+                            .and_where(super::dispatch_note_type::Column::Direction.eq(direction))
+                            .to_owned(),
+                    ),
+                )
+            }
+            DispatchNoteFields::Status => {
+                let status = match filter.value.value.trim().to_lowercase().as_str() {
+                    "draft" => DispatchNoteStatus::Draft,
+                    "issued" => DispatchNoteStatus::Issued,
+                    "cancelled" => DispatchNoteStatus::Cancelled,
+                    _ => {
+                        error!(
+                            "Failed to parse filter status value: {}",
+                            filter.value.value
+                        );
+                        return query;
+                    }
+                };
+                query.filter(column.eq(status))
+            }
+            DispatchNoteFields::BuyerName => query.filter(
+                Expr::expr(Func::lower(Expr::col((
+                    super::buyer::Entity,
+                    super::buyer::Column::Name,
+                ))))
+                .like(format!("%{}%", filter.value.value.trim().to_lowercase())),
+            ),
             DispatchNoteFields::Id => query,
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
+        let dispatch_note = Entity::find_locked(transaction, options.id).await?;
+        dispatch_note.check_editable(transaction).await?;
+
+        if let Some(note_type) = options.note_type {
+            // The identifier was taken from the tracker of the current type
+            if dispatch_note.numerical_identifier.is_some()
+                && dispatch_note.note_type != Some(note_type)
+            {
+                return Err(anyhow!(
+                    "The type of a dispatch note can't change once it has an identifier!"
+                ));
+            }
+            super::dispatch_note_type::Entity::find_for_data_group(
+                transaction,
+                note_type,
+                dispatch_note.d_group,
+            )
+            .await?;
+        }
+
+        if let MaybeUndefined::Value(id_buyer) = options.id_buyer {
+            super::buyer::Entity::find_for_data_group(transaction, id_buyer, dispatch_note.d_group)
+                .await?;
+        }
+
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             note_type: options
                 .note_type
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
-            numerical_identifier: options
-                .numerical_identifier
-                .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
             issuing_date: options.issuing_date.map_or(ActiveValue::NotSet, |val| {
                 ActiveValue::Set(Some(val.date_naive()))
             }),
+            id_buyer: match options.id_buyer {
+                MaybeUndefined::Undefined => ActiveValue::NotSet,
+                id_buyer => ActiveValue::Set(id_buyer.take()),
+            },
+            delivery_address: options.delivery_address.map_or(ActiveValue::NotSet, |val| {
+                ActiveValue::Set(Some(val).filter(|val| !val.trim().is_empty()))
+            }),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::update(model).exec(&transaction).await?;
-        transaction.commit().await?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &dispatch_note, &res).await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
+        if let Some(note_type) = options.note_type {
+            super::dispatch_note_type::Entity::find_for_data_group(
+                transaction,
+                note_type,
+                options.d_group,
+            )
+            .await?;
+        }
+
+        let corrected = match options.corrects_dispatch_note {
+            Some(corrects_dispatch_note) => {
+                let corrected = Entity::find_by_id(corrects_dispatch_note)
+                    .filter(Column::DGroup.eq(options.d_group))
+                    .filter(Column::DeletedAt.is_null())
+                    .one(transaction)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!("Corrected dispatch note not found in the data group!")
+                    })?;
+                if corrected.status == DispatchNoteStatus::Draft {
+                    return Err(anyhow!(
+                        "Only issued or cancelled dispatch notes can be corrected!"
+                    ));
+                }
+                Some(corrected)
+            }
+            None => None,
+        };
+
+        let (id_buyer, delivery_address) = match (options.id_buyer, corrected) {
+            (Some(id_buyer), _) => (
+                Some(
+                    super::buyer::Entity::find_for_data_group(
+                        transaction,
+                        id_buyer,
+                        options.d_group,
+                    )
+                    .await?
+                    .id,
+                ),
+                options.delivery_address,
+            ),
+            (None, Some(corrected)) => (
+                corrected.id_buyer,
+                options.delivery_address.or(corrected.delivery_address),
+            ),
+            (None, None) => (None, options.delivery_address),
+        };
+
         let model = ActiveModel {
             note_type: ActiveValue::Set(options.note_type),
-            numerical_identifier: ActiveValue::Set(options.numerical_identifier),
+            corrects_dispatch_note: ActiveValue::Set(options.corrects_dispatch_note),
+            id_buyer: ActiveValue::Set(id_buyer),
+            delivery_address: ActiveValue::Set(
+                delivery_address.filter(|address| !address.trim().is_empty()),
+            ),
             issuing_date: ActiveValue::Set(options.issuing_date.map(|d| d.date_naive())),
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
-
-        // WARN: As of time of writing seaorm does not support update queries as `SET c = c + 1`
-        // with update function
-        super::dispatch_note_ident_tracker::Entity::update_many()
-            .col_expr(
-                super::dispatch_note_ident_tracker::Column::Identifier,
-                Expr::add(
-                    super::dispatch_note_ident_tracker::Column::Identifier.into_expr(),
-                    1,
-                ),
-            )
-            .filter(super::dispatch_note_ident_tracker::Column::IdDataGroup.eq(options.d_group))
-            .exec(&transaction)
-            .await?;
-
-        transaction.commit().await?;
+        log_insert(transaction, session_data, &res).await?;
 
         Ok(res)
     }
@@ -333,10 +760,32 @@ impl DispatchNoteQuery {
             <Entity as QueryDatabase>::FetchIdType,
             DispatchNoteFilterValue,
         >,
-    ) -> Result<QueryResults<Model>> {
+    ) -> Result<QueryResults<DispatchNote>> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         Entity::fetch(db, options).await
     }
+
+    /// Cursor paged `dispatchNotes`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn dispatch_notes_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: FetchOptions<
+            DispatchNoteFields,
+            <Entity as QueryDatabase>::FetchIdType,
+            DispatchNoteFilterValue,
+        >,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<DispatchNote>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
+        })
+        .await
+    }
 }
 
 #[derive(Default)]
@@ -344,14 +793,24 @@ pub struct DispatchNoteMutation;
 
 #[Object]
 impl DispatchNoteMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_dispatch_note(
         &self,
         ctx: &Context<'_>,
         options: DispatchNoteInsertOptions,
-    ) -> Result<Model> {
+    ) -> Result<DispatchNote> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::DispatchNote,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -359,9 +818,19 @@ impl DispatchNoteMutation {
         &self,
         ctx: &Context<'_>,
         options: DispatchNoteUpdateOptions,
-    ) -> Result<Model> {
+    ) -> Result<DispatchNote> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::DispatchNote,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -371,7 +840,285 @@ impl DispatchNoteMutation {
         options: DeleteOptions,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::DispatchNote,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_dispatch_notes(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DispatchNoteInsertOptions>,
+    ) -> async_graphql::Result<Vec<DispatchNote>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::DispatchNote,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_dispatch_notes(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DispatchNoteUpdateOptions>,
+    ) -> async_graphql::Result<Vec<DispatchNote>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group),
+                AuditEntityType::DispatchNote,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_dispatch_notes(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::DispatchNote,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn restore_dispatch_note(
+        &self,
+        ctx: &Context<'_>,
+        options: RestoreOptions,
+    ) -> Result<DispatchNote> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let res = restore::<Entity>(&transaction, session_data, options.id).await?;
+        let res = Entity::with_buyer(&transaction, res).await?;
+
+        transaction.commit().await?;
+
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::DispatchNote,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
+    async fn issue_dispatch_note(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteIssueOptions,
+    ) -> Result<DispatchNote> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let dispatch_note = Entity::find_locked(&transaction, options.id).await?;
+        if dispatch_note.status != DispatchNoteStatus::Draft {
+            return Err(anyhow!("Only draft dispatch notes can be issued!"));
+        }
+
+        let articles = super::dispatch_note_article::Entity::find()
+            .filter(super::dispatch_note_article::Column::IdDispatchNote.eq(dispatch_note.id))
+            .count(&transaction)
+            .await?;
+        if articles == 0 {
+            return Err(anyhow!("Dispatch note without articles can't be issued!"));
+        }
+
+        let now: DateTimeWithTimeZone = chrono::Local::now().into();
+        let issuing_date = options
+            .issuing_date
+            .map(|date| date.date_naive())
+            .or(dispatch_note.issuing_date)
+            .unwrap_or_else(|| now.date_naive());
+
+        let mut model = ActiveModel {
+            id: ActiveValue::Set(dispatch_note.id),
+            status: ActiveValue::Set(DispatchNoteStatus::Issued),
+            issued_at: ActiveValue::Set(Some(now)),
+            issuing_date: ActiveValue::Set(Some(issuing_date)),
+            ..Default::default()
+        };
+
+        // Drafts numbered on creation, before numbering moved to issuing, keep their identifier
+        if dispatch_note.numerical_identifier.is_none() {
+            let note_type = dispatch_note
+                .find_related(super::dispatch_note_type::Entity)
+                .one(&transaction)
+                .await?;
+            let identifier = super::dispatch_note_ident_tracker::Entity::assign_identifier(
+                &transaction,
+                dispatch_note.d_group,
+                note_type.as_ref(),
+            )
+            .await?;
+
+            model.numerical_identifier = ActiveValue::Set(Some(identifier.numerical_identifier));
+            model.identifier_year = ActiveValue::Set(identifier.identifier_year);
+            model.formatted_identifier = ActiveValue::Set(Some(identifier.formatted_identifier));
+            model.id_ident_tracker = ActiveValue::Set(Some(identifier.id_ident_tracker));
+        }
+
+        let res = model.update(&transaction).await?;
+        log_update(&transaction, session_data, &dispatch_note, &res).await?;
+        let res = Entity::with_buyer(&transaction, res).await?;
+
+        transaction.commit().await?;
+
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::DispatchNote,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn cancel_dispatch_note(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteCancelOptions,
+    ) -> Result<DispatchNote> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let reason = options.reason.trim();
+        if reason.is_empty() {
+            return Err(anyhow!("Cancellation reason is required!"));
+        }
+
+        let transaction = db.begin().await?;
+
+        let dispatch_note = Entity::find_locked(&transaction, options.id).await?;
+        if dispatch_note.status != DispatchNoteStatus::Issued {
+            return Err(anyhow!("Only issued dispatch notes can be cancelled!"));
+        }
+
+        let res = ActiveModel {
+            id: ActiveValue::Set(dispatch_note.id),
+            status: ActiveValue::Set(DispatchNoteStatus::Cancelled),
+            cancelled_at: ActiveValue::Set(Some(chrono::Local::now().into())),
+            cancellation_reason: ActiveValue::Set(Some(reason.to_string())),
+            ..Default::default()
+        }
+        .update(&transaction)
+        .await?;
+        log_update(&transaction, session_data, &dispatch_note, &res).await?;
+        let res = Entity::with_buyer(&transaction, res).await?;
+
+        transaction.commit().await?;
+
+        publish(
+            ctx,
+            Some(res.d_group),
+            AuditEntityType::DispatchNote,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
+    }
+}
+
+impl Entity {
+    /// Locks the note so its status can't change until `transaction` ends
+    pub async fn find_locked(transaction: &DatabaseTransaction, id: i32) -> Result<Model> {
+        Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Dispatch note not found"))
+    }
+
+    /// Same as `find_locked` but only finds the note if it belongs to the data group
+    pub async fn find_locked_in_data_group(
+        transaction: &DatabaseTransaction,
+        id: i32,
+        d_group: i32,
+    ) -> Result<Model> {
+        Entity::find_by_id(id)
+            .filter(Column::DGroup.eq(d_group))
+            .lock_exclusive()
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Dispatch note not found in the data group!"))
+    }
+
+    pub async fn with_buyer(
+        transaction: &DatabaseTransaction,
+        model: Model,
+    ) -> Result<DispatchNote, DbErr> {
+        let buyer = model
+            .find_related(super::buyer::Entity)
+            .one(transaction)
+            .await?;
+        Ok(DispatchNote::new(model, buyer))
     }
 }
 
@@ -386,3 +1133,141 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        check_not_deleted(self.deleted_at)?;
+        match self.status {
+            DispatchNoteStatus::Draft => Ok(()),
+            DispatchNoteStatus::Issued => Err(anyhow!(
+                "Dispatch note {} is issued and can no longer be changed!",
+                self.formatted_identifier.as_deref().unwrap_or_default()
+            )),
+            DispatchNoteStatus::Cancelled => Err(anyhow!(
+                "Dispatch note {} is cancelled and can no longer be changed!",
+                self.formatted_identifier.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::DispatchNote;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        seaorm_models::{connection::ConnectionArgs, graphql_schema::Ordering},
+        test_database::TestDatabase,
+    };
+
+    async fn ordered_ids(
+        test_db: &TestDatabase,
+        d_group: i32,
+        order_by: DispatchNoteFields,
+    ) -> Vec<i32> {
+        let connection = Entity::fetch_connection(
+            &test_db.db,
+            FetchOptions {
+                id: None,
+                page_size: None,
+                page: None,
+                ordering: Some(OrderingOptions {
+                    order: Ordering::Asc,
+                    order_by,
+                }),
+                filters: None,
+                d_group,
+                as_of: None,
+            },
+            ConnectionArgs {
+                after: None,
+                before: None,
+                first: None,
+                last: None,
+                with_total_count: false,
+            },
+        )
+        .await
+        .unwrap();
+
+        connection
+            .edges
+            .into_iter()
+            .map(|edge| edge.node.id)
+            .collect()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn orders_by_direction_and_buyer_name() {
+        let test_db = TestDatabase::new().await;
+        let mbe_group = test_db.mbe_group().await;
+        let d_group = test_db.data_group(mbe_group).await;
+
+        let type_out = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note_type (id_mbe_group, name, direction) VALUES ({}, 'Out', 'out')",
+                mbe_group
+            ))
+            .await;
+        let type_in = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note_type (id_mbe_group, name, direction) VALUES ({}, 'In', 'in')",
+                mbe_group
+            ))
+            .await;
+        let zeta = test_db
+            .insert(&format!(
+                "INSERT INTO buyer (d_group, name) VALUES ({}, 'Zeta')",
+                d_group
+            ))
+            .await;
+        let alpha = test_db
+            .insert(&format!(
+                "INSERT INTO buyer (d_group, name) VALUES ({}, 'Alpha')",
+                d_group
+            ))
+            .await;
+
+        // Type and buyer ids are ordered the other way around than direction and name
+        let first = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note (d_group, note_type, id_buyer) VALUES ({}, {}, {})",
+                d_group, type_out, zeta
+            ))
+            .await;
+        let second = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note (d_group, note_type, id_buyer) VALUES ({}, {}, {})",
+                d_group, type_in, alpha
+            ))
+            .await;
+        let without = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note (d_group) VALUES ({})",
+                d_group
+            ))
+            .await;
+
+        for order_by in [DispatchNoteFields::Direction, DispatchNoteFields::BuyerName] {
+            assert_eq!(
+                ordered_ids(&test_db, d_group, order_by).await,
+                vec![second, first, without]
+            );
+        }
+
+        test_db.close().await;
+    }
+}
@@ -1,24 +1,34 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{
+    ComplexObject, Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject,
+};
 use async_trait::async_trait;
 
 use log::error;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
-    ActiveValue, DatabaseTransaction, DeleteResult, FromQueryResult, Order, QueryOrder,
-    QuerySelect, TransactionTrait,
+    ActiveValue, DatabaseTransaction, DeleteResult, FromQueryResult, JoinType, Order, QueryOrder,
+    QuerySelect,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
-    dispatch_note::{Comparator, DispatchNoteFilterValue, DispatchNoteFilterValueTrait},
+    audit_log::{log_delete, log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
+    dispatch_note::{
+        joined_buyer, Comparator, DispatchNote, DispatchNoteFilterValue,
+        DispatchNoteFilterValueTrait, DispatchNoteStatus,
+    },
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions, Pagination,
-        QueryResults, UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EveryGuard, FetchOptions, Filter,
+        OrderingOptions, Pagination, QueryResults, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    unit_conversion::convert,
+    weight_type::{WeightTypeCache, WeightTypeDimension},
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
     QueryResultsHelperType, RowsDeleted,
 };
 
@@ -144,6 +154,7 @@ pub struct DispatchNoteArticleUpdateOptions {
     pub quantity: Option<f64>,
 }
 
+#[allow(dead_code)]
 #[derive(InputObject)]
 pub struct DispatchNoteArticleDeleteOptions {
     pub id_dispatch_note: i32,
@@ -157,6 +168,7 @@ pub struct DispatchNoteArticleFlattened {
     pub id_dispatch_note: i32,
     pub quantity: f64,
     pub created_at: DateTimeWithTimeZone,
+    #[allow(dead_code)]
     pub d_group: i32,
 
     pub id_article: i32,
@@ -164,12 +176,31 @@ pub struct DispatchNoteArticleFlattened {
     pub description_article: Option<String>,
     pub d_group_article: i32,
     pub created_at_article: DateTimeWithTimeZone,
+    pub deleted_at_article: Option<DateTimeWithTimeZone>,
 
     pub note_type_dispatch_note: Option<i32>,
     pub numerical_identifier_dispatch_note: Option<i32>,
     pub issuing_date_dispatch_note: Option<Date>,
     pub created_at_dispatch_note: DateTimeWithTimeZone,
     pub d_group_dispatch_note: i32,
+    pub identifier_year_dispatch_note: Option<i32>,
+    pub formatted_identifier_dispatch_note: Option<String>,
+    pub id_ident_tracker_dispatch_note: Option<i32>,
+    pub status_dispatch_note: DispatchNoteStatus,
+    pub issued_at_dispatch_note: Option<DateTimeWithTimeZone>,
+    pub cancelled_at_dispatch_note: Option<DateTimeWithTimeZone>,
+    pub cancellation_reason_dispatch_note: Option<String>,
+    pub corrects_dispatch_note_dispatch_note: Option<i32>,
+    pub id_buyer_dispatch_note: Option<i32>,
+    pub delivery_address_dispatch_note: Option<String>,
+    pub deleted_at_dispatch_note: Option<DateTimeWithTimeZone>,
+
+    pub name_buyer: Option<String>,
+    pub address_buyer: Option<String>,
+    pub contact_buyer: Option<String>,
+    pub created_at_buyer: Option<DateTimeWithTimeZone>,
+    pub d_group_buyer: Option<i32>,
+    pub deleted_at_buyer: Option<DateTimeWithTimeZone>,
 
     pub id_d_group: i32,
     pub name_d_group: String,
@@ -181,12 +212,15 @@ pub struct DispatchNoteArticleFlattened {
     pub unit_short: String,
     pub unit: String,
     pub created_at_weight_type: DateTimeWithTimeZone,
+    pub conversion_factor: Option<f64>,
+    pub dimension: WeightTypeDimension,
 }
 
 #[derive(Debug, SimpleObject)]
+#[graphql(complex)]
 pub struct DispatchNoteArticle {
     pub id: i32,
-    pub dispatch_note: super::dispatch_note::Model,
+    pub dispatch_note: DispatchNote,
     pub article: super::article::Model,
     pub weight_type: super::weight_type::Model,
     pub quantity: f64,
@@ -194,6 +228,38 @@ pub struct DispatchNoteArticle {
     pub d_group: super::data_group::Model,
 }
 
+#[ComplexObject]
+impl DispatchNoteArticle {
+    /// Quantity converted into another weight type of the same dimension,
+    /// fails with `INCOMPATIBLE_DIMENSIONS` or `MISSING_CONVERSION_FACTOR`
+    async fn quantity_in(
+        &self,
+        ctx: &Context<'_>,
+        id_weight_type: i32,
+    ) -> async_graphql::Result<f64> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        // Subscriptions share their data for the whole connection, they look the target up
+        let target = match ctx.data_opt::<WeightTypeCache>() {
+            Some(cache) => {
+                cache
+                    .find_for_mbe_group(db.get_ref(), id_weight_type, self.d_group.id_mbe_group)
+                    .await?
+            }
+            None => {
+                super::weight_type::Entity::find_for_mbe_group(
+                    db.get_ref(),
+                    id_weight_type,
+                    self.d_group.id_mbe_group,
+                )
+                .await?
+            }
+        };
+
+        convert(self.quantity, &self.weight_type, &target).map_err(|e| e.extend())
+    }
+}
+
 impl From<QueryResultsHelperType<DispatchNoteArticleFlattened>>
     for QueryResults<DispatchNoteArticle>
 {
@@ -206,26 +272,51 @@ impl From<QueryResultsHelperType<DispatchNoteArticleFlattened>>
                     id: flat.id,
                     quantity: flat.quantity,
                     created_at: flat.created_at,
-                    dispatch_note: super::dispatch_note::Model {
-                        id: flat.id_dispatch_note,
-                        note_type: flat.note_type_dispatch_note,
-                        numerical_identifier: flat.numerical_identifier_dispatch_note,
-                        issuing_date: flat.issuing_date_dispatch_note,
-                        created_at: flat.created_at_dispatch_note,
-                        d_group: flat.d_group_dispatch_note,
-                    },
+                    dispatch_note: DispatchNote::new(
+                        super::dispatch_note::Model {
+                            id: flat.id_dispatch_note,
+                            note_type: flat.note_type_dispatch_note,
+                            numerical_identifier: flat.numerical_identifier_dispatch_note,
+                            issuing_date: flat.issuing_date_dispatch_note,
+                            created_at: flat.created_at_dispatch_note,
+                            d_group: flat.d_group_dispatch_note,
+                            identifier_year: flat.identifier_year_dispatch_note,
+                            formatted_identifier: flat.formatted_identifier_dispatch_note,
+                            id_ident_tracker: flat.id_ident_tracker_dispatch_note,
+                            status: flat.status_dispatch_note,
+                            issued_at: flat.issued_at_dispatch_note,
+                            cancelled_at: flat.cancelled_at_dispatch_note,
+                            cancellation_reason: flat.cancellation_reason_dispatch_note,
+                            corrects_dispatch_note: flat.corrects_dispatch_note_dispatch_note,
+                            id_buyer: flat.id_buyer_dispatch_note,
+                            delivery_address: flat.delivery_address_dispatch_note,
+                            deleted_at: flat.deleted_at_dispatch_note,
+                        },
+                        joined_buyer(
+                            flat.id_buyer_dispatch_note,
+                            flat.name_buyer,
+                            flat.address_buyer,
+                            flat.contact_buyer,
+                            flat.created_at_buyer,
+                            flat.d_group_buyer,
+                            flat.deleted_at_buyer,
+                        ),
+                    ),
                     article: super::article::Model {
                         id: flat.id_article,
                         name: flat.name_article,
                         description: flat.description_article,
                         created_at: flat.created_at_article,
                         d_group: flat.d_group_article,
+                        deleted_at: flat.deleted_at_article,
                     },
                     weight_type: super::weight_type::Model {
                         id: flat.id_weight_type,
                         unit_short: flat.unit_short,
                         unit: flat.unit,
                         created_at: flat.created_at_weight_type,
+                        conversion_factor: flat.conversion_factor,
+                        dimension: flat.dimension,
                     },
                     d_group: super::data_group::Model {
                         id: flat.id_d_group,
@@ -236,12 +327,7 @@ impl From<QueryResultsHelperType<DispatchNoteArticleFlattened>>
                     },
                 })
                 .collect(),
-            pagination: Pagination {
-                page: page.page,
-                page_size: page_size.0,
-                total_items: items_and_page_number.number_of_items,
-                total_pages: items_and_page_number.number_of_pages,
-            },
+            pagination: Pagination::new(page, page_size, items_and_page_number),
         }
     }
 }
@@ -266,17 +352,23 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = DispatchNoteFilterValue;
 
+    /// Articles of deleted dispatch notes are hidden with them
     fn get_query() -> Select<Self> {
         Entity::find()
             .inner_join(super::article::Entity)
             .inner_join(super::dispatch_note::Entity)
             .inner_join(super::data_group::Entity)
             .inner_join(super::weight_type::Entity)
+            .join(
+                JoinType::LeftJoin,
+                super::dispatch_note::Relation::Buyer.def(),
+            )
             .column_as(super::article::Column::Id, "id_article")
             .column_as(super::article::Column::Name, "name_article")
             .column_as(super::article::Column::Description, "description_article")
             .column_as(super::article::Column::DGroup, "d_group_article")
             .column_as(super::article::Column::CreatedAt, "created_at_article")
+            .column_as(super::article::Column::DeletedAt, "deleted_at_article")
             .column_as(super::dispatch_note::Column::Id, "id_dispatch_note")
             .column_as(
                 super::dispatch_note::Column::NoteType,
@@ -298,6 +390,53 @@ impl QueryDatabase for Entity {
                 super::dispatch_note::Column::CreatedAt,
                 "created_at_dispatch_note",
             )
+            .column_as(
+                super::dispatch_note::Column::IdentifierYear,
+                "identifier_year_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::FormattedIdentifier,
+                "formatted_identifier_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::IdIdentTracker,
+                "id_ident_tracker_dispatch_note",
+            )
+            .column_as(super::dispatch_note::Column::Status, "status_dispatch_note")
+            .column_as(
+                super::dispatch_note::Column::IssuedAt,
+                "issued_at_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::CancelledAt,
+                "cancelled_at_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::CancellationReason,
+                "cancellation_reason_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::CorrectsDispatchNote,
+                "corrects_dispatch_note_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::IdBuyer,
+                "id_buyer_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::DeliveryAddress,
+                "delivery_address_dispatch_note",
+            )
+            .column_as(
+                super::dispatch_note::Column::DeletedAt,
+                "deleted_at_dispatch_note",
+            )
+            .column_as(super::buyer::Column::Name, "name_buyer")
+            .column_as(super::buyer::Column::Address, "address_buyer")
+            .column_as(super::buyer::Column::Contact, "contact_buyer")
+            .column_as(super::buyer::Column::CreatedAt, "created_at_buyer")
+            .column_as(super::buyer::Column::DGroup, "d_group_buyer")
+            .column_as(super::buyer::Column::DeletedAt, "deleted_at_buyer")
             .column_as(super::data_group::Column::Id, "id_d_group")
             .column_as(super::data_group::Column::Name, "name_d_group")
             .column_as(
@@ -313,12 +452,25 @@ impl QueryDatabase for Entity {
                 super::weight_type::Column::CreatedAt,
                 "created_at_weight_type",
             )
+            .column_as(
+                super::weight_type::Column::ConversionFactor,
+                "conversion_factor",
+            )
+            .column_as(super::weight_type::Column::Dimension, "dimension")
+            .filter(super::dispatch_note::Column::DeletedAt.is_null())
     }
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
+        if let Some(dispatch_note_article) = Entity::find_by_id(options.id).one(transaction).await?
+        {
+            dispatch_note_article.check_editable(transaction).await?;
+            log_delete(transaction, session_data, &dispatch_note_article).await?;
+        }
+
         Ok(Self::delete_by_id(options.id).exec(transaction).await?)
     }
 
@@ -347,6 +499,27 @@ impl QueryDatabase for Entity {
         query
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        match ordering_options {
+            Some(options) => {
+                let column = match options.order_by {
+                    Self::InputFields::ArticleName => {
+                        OrderingColumn::new(super::article::Column::Name)
+                    }
+                    Self::InputFields::ArticleDescription => {
+                        OrderingColumn::new(super::article::Column::Description)
+                    }
+                    Self::InputFields::WeightType => OrderingColumn::new(Column::WeightType),
+                    Self::InputFields::Quantity => OrderingColumn::new(Column::Quantity),
+                };
+                (column, options.order.into())
+            }
+            None => (OrderingColumn::new(super::article::Column::Id), Order::Asc),
+        }
+    }
+
     fn add_id_and_data_group_filters(
         mut query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType, Self::FilterValueType>,
@@ -420,11 +593,30 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
-        let transaction = db.begin().await?;
+    ) -> Result<Model> {
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("DispatchNoteArticle not found"))?;
+        before.check_editable(transaction).await?;
+        if let Some(id_dispatch_note) = options.id_dispatch_note {
+            check_dispatch_note_editable(transaction, id_dispatch_note, before.d_group).await?;
+        }
+        if let Some(id_article) = options.id_article {
+            find_article(transaction, id_article, before.d_group).await?;
+        }
+        if let Some(weight_type) = options.weight_type {
+            super::weight_type::Entity::find_for_data_group(
+                transaction,
+                weight_type,
+                before.d_group,
+            )
+            .await?;
+        }
 
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
@@ -442,41 +634,17 @@ impl QueryDatabase for Entity {
                 .map_or(ActiveValue::NotSet, ActiveValue::Set),
             ..Default::default()
         };
-        let res = Entity::update(model).exec(&transaction).await?;
-
-        transaction.commit().await?;
-
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<
-                DispatchNoteArticleFields,
-                Option<DispatchNoteArticleIds>,
-                Self::FilterValueType,
-            > {
-                id: Some(DispatchNoteArticleIds {
-                    id_dispatch_note: Some(res.id_dispatch_note),
-                    id_article: Some(res.id_article),
-                }),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
-        )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Updated DispatchNoteArticle not found"))?
-        .1)
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
+
+        Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id_dispatch_note: ActiveValue::Set(options.id_dispatch_note),
             id_article: ActiveValue::Set(options.id_article),
@@ -485,37 +653,21 @@ impl QueryDatabase for Entity {
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
-        let transaction = db.begin().await?;
-        let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+        check_dispatch_note_editable(transaction, options.id_dispatch_note, options.d_group)
             .await?;
-        transaction.commit().await?;
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<
-                DispatchNoteArticleFields,
-                Option<DispatchNoteArticleIds>,
-                Self::FilterValueType,
-            > {
-                id: Some(DispatchNoteArticleIds {
-                    id_dispatch_note: Some(res.id_dispatch_note),
-                    id_article: Some(res.id_article),
-                    // d_group: res.d_group,
-                }),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
+        find_article(transaction, options.id_article, options.d_group).await?;
+        super::weight_type::Entity::find_for_data_group(
+            transaction,
+            options.weight_type,
+            options.d_group,
         )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Inserted DispatchNoteArticle not found"))?
-        .1)
+        .await?;
+        let res = Entity::insert(model)
+            .exec_with_returning(transaction)
+            .await?;
+        log_insert(transaction, session_data, &res).await?;
+
+        Ok(res)
     }
 }
 
@@ -537,6 +689,28 @@ impl DispatchNoteArticleQuery {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         Entity::fetch(db, options).await
     }
+
+    /// Cursor paged `dispatchNoteArticles`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn dispatch_note_articles_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: FetchOptions<
+            DispatchNoteArticleFields,
+            Option<DispatchNoteArticleIds>,
+            DispatchNoteFilterValue,
+        >,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<DispatchNoteArticle>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
+        })
+        .await
+    }
 }
 
 #[derive(Default)]
@@ -544,14 +718,24 @@ pub struct DispatchNoteArticleMutation;
 
 #[Object]
 impl DispatchNoteArticleMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_dispatch_note_article(
         &self,
         ctx: &Context<'_>,
         options: DispatchNoteArticleInsertOptions,
     ) -> Result<DispatchNoteArticle> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::DispatchNoteArticle,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -561,7 +745,17 @@ impl DispatchNoteArticleMutation {
         options: DispatchNoteArticleUpdateOptions,
     ) -> Result<DispatchNoteArticle> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::DispatchNoteArticle,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
@@ -571,7 +765,101 @@ impl DispatchNoteArticleMutation {
         options: DeleteOptions,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::DispatchNoteArticle,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_dispatch_note_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DispatchNoteArticleInsertOptions>,
+    ) -> async_graphql::Result<Vec<DispatchNoteArticle>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::DispatchNoteArticle,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_dispatch_note_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DispatchNoteArticleUpdateOptions>,
+    ) -> async_graphql::Result<Vec<DispatchNoteArticle>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::DispatchNoteArticle,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_dispatch_note_articles(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::DispatchNoteArticle,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
     }
 }
 
@@ -586,3 +874,49 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+/// Articles can only change while their dispatch note is a draft,
+/// the note stays locked until `transaction` ends so it can't be issued in the meantime
+async fn check_dispatch_note_editable(
+    transaction: &DatabaseTransaction,
+    id_dispatch_note: i32,
+    d_group: i32,
+) -> Result<()> {
+    super::dispatch_note::Entity::find_locked_in_data_group(transaction, id_dispatch_note, d_group)
+        .await?
+        .check_editable(transaction)
+        .await
+}
+
+/// Finds the article only if it belongs to the data group
+async fn find_article(
+    transaction: &DatabaseTransaction,
+    id_article: i32,
+    d_group: i32,
+) -> Result<super::article::Model> {
+    super::article::Entity::find_by_id(id_article)
+        .filter(super::article::Column::DGroup.eq(d_group))
+        .filter(super::article::Column::DeletedAt.is_null())
+        .one(transaction)
+        .await?
+        .ok_or_else(|| anyhow!("Article not found in the data group!"))
+}
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, transaction: &DatabaseTransaction) -> Result<()> {
+        check_dispatch_note_editable(transaction, self.id_dispatch_note, self.d_group).await
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::DispatchNoteArticle;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
@@ -1,19 +1,38 @@
 use anyhow::{anyhow, Result};
-use async_graphql::{Context, InputObject, Object, SimpleObject};
-use sea_orm::{entity::prelude::*, ActiveValue, TransactionTrait};
+use async_graphql::{ComplexObject, Context, InputObject, Object, SimpleObject};
+use chrono::{Datelike, Local};
+use sea_orm::{
+    entity::prelude::*, sea_query::OnConflict, ActiveValue, DatabaseTransaction, QuerySelect,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::SeaOrmPool;
+use crate::{user_models::mbe_group_members::MbeGroupRole, SeaOrmPool};
 
-use super::graphql_schema::DataGroupAccessGuard;
+use super::{
+    audit_log::{log_update, AuditEntityType, Auditable},
+    graphql_schema::{extract_session, is_data_group_owner, DataGroupAccessGuard},
+};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "dispatch_note_ident_tracker")]
+#[graphql(complex)]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
+    pub id: i32,
     pub id_data_group: i32,
+    /// Set on counters of dispatch note types with their own numbering,
+    /// the data groups shared counter has no type
+    pub id_dispatch_note_type: Option<i32>,
+    /// Identifier the next dispatch note will receive
     pub identifier: i32,
     pub created_at: DateTimeWithTimeZone,
+    /// Numbering starts from 1 in every calendar year
+    pub reset_yearly: bool,
+    /// Year `identifier` belongs to, only kept while `reset_yearly` is enabled
+    pub identifier_year: Option<i32>,
+    /// Minimal number of digits, shorter identifiers are padded with zeros
+    pub number_width: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,6 +45,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     DataGroup,
+    #[sea_orm(
+        belongs_to = "super::dispatch_note_type::Entity",
+        from = "Column::IdDispatchNoteType",
+        to = "super::dispatch_note_type::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    DispatchNoteType,
 }
 
 impl Related<super::data_group::Entity> for Entity {
@@ -34,17 +61,150 @@ impl Related<super::data_group::Entity> for Entity {
     }
 }
 
+impl Related<super::dispatch_note_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DispatchNoteType.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
+/// Identifier assigned to a dispatch note when it is issued
+pub struct AssignedIdentifier {
+    pub id_ident_tracker: i32,
+    pub numerical_identifier: i32,
+    pub identifier_year: Option<i32>,
+    pub formatted_identifier: String,
+}
+
+impl Model {
+    /// Formats identifiers as `0042` or `2026/0042` when numbering resets yearly
+    pub fn format_identifier(&self, identifier: i32, year: Option<i32>) -> String {
+        let width = self.number_width as usize;
+        match year {
+            Some(year) => format!("{}/{:0width$}", year, identifier, width = width),
+            None => format!("{:0width$}", identifier, width = width),
+        }
+    }
+
+    /// Identifier and year the next dispatch note receives when numbered in `year`
+    fn next_in(&self, year: i32) -> (i32, Option<i32>) {
+        if !self.reset_yearly {
+            (self.identifier, None)
+        } else if self
+            .identifier_year
+            .is_none_or(|identifier_year| identifier_year < year)
+        {
+            (1, Some(year))
+        } else {
+            (self.identifier, self.identifier_year)
+        }
+    }
+
+    /// Whether setting `identifier` in `year` numbers notes below the next one,
+    /// yearly numbering compares against the restart at 1 in a new year
+    fn moves_back(&self, identifier: i32, year: i32) -> bool {
+        identifier < self.next_in(year).0
+    }
+}
+
+#[ComplexObject]
+impl Model {
+    async fn next_formatted_identifier(&self) -> String {
+        let (identifier, year) = self.next_in(Local::now().year());
+        self.format_identifier(identifier, year)
+    }
+}
+
+impl Entity {
+    fn find_counter(id_data_group: i32, id_dispatch_note_type: Option<i32>) -> Select<Self> {
+        let query = Entity::find().filter(Column::IdDataGroup.eq(id_data_group));
+        match id_dispatch_note_type {
+            Some(id_dispatch_note_type) => {
+                query.filter(Column::IdDispatchNoteType.eq(id_dispatch_note_type))
+            }
+            None => query.filter(Column::IdDispatchNoteType.is_null()),
+        }
+    }
+
+    /// Locks the counter row until `transaction` ends,
+    /// counters of dispatch note types are created on first use
+    async fn find_locked(
+        transaction: &DatabaseTransaction,
+        id_data_group: i32,
+        id_dispatch_note_type: Option<i32>,
+    ) -> Result<Model> {
+        if id_dispatch_note_type.is_some() {
+            Entity::insert(ActiveModel {
+                id_data_group: ActiveValue::Set(id_data_group),
+                id_dispatch_note_type: ActiveValue::Set(id_dispatch_note_type),
+                ..Default::default()
+            })
+            .on_conflict(
+                OnConflict::columns([Column::IdDataGroup, Column::IdDispatchNoteType])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(transaction)
+            .await?;
+        }
+
+        Self::find_counter(id_data_group, id_dispatch_note_type)
+            .lock_exclusive()
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("DispatchNoteIdentTracker not found"))
+    }
+
+    /// Takes the next identifier from the counter the dispatch note type is numbered by,
+    /// concurrently issued notes wait on the row lock so no two notes get the same identifier
+    pub async fn assign_identifier(
+        transaction: &DatabaseTransaction,
+        id_data_group: i32,
+        note_type: Option<&super::dispatch_note_type::Model>,
+    ) -> Result<AssignedIdentifier> {
+        let id_dispatch_note_type = note_type
+            .filter(|note_type| note_type.own_numbering)
+            .map(|note_type| note_type.id);
+
+        let tracker = Self::find_locked(transaction, id_data_group, id_dispatch_note_type).await?;
+        let (numerical_identifier, identifier_year) = tracker.next_in(Local::now().year());
+        let formatted_identifier = tracker.format_identifier(numerical_identifier, identifier_year);
+
+        ActiveModel {
+            id: ActiveValue::Set(tracker.id),
+            identifier: ActiveValue::Set(numerical_identifier + 1),
+            identifier_year: ActiveValue::Set(identifier_year),
+            ..Default::default()
+        }
+        .update(transaction)
+        .await?;
+
+        Ok(AssignedIdentifier {
+            id_ident_tracker: tracker.id,
+            numerical_identifier,
+            identifier_year,
+            formatted_identifier,
+        })
+    }
+}
+
 #[derive(InputObject)]
 struct DispatchNoteIdentFetchOptions {
     id_data_group: i32,
+    /// Counter of a dispatch note type with its own numbering
+    id_dispatch_note_type: Option<i32>,
 }
 
 #[derive(InputObject)]
 struct DispatchNoteIdentUpdateOptions {
     id_data_group: i32,
-    identifier: i32,
+    id_dispatch_note_type: Option<i32>,
+    identifier: Option<i32>,
+    /// Allows moving the identifier backwards, only the owner of the mbe group can override
+    override_backwards: Option<bool>,
+    reset_yearly: Option<bool>,
+    number_width: Option<i32>,
 }
 
 #[derive(Default)]
@@ -52,6 +212,7 @@ pub struct DispatchNoteIdentTrackerQuery;
 
 #[Object]
 impl DispatchNoteIdentTrackerQuery {
+    #[graphql(guard = "DataGroupAccessGuard::new(options.id_data_group)")]
     async fn dispatch_note_ident(
         &self,
         ctx: &Context<'_>,
@@ -61,8 +222,7 @@ impl DispatchNoteIdentTrackerQuery {
 
         let transaction = db.begin().await?;
 
-        let res = Entity::find()
-            .filter(Column::IdDataGroup.eq(options.id_data_group))
+        let res = Entity::find_counter(options.id_data_group, options.id_dispatch_note_type)
             .one(&transaction)
             .await?
             .ok_or_else(|| anyhow!("DispatchNoteIdentTracker not found"))?;
@@ -78,26 +238,324 @@ pub struct DispatchNoteIdentTrackerMutation;
 
 #[Object]
 impl DispatchNoteIdentTrackerMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.id_data_group)")]
+    #[graphql(
+        guard = "DataGroupAccessGuard::with_role(options.id_data_group, MbeGroupRole::Admin)"
+    )]
     async fn update_dispatch_note_ident(
         &self,
         ctx: &Context<'_>,
         options: DispatchNoteIdentUpdateOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        if let Some(number_width) = options.number_width {
+            if !(1..=12).contains(&number_width) {
+                return Err(anyhow!("Number width must be between 1 and 12!"));
+            }
+        }
+
+        let transaction = db.begin().await?;
+
+        if let Some(id_dispatch_note_type) = options.id_dispatch_note_type {
+            super::dispatch_note_type::Entity::find_for_data_group(
+                &transaction,
+                id_dispatch_note_type,
+                options.id_data_group,
+            )
+            .await?;
+        }
+
+        let tracker = Entity::find_locked(
+            &transaction,
+            options.id_data_group,
+            options.id_dispatch_note_type,
+        )
+        .await?;
+        let year = Local::now().year();
+        let reset_yearly = options.reset_yearly.unwrap_or(tracker.reset_yearly);
+
+        if let Some(identifier) = options.identifier {
+            if identifier < 1 {
+                return Err(anyhow!("Identifier must be a positive number!"));
+            }
+            if tracker.moves_back(identifier, year)
+                && !(options.override_backwards.unwrap_or(false)
+                    && is_data_group_owner(db, session_data.user_id, options.id_data_group).await?)
+            {
+                return Err(anyhow!(
+                    "Identifier can only be moved forward! Only the group owner can move it back."
+                ));
+            }
+        }
 
         let model = ActiveModel {
-            id_data_group: ActiveValue::Set(options.id_data_group),
-            identifier: ActiveValue::Set(options.identifier),
+            id: ActiveValue::Set(tracker.id),
+            identifier: options
+                .identifier
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            reset_yearly: options
+                .reset_yearly
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            // A set identifier counts for the current year, the next note would restart at 1 otherwise
+            identifier_year: match options.reset_yearly {
+                Some(false) => ActiveValue::Set(None),
+                _ if reset_yearly && (options.identifier.is_some() || !tracker.reset_yearly) => {
+                    ActiveValue::Set(Some(year))
+                }
+                _ => ActiveValue::NotSet,
+            },
+            number_width: options
+                .number_width
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
             ..Default::default()
         };
 
-        let transaction = db.begin().await?;
-
         let res = model.update(&transaction).await?;
+        log_update(&transaction, session_data, &tracker, &res).await?;
 
         transaction.commit().await?;
 
         Ok(res)
     }
 }
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::DispatchNoteIdentTracker;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.id_data_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_web::rt::time::sleep;
+    use futures::future::join_all;
+
+    use super::*;
+    use crate::{
+        seaorm_models::{
+            data_group,
+            dispatch_note_type::{self, DispatchNoteDirection},
+        },
+        test_database::TestDatabase,
+    };
+
+    fn tracker(identifier: i32, reset_yearly: bool, identifier_year: Option<i32>) -> Model {
+        Model {
+            id: 1,
+            id_data_group: 1,
+            id_dispatch_note_type: None,
+            identifier,
+            created_at: Local::now().into(),
+            reset_yearly,
+            identifier_year,
+            number_width: 4,
+        }
+    }
+
+    #[test]
+    fn continues_numbering_without_yearly_reset() {
+        assert_eq!(tracker(42, false, None).next_in(2026), (42, None));
+    }
+
+    #[test]
+    fn restarts_numbering_in_a_new_year() {
+        assert_eq!(tracker(42, true, Some(2025)).next_in(2026), (1, Some(2026)));
+        assert_eq!(tracker(42, true, None).next_in(2026), (1, Some(2026)));
+        assert_eq!(
+            tracker(42, true, Some(2026)).next_in(2026),
+            (42, Some(2026))
+        );
+    }
+
+    #[test]
+    fn moving_back_compares_against_the_yearly_restart() {
+        assert!(tracker(42, false, None).moves_back(41, 2026));
+        assert!(!tracker(42, false, None).moves_back(42, 2026));
+        assert!(!tracker(42, true, Some(2025)).moves_back(5, 2026));
+        assert!(tracker(42, true, Some(2026)).moves_back(5, 2026));
+    }
+
+    #[test]
+    fn pads_formatted_identifiers() {
+        let tracker = tracker(1, true, Some(2026));
+        assert_eq!(tracker.format_identifier(42, None), "0042");
+        assert_eq!(tracker.format_identifier(42, Some(2026)), "2026/0042");
+        assert_eq!(tracker.format_identifier(123456, None), "123456");
+    }
+
+    async fn tracked_data_group(test_db: &TestDatabase, tracker_sql: &str) -> i32 {
+        let mbe_group = test_db.mbe_group().await;
+        let d_group = test_db.data_group(mbe_group).await;
+        test_db
+            .db
+            .execute_unprepared(&tracker_sql.replace("{d_group}", &d_group.to_string()))
+            .await
+            .unwrap();
+        d_group
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn concurrent_assignments_get_distinct_identifiers() {
+        let test_db = TestDatabase::new().await;
+        let d_group = tracked_data_group(
+            &test_db,
+            "INSERT INTO dispatch_note_ident_tracker (id_data_group, identifier) VALUES ({d_group}, 7)",
+        )
+        .await;
+
+        let assignments = (0..8).map(|_| async {
+            let transaction = test_db.db.begin().await.unwrap();
+            let assigned = Entity::assign_identifier(&transaction, d_group, None)
+                .await
+                .unwrap();
+            // Keeps the row locked while the other transactions wait for it
+            sleep(Duration::from_millis(10)).await;
+            transaction.commit().await.unwrap();
+            assigned.numerical_identifier
+        });
+        let mut identifiers = join_all(assignments).await;
+        identifiers.sort();
+
+        assert_eq!(identifiers, (7..15).collect::<Vec<_>>());
+        let tracker = Entity::find_counter(d_group, None)
+            .one(&test_db.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tracker.identifier, 15);
+
+        test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn own_numbering_types_use_their_own_counter() {
+        let test_db = TestDatabase::new().await;
+        let d_group = tracked_data_group(
+            &test_db,
+            "INSERT INTO dispatch_note_ident_tracker (id_data_group, identifier) VALUES ({d_group}, 7)",
+        )
+        .await;
+        let id_mbe_group = data_group::Entity::find_by_id(d_group)
+            .one(&test_db.db)
+            .await
+            .unwrap()
+            .unwrap()
+            .id_mbe_group;
+        let shared = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note_type (id_mbe_group, name) VALUES ({}, 'Shared')",
+                id_mbe_group
+            ))
+            .await;
+        let own = test_db
+            .insert(&format!(
+                "INSERT INTO dispatch_note_type (id_mbe_group, name, own_numbering) VALUES ({}, 'Own', TRUE)",
+                id_mbe_group
+            ))
+            .await;
+
+        let note_type = |id, own_numbering| dispatch_note_type::Model {
+            id,
+            id_mbe_group,
+            name: format!("Type {}", id),
+            direction: DispatchNoteDirection::Out,
+            own_numbering,
+            print_template: None,
+            created_at: Local::now().into(),
+        };
+        let shared = note_type(shared, false);
+        let own = note_type(own, true);
+
+        let transaction = test_db.db.begin().await.unwrap();
+        let mut identifiers = Vec::new();
+        for note_type in [Some(&shared), Some(&own), Some(&own), None] {
+            identifiers.push(
+                Entity::assign_identifier(&transaction, d_group, note_type)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let [shared_first, own_first, own_second, shared_second] = &identifiers[..] else {
+            unreachable!()
+        };
+        transaction.commit().await.unwrap();
+
+        assert_eq!(shared_first.numerical_identifier, 7);
+        assert_eq!(own_first.numerical_identifier, 1);
+        assert_eq!(own_second.numerical_identifier, 2);
+        assert_eq!(shared_second.numerical_identifier, 8);
+        assert_eq!(own_first.id_ident_tracker, own_second.id_ident_tracker);
+        assert_ne!(own_first.id_ident_tracker, shared_first.id_ident_tracker);
+
+        test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn rolled_back_assignment_keeps_identifier() {
+        let test_db = TestDatabase::new().await;
+        let d_group = tracked_data_group(
+            &test_db,
+            "INSERT INTO dispatch_note_ident_tracker (id_data_group, identifier) VALUES ({d_group}, 7)",
+        )
+        .await;
+
+        let transaction = test_db.db.begin().await.unwrap();
+        Entity::assign_identifier(&transaction, d_group, None)
+            .await
+            .unwrap();
+        transaction.rollback().await.unwrap();
+
+        let transaction = test_db.db.begin().await.unwrap();
+        let assigned = Entity::assign_identifier(&transaction, d_group, None)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        assert_eq!(assigned.numerical_identifier, 7);
+
+        test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn yearly_reset_restarts_at_one() {
+        let test_db = TestDatabase::new().await;
+        let year = Local::now().year();
+        let d_group = tracked_data_group(
+            &test_db,
+            &format!(
+                "INSERT INTO dispatch_note_ident_tracker (id_data_group, identifier, reset_yearly, identifier_year) \
+                 VALUES ({{d_group}}, 57, TRUE, {})",
+                year - 1
+            ),
+        )
+        .await;
+
+        let transaction = test_db.db.begin().await.unwrap();
+        let first = Entity::assign_identifier(&transaction, d_group, None)
+            .await
+            .unwrap();
+        let second = Entity::assign_identifier(&transaction, d_group, None)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        assert_eq!(first.numerical_identifier, 1);
+        assert_eq!(first.identifier_year, Some(year));
+        assert_eq!(first.formatted_identifier, format!("{}/0001", year));
+        assert_eq!(second.numerical_identifier, 2);
+
+        test_db.close().await;
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, Result};
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use sea_orm::{
+    entity::prelude::*, ActiveValue, DatabaseTransaction, PaginatorTrait, QueryOrder,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{user_models::mbe_group_members::MbeGroupRole, SeaOrmPool};
+
+use super::{
+    audit_log::{log_delete, log_insert, log_update, AuditEntityType, Auditable},
+    graphql_schema::{extract_session, MbeGroupAccessGuard},
+    RowsDeleted,
+};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum DispatchNoteDirection {
+    /// Goods received
+    #[sea_orm(string_value = "in")]
+    In,
+    /// Goods dispatched
+    #[sea_orm(string_value = "out")]
+    Out,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "dispatch_note_type")]
+#[graphql(name = "DispatchNoteType")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub id_mbe_group: i32,
+    pub name: String,
+    pub direction: DispatchNoteDirection,
+    /// Notes of this type are numbered from their own counter
+    /// instead of the counter shared by the whole data group
+    pub own_numbering: bool,
+    /// Title printed on the dispatch note, `{identifier}` is replaced with the notes identifier
+    pub print_template: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::user_models::mbe_group::Entity",
+        from = "Column::IdMbeGroup",
+        to = "crate::user_models::mbe_group::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    MbeGroup,
+    #[sea_orm(has_many = "super::dispatch_note::Entity")]
+    DispatchNote,
+    #[sea_orm(has_many = "super::dispatch_note_ident_tracker::Entity")]
+    DispatchNoteIdentTracker,
+}
+
+impl Related<crate::user_models::mbe_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeGroup.def()
+    }
+}
+
+impl Related<super::dispatch_note::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DispatchNote.def()
+    }
+}
+
+impl Related<super::dispatch_note_ident_tracker::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DispatchNoteIdentTracker.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Title of a printed dispatch note of this type
+    pub fn print_title(&self, formatted_identifier: &str) -> Option<String> {
+        self.print_template
+            .as_ref()
+            .map(|template| template.replace("{identifier}", formatted_identifier))
+    }
+}
+
+impl Entity {
+    /// Finds the type only if it belongs to the mbe group owning the data group
+    pub async fn find_for_data_group(
+        transaction: &DatabaseTransaction,
+        id: i32,
+        d_group: i32,
+    ) -> Result<Model> {
+        let data_group = super::data_group::Entity::find_by_id(d_group)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Data group not found"))?;
+
+        Entity::find_by_id(id)
+            .filter(Column::IdMbeGroup.eq(data_group.id_mbe_group))
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Dispatch note type not found in the data groups mbe group!"))
+    }
+}
+
+#[derive(InputObject)]
+struct DispatchNoteTypeFetchOptions {
+    id_mbe_group: i32,
+    direction: Option<DispatchNoteDirection>,
+}
+
+#[derive(InputObject)]
+struct DispatchNoteTypeInsertOptions {
+    id_mbe_group: i32,
+    name: String,
+    direction: DispatchNoteDirection,
+    own_numbering: Option<bool>,
+    print_template: Option<String>,
+}
+
+#[derive(InputObject)]
+struct DispatchNoteTypeUpdateOptions {
+    id: i32,
+    id_mbe_group: i32,
+    name: Option<String>,
+    direction: Option<DispatchNoteDirection>,
+    own_numbering: Option<bool>,
+    print_template: Option<String>,
+}
+
+#[derive(InputObject)]
+struct DispatchNoteTypeDeleteOptions {
+    id: i32,
+    id_mbe_group: i32,
+}
+
+fn validate_name(name: String) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(anyhow!("Invalid name!"))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+#[derive(Default)]
+pub struct DispatchNoteTypeQuery;
+
+#[Object]
+impl DispatchNoteTypeQuery {
+    #[graphql(guard = "MbeGroupAccessGuard::new(options.id_mbe_group)")]
+    async fn dispatch_note_types(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteTypeFetchOptions,
+    ) -> Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let mut query = Entity::find()
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .order_by_asc(Column::Name);
+        if let Some(direction) = options.direction {
+            query = query.filter(Column::Direction.eq(direction));
+        }
+
+        let transaction = db.begin().await?;
+
+        let res = query.all(&transaction).await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+#[derive(Default)]
+pub struct DispatchNoteTypeMutation;
+
+#[Object]
+impl DispatchNoteTypeMutation {
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn insert_dispatch_note_type(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteTypeInsertOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let model = ActiveModel {
+            id_mbe_group: ActiveValue::Set(options.id_mbe_group),
+            name: ActiveValue::Set(validate_name(options.name)?),
+            direction: ActiveValue::Set(options.direction),
+            own_numbering: options
+                .own_numbering
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            print_template: ActiveValue::Set(options.print_template),
+            ..Default::default()
+        };
+
+        let transaction = db.begin().await?;
+
+        let res = model.insert(&transaction).await?;
+        log_insert(&transaction, session_data, &res).await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn update_dispatch_note_type(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteTypeUpdateOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let model = ActiveModel {
+            id: ActiveValue::Set(options.id),
+            name: match options.name {
+                Some(name) => ActiveValue::Set(validate_name(name)?),
+                None => ActiveValue::NotSet,
+            },
+            direction: options
+                .direction
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            own_numbering: options
+                .own_numbering
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            print_template: options
+                .print_template
+                .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
+            ..Default::default()
+        };
+
+        let transaction = db.begin().await?;
+
+        let before = Entity::find_by_id(options.id)
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Dispatch note type not found"))?;
+
+        let res = model.update(&transaction).await?;
+        log_update(&transaction, session_data, &before, &res).await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn delete_dispatch_note_type(
+        &self,
+        ctx: &Context<'_>,
+        options: DispatchNoteTypeDeleteOptions,
+    ) -> Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let used_by = super::dispatch_note::Entity::find()
+            .filter(super::dispatch_note::Column::NoteType.eq(options.id))
+            .count(&transaction)
+            .await?;
+        if used_by > 0 {
+            return Err(anyhow!(
+                "Dispatch note type is used by {} dispatch notes!",
+                used_by
+            ));
+        }
+
+        if let Some(before) = Entity::find_by_id(options.id)
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .one(&transaction)
+            .await?
+        {
+            log_delete(&transaction, session_data, &before).await?;
+        }
+
+        let res = Entity::delete_many()
+            .filter(Column::Id.eq(options.id))
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .exec(&transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(res.into())
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::DispatchNoteType;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id_mbe_group)
+    }
+}
@@ -1,4 +1,4 @@
-use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use async_graphql::{Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
 use log::error;
 use sea_orm::{
@@ -12,15 +12,20 @@ use serde::{Deserialize, Serialize};
 use anyhow::anyhow;
 use anyhow::Result;
 
-use crate::SeaOrmPool;
+use crate::{auth::SessionData, SeaOrmPool};
 
 use super::{
+    audit_log::{log_insert, log_update, AuditAction, AuditEntityType, Auditable},
+    connection::{query_connection, OrderingColumn, QueryConnection},
+    data_group_events::{data_group_of, publish},
     graphql_schema::{
-        DataGroupAccessGuard, DeleteOptions, FetchOptions, Filter, OrderingOptions, Pagination,
-        UpdateDeleteGuard,
+        extract_session, DataGroupAccessGuard, DeleteOptions, EntityAccessGuard, EveryGuard,
+        FetchOptions, Filter, OrderingOptions, Pagination, UpdateDeleteGuard,
     },
-    GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase, QueryResults,
-    QueryResultsHelperType, RowsDeleted,
+    trash::{check_not_deleted, restore, soft_delete, RestoreOptions},
+    weight_type::WeightTypeDimension,
+    CheckEditable, GetEntityDataGroupColumnTrait, GetEntityDataGroupId, GetEntityId, QueryDatabase,
+    QueryResults, QueryResultsHelperType, RowsDeleted,
 };
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
@@ -34,6 +39,9 @@ pub struct Model {
     pub id_buyer: i32,
     pub id_cell_culture_pair: i32,
     pub d_group: i32,
+    pub weight_type: i32,
+    /// Set while the entry is in the trash
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -62,6 +70,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     DataGroup,
+    #[sea_orm(
+        belongs_to = "super::weight_type::Entity",
+        from = "Column::WeightType",
+        to = "super::weight_type::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    WeightType,
 }
 
 impl Related<super::buyer::Entity> for Entity {
@@ -82,6 +98,12 @@ impl Related<super::data_group::Entity> for Entity {
     }
 }
 
+impl Related<super::weight_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WeightType.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 #[allow(clippy::derivable_impls)]
@@ -95,6 +117,8 @@ impl Default for Column {
 pub struct EntryInsertOptions {
     pub date: DateTimeWithTimeZone,
     pub weight: Option<f64>,
+    /// Unit of `weight`, must be available to the data groups mbe group
+    pub weight_type: i32,
     pub id_buyer: i32,
     pub id_cell: i32,
     pub id_culture: i32,
@@ -111,12 +135,16 @@ pub struct PairIds {
 pub struct EntryUpdateOptions {
     pub id: i32,
     pub weight: Option<f64>,
+    pub weight_type: Option<i32>,
     pub date: Option<DateTimeWithTimeZone>,
     pub id_buyer: Option<i32>,
     pub pair_ids: Option<PairIds>,
     pub d_group: Option<i32>,
 }
 
+/// Entry weight in kilograms, `NULL` when the entries unit isn't a mass with a conversion factor
+pub const ENTRY_WEIGHT_KG: &str = r#"CASE WHEN "weight_type"."dimension" = 'mass' THEN "entry"."weight" * "weight_type"."conversion_factor" END"#;
+
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
 pub enum EntryFields {
     Id,
@@ -137,7 +165,9 @@ pub struct EntryFlattened {
     pub weight: Option<f64>,
     pub date: Date,
     pub created_at: DateTimeWithTimeZone,
+    #[allow(dead_code)]
     pub d_group: i32,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 
     pub id_buyer: i32,
     pub name_buyer: Option<String>,
@@ -145,28 +175,34 @@ pub struct EntryFlattened {
     pub contact_buyer: Option<String>,
     pub created_at_buyer: DateTimeWithTimeZone,
     pub d_group_buyer: i32,
+    pub deleted_at_buyer: Option<DateTimeWithTimeZone>,
 
     pub id_cell: i32,
     pub name_cell: String,
     pub description_cell: Option<String>,
     pub created_at_cell: DateTimeWithTimeZone,
     pub d_group_cell: i32,
+    pub deleted_at_cell: Option<DateTimeWithTimeZone>,
 
     pub id_culture: i32,
     pub name_culture: String,
     pub description_culture: Option<String>,
     pub created_at_culture: DateTimeWithTimeZone,
     pub d_group_culture: i32,
+    pub deleted_at_culture: Option<DateTimeWithTimeZone>,
 
     pub id_d_group: i32,
     pub name_d_group: String,
     pub description_d_group: Option<String>,
     pub created_at_d_group: DateTimeWithTimeZone,
     pub id_mbe_group: i32,
-    // pub id_weight_type: i32,
-    // pub unit_short: String,
-    // pub unit: String,
-    // pub created_at_weight_type: DateTimeWithTimeZone,
+
+    pub id_weight_type: i32,
+    pub unit_short: String,
+    pub unit: String,
+    pub created_at_weight_type: DateTimeWithTimeZone,
+    pub conversion_factor: Option<f64>,
+    pub dimension: WeightTypeDimension,
 }
 
 #[derive(Debug, SimpleObject)]
@@ -175,20 +211,15 @@ pub struct Entry {
     pub weight: Option<f64>,
     pub date: Date,
     pub created_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 
     pub buyer: super::buyer::Model,
     pub cell: super::cell::Model,
     pub culture: super::culture::Model,
-    // pub weight_type: super::weight_type::Model,
+    pub weight_type: super::weight_type::Model,
     pub d_group: super::data_group::Model,
 }
 
-#[derive(Debug, SimpleObject)]
-pub struct AllEntires {
-    results: Vec<Entry>,
-    total: u64,
-}
-
 impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
     fn from(inp: QueryResultsHelperType<EntryFlattened>) -> Self {
         let (results, items_and_page_number, page, page_size) = inp;
@@ -200,6 +231,7 @@ impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
                     weight: flat.weight,
                     date: flat.date,
                     created_at: flat.created_at,
+                    deleted_at: flat.deleted_at,
                     buyer: super::buyer::Model {
                         id: flat.id_buyer,
                         name: flat.name_buyer,
@@ -207,6 +239,7 @@ impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
                         contact: flat.contact_buyer,
                         created_at: flat.created_at_buyer,
                         d_group: flat.d_group_buyer,
+                        deleted_at: flat.deleted_at_buyer,
                     },
                     cell: super::cell::Model {
                         id: flat.id_cell,
@@ -214,6 +247,7 @@ impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
                         description: flat.description_cell,
                         created_at: flat.created_at_cell,
                         d_group: flat.d_group_cell,
+                        deleted_at: flat.deleted_at_cell,
                     },
                     culture: super::culture::Model {
                         id: flat.id_culture,
@@ -221,13 +255,16 @@ impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
                         description: flat.description_culture,
                         created_at: flat.created_at_culture,
                         d_group: flat.d_group_culture,
+                        deleted_at: flat.deleted_at_culture,
+                    },
+                    weight_type: super::weight_type::Model {
+                        id: flat.id_weight_type,
+                        unit_short: flat.unit_short,
+                        unit: flat.unit,
+                        created_at: flat.created_at_weight_type,
+                        conversion_factor: flat.conversion_factor,
+                        dimension: flat.dimension,
                     },
-                    // weight_type: super::weight_type::Model {
-                    //     id: flat.id_weight_type,
-                    //     unit_short: flat.unit_short,
-                    //     unit: flat.unit,
-                    //     created_at: flat.created_at_weight_type,
-                    // },
                     d_group: super::data_group::Model {
                         id: flat.id_d_group,
                         name: flat.name_d_group,
@@ -237,12 +274,7 @@ impl From<QueryResultsHelperType<EntryFlattened>> for QueryResults<Entry> {
                     },
                 })
                 .collect(),
-            pagination: Pagination {
-                page: page.page,
-                page_size: page_size.0,
-                total_items: items_and_page_number.number_of_items,
-                total_pages: items_and_page_number.number_of_pages,
-            },
+            pagination: Pagination::new(page, page_size, items_and_page_number),
         }
     }
 }
@@ -273,11 +305,16 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
-    fn get_query() -> Select<Self> {
+    fn get_deleted_at_column() -> Option<Column> {
+        Some(Column::DeletedAt)
+    }
+
+    fn get_query_with_deleted() -> Select<Self> {
         Entity::find()
             .inner_join(super::buyer::Entity)
             .inner_join(super::cell_culture_pair::Entity)
             .inner_join(super::data_group::Entity)
+            .inner_join(super::weight_type::Entity)
             .join(
                 JoinType::InnerJoin,
                 super::cell_culture_pair::Relation::Culture.def(),
@@ -292,16 +329,19 @@ impl QueryDatabase for Entity {
             .column_as(super::buyer::Column::Contact, "contact_buyer")
             .column_as(super::buyer::Column::CreatedAt, "created_at_buyer")
             .column_as(super::buyer::Column::DGroup, "d_group_buyer")
+            .column_as(super::buyer::Column::DeletedAt, "deleted_at_buyer")
             .column_as(super::cell::Column::Id, "id_cell")
             .column_as(super::cell::Column::Name, "name_cell")
             .column_as(super::cell::Column::Description, "description_cell")
             .column_as(super::cell::Column::CreatedAt, "created_at_cell")
             .column_as(super::cell::Column::DGroup, "d_group_cell")
+            .column_as(super::cell::Column::DeletedAt, "deleted_at_cell")
             .column_as(super::culture::Column::Id, "id_culture")
             .column_as(super::culture::Column::Name, "name_culture")
             .column_as(super::culture::Column::Description, "description_culture")
             .column_as(super::culture::Column::CreatedAt, "created_at_culture")
             .column_as(super::culture::Column::DGroup, "d_group_culture")
+            .column_as(super::culture::Column::DeletedAt, "deleted_at_culture")
             .column_as(super::data_group::Column::Id, "id_d_group")
             .column_as(super::data_group::Column::Name, "name_d_group")
             .column_as(
@@ -310,13 +350,26 @@ impl QueryDatabase for Entity {
             )
             .column_as(super::data_group::Column::CreatedAt, "created_at_d_group")
             .column_as(super::data_group::Column::IdMbeGroup, "id_mbe_group")
+            .column_as(super::weight_type::Column::Id, "id_weight_type")
+            .column_as(super::weight_type::Column::UnitShort, "unit_short")
+            .column_as(super::weight_type::Column::Unit, "unit")
+            .column_as(
+                super::weight_type::Column::CreatedAt,
+                "created_at_weight_type",
+            )
+            .column_as(
+                super::weight_type::Column::ConversionFactor,
+                "conversion_factor",
+            )
+            .column_as(super::weight_type::Column::Dimension, "dimension")
     }
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        Ok(Self::delete_by_id(options.id).exec(transaction).await?)
+        soft_delete::<Self>(transaction, session_data, options.id).await
     }
 
     fn add_ordering(
@@ -358,6 +411,39 @@ impl QueryDatabase for Entity {
         query
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        match ordering_options {
+            Some(options) => {
+                let column = match options.order_by {
+                    Self::InputFields::Id => OrderingColumn::new(Column::Id),
+                    Self::InputFields::Weight => OrderingColumn::new(Column::Weight),
+                    Self::InputFields::Date => OrderingColumn::new(Column::Date),
+                    Self::InputFields::BuyerName => OrderingColumn::new(super::buyer::Column::Name),
+                    Self::InputFields::BuyerAddress => {
+                        OrderingColumn::new(super::buyer::Column::Address)
+                    }
+                    Self::InputFields::BuyerContact => {
+                        OrderingColumn::new(super::buyer::Column::Contact)
+                    }
+                    Self::InputFields::CellName => OrderingColumn::new(super::cell::Column::Name),
+                    Self::InputFields::CellDescription => {
+                        OrderingColumn::new(super::cell::Column::Description)
+                    }
+                    Self::InputFields::CultureName => {
+                        OrderingColumn::new(super::culture::Column::Name)
+                    }
+                    Self::InputFields::CultureDescription => {
+                        OrderingColumn::new(super::culture::Column::Description)
+                    }
+                };
+                (column, options.order.into())
+            }
+            None => (OrderingColumn::new(super::cell::Column::Name), Order::Asc),
+        }
+    }
+
     fn add_id_and_data_group_filters(
         mut query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -441,31 +527,51 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
-        let transaction = db.begin().await?;
+    ) -> Result<Model> {
+        let before = Entity::find_by_id(options.id)
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Entry not found"))?;
 
         let pair = match options.pair_ids {
-            Some(pair) => {
-                super::cell_culture_pair::Entity::find()
-                    .filter(super::cell_culture_pair::Column::IdCell.eq(pair.id_cell))
-                    .filter(super::cell_culture_pair::Column::IdCulture.eq(pair.id_culture))
-                    .one(&transaction)
-                    .await?
-            }
+            Some(pair) => Some(
+                super::cell_culture_pair::Entity::find_for_data_group(
+                    transaction,
+                    pair.id_cell,
+                    pair.id_culture,
+                    before.d_group,
+                )
+                .await?,
+            ),
             None => None,
         };
 
+        if let Some(id_buyer) = options.id_buyer {
+            super::buyer::Entity::find_for_data_group(transaction, id_buyer, before.d_group)
+                .await?;
+        }
+
+        if let Some(weight_type) = options.weight_type {
+            super::weight_type::Entity::find_for_data_group(
+                transaction,
+                weight_type,
+                before.d_group,
+            )
+            .await?;
+        }
+
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             weight: options
                 .weight
                 .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
-            // weight_type: options
-            //     .weight_type
-            //     .map_or(ActiveValue::NotSet, ActiveValue::Set),
+            weight_type: options
+                .weight_type
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
             date: options.date.map_or(ActiveValue::NotSet, |val| {
                 ActiveValue::Set(val.date_naive())
             }),
@@ -475,76 +581,50 @@ impl QueryDatabase for Entity {
             id_cell_culture_pair: pair.map_or(ActiveValue::NotSet, |val| ActiveValue::Set(val.id)),
             ..Default::default()
         };
-        let res = Entity::update(model).exec(&transaction).await?;
-
-        transaction.commit().await?;
+        let res = Entity::update(model).exec(transaction).await?;
+        log_update(transaction, session_data, &before, &res).await?;
 
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<EntryFields> {
-                id: Some(res.id),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
-        )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Updated CellCulturePair not found"))?
-        .1)
+        Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
-        let transaction = db.begin().await?;
+    ) -> Result<Model> {
+        let cell_culture_pair = super::cell_culture_pair::Entity::find_for_data_group(
+            transaction,
+            options.id_cell,
+            options.id_culture,
+            options.d_group,
+        )
+        .await?;
 
-        let cell_culture_pair = super::cell_culture_pair::Entity::find()
-            .filter(super::cell_culture_pair::Column::IdCell.eq(options.id_cell))
-            .filter(super::cell_culture_pair::Column::IdCulture.eq(options.id_culture))
-            .one(&transaction)
-            .await?
-            .ok_or_else(|| anyhow!("CellCulturePair with provided ids must exist!"))?;
+        super::buyer::Entity::find_for_data_group(transaction, options.id_buyer, options.d_group)
+            .await?;
+
+        super::weight_type::Entity::find_for_data_group(
+            transaction,
+            options.weight_type,
+            options.d_group,
+        )
+        .await?;
 
         let model = ActiveModel {
             date: ActiveValue::Set(options.date.date_naive()),
             weight: ActiveValue::Set(options.weight),
-            // weight_type: ActiveValue::Set(options.weight_type),
+            weight_type: ActiveValue::Set(options.weight_type),
             id_buyer: ActiveValue::Set(options.id_buyer),
             id_cell_culture_pair: ActiveValue::Set(cell_culture_pair.id),
             d_group: ActiveValue::Set(options.d_group),
             ..Default::default()
         };
         let res = Entity::insert(model)
-            .exec_with_returning(&transaction)
+            .exec_with_returning(transaction)
             .await?;
+        log_insert(transaction, session_data, &res).await?;
 
-        transaction.commit().await?;
-
-        Ok(Self::fetch(
-            db,
-            FetchOptions::<EntryFields> {
-                id: Some(res.id),
-                page_size: None,
-                page: None,
-                ordering: None,
-                filters: None,
-                d_group: res.d_group,
-            },
-        )
-        .await?
-        .results
-        .into_iter()
-        .enumerate()
-        .find(|(i, _)| *i == 0)
-        .ok_or_else(|| anyhow!("Updated CellCulturePair not found"))?
-        .1)
+        Ok(res)
     }
 }
 
@@ -563,76 +643,22 @@ impl EntryQuery {
         Entity::fetch(db, options).await
     }
 
+    /// Cursor paged `entries`, ignores `page` and `pageSize` of the options
     #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
-    async fn all_entries(
+    async fn entries_connection(
         &self,
         ctx: &Context<'_>,
         options: FetchOptions<EntryFields>,
-    ) -> Result<AllEntires> {
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<Entry>> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-
-        let mut query = Entity::get_query();
-
-        query = Entity::add_id_and_data_group_filters(query, &options);
-        query = Entity::add_ordering(query, options.ordering);
-        query = Entity::add_filters(query, options.filters);
-
-        let transaction = db.begin().await?;
-
-        let res = query
-            .into_model::<<Entity as QueryDatabase>::FetchModel>()
-            .all(&transaction)
-            .await?;
-
-        transaction.commit().await?;
-
-        Ok(AllEntires {
-            total: res.len() as u64,
-            results: res
-                .into_iter()
-                .map(|flat| Entry {
-                    id: flat.id,
-                    weight: flat.weight,
-                    date: flat.date,
-                    created_at: flat.created_at,
-                    buyer: super::buyer::Model {
-                        id: flat.id_buyer,
-                        name: flat.name_buyer,
-                        address: flat.address_buyer,
-                        contact: flat.contact_buyer,
-                        created_at: flat.created_at_buyer,
-                        d_group: flat.d_group_buyer,
-                    },
-                    cell: super::cell::Model {
-                        id: flat.id_cell,
-                        name: flat.name_cell,
-                        description: flat.description_cell,
-                        created_at: flat.created_at_cell,
-                        d_group: flat.d_group_cell,
-                    },
-                    culture: super::culture::Model {
-                        id: flat.id_culture,
-                        name: flat.name_culture,
-                        description: flat.description_culture,
-                        created_at: flat.created_at_culture,
-                        d_group: flat.d_group_culture,
-                    },
-                    // weight_type: super::weight_type::Model {
-                    //     id: flat.id_weight_type,
-                    //     unit_short: flat.unit_short,
-                    //     unit: flat.unit,
-                    //     created_at: flat.created_at_weight_type,
-                    // },
-                    d_group: super::data_group::Model {
-                        id: flat.id_d_group,
-                        name: flat.name_d_group,
-                        description: flat.description_d_group,
-                        created_at: flat.created_at_d_group,
-                        id_mbe_group: flat.id,
-                    },
-                })
-                .collect(),
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection(db, options, args)
         })
+        .await
     }
 }
 
@@ -641,22 +667,173 @@ pub struct EntryMutation;
 
 #[Object]
 impl EntryMutation {
-    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    #[graphql(guard = "DataGroupAccessGuard::write(options.d_group)")]
     async fn insert_entry(&self, ctx: &Context<'_>, options: EntryInsertOptions) -> Result<Entry> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::insert_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::Entry,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn update_entry(&self, ctx: &Context<'_>, options: EntryUpdateOptions) -> Result<Entry> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::Entry,
+            AuditAction::Update,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 
     #[graphql(guard = "UpdateDeleteGuard::<Entity>::new(options.id)")]
     async fn delete_entry(&self, ctx: &Context<'_>, options: DeleteOptions) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        let id = options.id;
+        let d_group = data_group_of::<Entity>(db, id).await?;
+        let res = Entity::delete_entity(db, session_data, options).await?;
+        publish(
+            ctx,
+            d_group,
+            AuditEntityType::Entry,
+            AuditAction::Delete,
+            id,
+        )
+        .await;
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| DataGroupAccessGuard::write(options.d_group)))"
+    )]
+    async fn insert_entries(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<EntryInsertOptions>,
+    ) -> async_graphql::Result<Vec<Entry>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::insert_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::Entry,
+                AuditAction::Insert,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn update_entries(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<EntryUpdateOptions>,
+    ) -> async_graphql::Result<Vec<Entry>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let res = Entity::update_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for res in &res {
+            publish(
+                ctx,
+                Some(res.d_group.id),
+                AuditEntityType::Entry,
+                AuditAction::Update,
+                res.id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(
+        guard = "EveryGuard::new(options.iter().map(|options| UpdateDeleteGuard::<Entity>::new(options.id)))"
+    )]
+    async fn delete_entries(
+        &self,
+        ctx: &Context<'_>,
+        options: Vec<DeleteOptions>,
+    ) -> async_graphql::Result<RowsDeleted> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+        let mut deleted = Vec::with_capacity(options.len());
+        for options in &options {
+            deleted.push((options.id, data_group_of::<Entity>(db, options.id).await?));
+        }
+        let res = Entity::delete_entities(db, session_data, options)
+            .await
+            .map_err(|e| e.extend())?;
+        for (id, d_group) in deleted {
+            publish(
+                ctx,
+                d_group,
+                AuditEntityType::Entry,
+                AuditAction::Delete,
+                id,
+            )
+            .await;
+        }
+        Ok(res)
+    }
+
+    #[graphql(guard = "EntityAccessGuard::<Entity>::new(options.id)")]
+    async fn restore_entry(&self, ctx: &Context<'_>, options: RestoreOptions) -> Result<Entry> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+        let res = restore::<Entity>(&transaction, session_data, options.id).await?;
+        transaction.commit().await?;
+
+        let res = Entity::fetch(
+            db,
+            FetchOptions::<EntryFields> {
+                id: Some(res.id),
+                page_size: None,
+                page: None,
+                ordering: None,
+                filters: None,
+                d_group: res.d_group,
+                as_of: None,
+            },
+        )
+        .await?
+        .results
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Restored entry not found"))?;
+        publish(
+            ctx,
+            Some(res.d_group.id),
+            AuditEntityType::Entry,
+            AuditAction::Insert,
+            res.id,
+        )
+        .await;
+        Ok(res)
     }
 }
 
@@ -671,3 +848,22 @@ impl GetEntityDataGroupId for Model {
         self.d_group
     }
 }
+
+#[async_trait]
+impl CheckEditable for Model {
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        check_not_deleted(self.deleted_at)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::Entry;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_data_group(&self) -> Option<i32> {
+        Some(self.d_group)
+    }
+}
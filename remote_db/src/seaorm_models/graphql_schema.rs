@@ -1,24 +1,33 @@
 use std::marker::PhantomData;
 
 use async_graphql::{
-    Context, Enum, Guard, InputObject, InputType, MergedObject, OutputType, SimpleObject,
+    Context, Enum, Guard, InputObject, InputType, MergedObject, MergedSubscription, OutputType,
+    SimpleObject,
 };
 use async_trait::async_trait;
-use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, TransactionTrait};
+use sea_orm::{
+    prelude::DateTimeWithTimeZone, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order,
+    QueryFilter, TransactionTrait,
+};
 
 use crate::{
     auth::SessionData,
     http_response_errors::AuthError,
     user_models::{
-        mbe_group::{MbeGroupMutation, MbeGroupQuery},
-        mbe_group_members::{self, MbeGroupMembersMutation, MbeGroupMembersQuery},
-        mbe_user::MbeUserMutation,
+        mbe_group::{self, MbeGroupMutation, MbeGroupQuery},
+        mbe_group_invitation::{MbeGroupInvitationMutation, MbeGroupInvitationQuery},
+        mbe_group_members::{self, MbeGroupMembersMutation, MbeGroupMembersQuery, MbeGroupRole},
+        mbe_group_print_header::{MbeGroupPrintHeaderMutation, MbeGroupPrintHeaderQuery},
+        mbe_user::{self, MbeUserMutation},
+        mbe_user_session::{MbeUserSessionMutation, MbeUserSessionQuery},
+        personal_access_token::{PersonalAccessTokenMutation, PersonalAccessTokenQuery},
     },
     SeaOrmPool,
 };
 
 use super::{
     article::{ArticleFields, ArticleMutation, ArticleQuery},
+    audit_log::AuditLogQuery,
     buyer::{BuyerFields, BuyerMutation, BuyerQuery},
     cell::{CellFields, CellMutation, CellParity, CellQuery},
     cell_culture_pair::{
@@ -27,6 +36,7 @@ use super::{
     culture::{CultureFields, CultureMutation, CultureParity, CultureQuery},
     data_group,
     data_group::{DataGroupFields, DataGroupMutation, DataGroupQuery},
+    data_group_events::DataGroupEventsSubscription,
     dispatch_note::{
         DispatchNoteFields, DispatchNoteFilterValue, DispatchNoteMutation, DispatchNoteQuery,
     },
@@ -37,9 +47,14 @@ use super::{
     dispatch_note_ident_tracker::{
         DispatchNoteIdentTrackerMutation, DispatchNoteIdentTrackerQuery,
     },
+    dispatch_note_type::{DispatchNoteTypeMutation, DispatchNoteTypeQuery},
     entry::{EntryFields, EntryMutation, EntryQuery},
+    history::HistoryQuery,
+    mass_balance::MassBalanceQuery,
+    reconciliation::ReconciliationQuery,
+    trash::TrashQuery,
     weight_type::{WeightTypeDeleteOptions, WeightTypeFields, WeightTypeMutation, WeightTypeQuery},
-    GetEntityDataGroupId, GetEntityId, QueryResultsTrait,
+    CheckEditable, GetEntityDataGroupId, GetEntityId, QueryResultsTrait,
 };
 
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
@@ -67,7 +82,10 @@ pub enum FieldTypes {
 #[derive(SimpleObject, Debug)]
 pub struct Pagination {
     pub page: u64,
+    #[graphql(deprecation = "Use `effectivePageSize`")]
     pub page_size: u64,
+    /// Requested page size after it was clamped to the limits configured on the server
+    pub effective_page_size: u64,
     pub total_items: u64,
     pub total_pages: u64,
 }
@@ -85,8 +103,18 @@ pub struct QueryRoot(
     DispatchNoteArticleQuery,
     MbeGroupQuery,
     MbeGroupMembersQuery,
+    MbeGroupInvitationQuery,
+    MbeGroupPrintHeaderQuery,
+    PersonalAccessTokenQuery,
+    MbeUserSessionQuery,
     WeightTypeQuery,
     DispatchNoteIdentTrackerQuery,
+    DispatchNoteTypeQuery,
+    MassBalanceQuery,
+    ReconciliationQuery,
+    AuditLogQuery,
+    TrashQuery,
+    HistoryQuery,
 );
 
 #[derive(MergedObject, Default)]
@@ -102,11 +130,19 @@ pub struct MutationRoot(
     DispatchNoteArticleMutation,
     MbeGroupMutation,
     MbeGroupMembersMutation,
+    MbeGroupInvitationMutation,
+    MbeGroupPrintHeaderMutation,
     MbeUserMutation,
+    PersonalAccessTokenMutation,
+    MbeUserSessionMutation,
     WeightTypeMutation,
     DispatchNoteIdentTrackerMutation,
+    DispatchNoteTypeMutation,
 );
 
+#[derive(MergedSubscription, Default)]
+pub struct SubscriptionRoot(DataGroupEventsSubscription);
+
 #[derive(SimpleObject, Debug)]
 #[graphql(concrete(name = "BuyerResult", params(super::buyer::Model)))]
 #[graphql(concrete(name = "CellResult", params(super::cell::Model)))]
@@ -118,12 +154,16 @@ pub struct MutationRoot(
 ))]
 #[graphql(concrete(name = "EntryResult", params(super::entry::Entry)))]
 #[graphql(concrete(name = "ArticleResults", params(super::article::Model)))]
-#[graphql(concrete(name = "DispatchNoteResults", params(super::dispatch_note::Model)))]
+#[graphql(concrete(
+    name = "DispatchNoteResults",
+    params(super::dispatch_note::DispatchNote)
+))]
 #[graphql(concrete(
     name = "DispatchNoteArticleResults",
     params(super::dispatch_note_article::DispatchNoteArticle)
 ))]
 #[graphql(concrete(name = "WeightTypeResults", params(super::weight_type::Model)))]
+#[graphql(concrete(name = "AuditLogResults", params(super::audit_log::Model)))]
 pub struct QueryResults<T: OutputType> {
     pub results: Vec<T>,
     #[graphql(flatten)]
@@ -137,6 +177,10 @@ where
     fn get_results(&self) -> &[T] {
         self.results.as_ref()
     }
+
+    fn into_results(self) -> Vec<T> {
+        self.results
+    }
 }
 
 #[derive(InputObject)]
@@ -165,6 +209,7 @@ pub struct OrderingOptions<T: InputType> {
     pub order_by: T,
 }
 
+#[allow(clippy::duplicated_attributes)]
 #[derive(InputObject)]
 #[graphql(concrete(name = "BuyerFilterOptions", params(BuyerFields)))]
 #[graphql(concrete(name = "CellFilterOptions", params(CellFields)))]
@@ -196,6 +241,7 @@ type OptionalCellCulturePairIds = Option<CellCulturePairIds>;
 type OptionalDispatchNoteArticleIds = Option<DispatchNoteArticleIds>;
 type OptionalI = Option<i32>;
 
+#[allow(clippy::duplicated_attributes)]
 #[derive(InputObject)]
 #[graphql(concrete(name = "BuyerFetchOptions", params(BuyerFields)))]
 #[graphql(concrete(name = "CellFetchOptions", params(CellFields)))]
@@ -239,6 +285,8 @@ where
     pub ordering: Option<OrderingOptions<O>>,
     pub filters: Option<Vec<Filter<T, V>>>,
     pub d_group: i32,
+    /// Answers from the state versioned entities had at this time
+    pub as_of: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(InputObject)]
@@ -259,13 +307,28 @@ where
     pub mbe_group_id: i32,
 }
 
+#[derive(PartialEq)]
 pub struct DataGroupAccessGuard {
     data_group_id: i32,
+    role: MbeGroupRole,
 }
 
 impl DataGroupAccessGuard {
+    /// Read access, granted to every member of the mbe group
     pub fn new(data_group_id: i32) -> Self {
-        Self { data_group_id }
+        Self::with_role(data_group_id, MbeGroupRole::Viewer)
+    }
+
+    /// Write access, viewers are rejected
+    pub fn write(data_group_id: i32) -> Self {
+        Self::with_role(data_group_id, MbeGroupRole::Editor)
+    }
+
+    pub fn with_role(data_group_id: i32, role: MbeGroupRole) -> Self {
+        Self {
+            data_group_id,
+            role,
+        }
     }
 }
 
@@ -273,37 +336,161 @@ impl DataGroupAccessGuard {
 impl Guard for DataGroupAccessGuard {
     async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        // let session_data = ctx.data::<SessionData>()?;
+        let session_data = ctx.data::<SessionData>()?;
+
+        match data_group_role(db, session_data, self.data_group_id).await? {
+            Some(role) if role.grants(self.role) => Ok(()),
+            _ => Err(AuthError::Unauthorized.into()),
+        }
+    }
+}
+
+/// Data group access is granted to all members of the mbe group owning the data group
+pub async fn has_data_group_access(
+    db: &DatabaseConnection,
+    session_data: &SessionData,
+    data_group_id: i32,
+) -> Result<bool, DbErr> {
+    Ok(data_group_role(db, session_data, data_group_id)
+        .await?
+        .is_some())
+}
+
+/// Role of the user in the mbe group owning the data group, `None` if the user is not a member
+pub async fn data_group_role(
+    db: &DatabaseConnection,
+    session_data: &SessionData,
+    data_group_id: i32,
+) -> Result<Option<MbeGroupRole>, DbErr> {
+    let transaction = db.begin().await?;
+
+    let id_mbe_group = data_group::Entity::find()
+        .filter(data_group::Column::Id.eq(data_group_id))
+        .one(&transaction)
+        .await?
+        .map(|data_group| data_group.id_mbe_group);
+
+    transaction.commit().await?;
+
+    match id_mbe_group {
+        Some(id_mbe_group) => mbe_group_role(db, session_data, id_mbe_group).await,
+        None => Ok(None),
+    }
+}
+
+/// Checks if the user owns the mbe group the data group belongs to
+pub async fn is_data_group_owner(
+    db: &DatabaseConnection,
+    user_id: i32,
+    data_group_id: i32,
+) -> Result<bool, DbErr> {
+    let transaction = db.begin().await?;
+
+    let owner = data_group::Entity::find_by_id(data_group_id)
+        .find_also_related(mbe_group::Entity)
+        .one(&transaction)
+        .await?
+        .and_then(|(_, mbe_group)| mbe_group)
+        .map(|mbe_group| mbe_group.owner);
+
+    transaction.commit().await?;
+
+    Ok(owner == Some(user_id))
+}
+
+pub struct UpdateDeleteGuard<T>
+where
+    T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
+    <T as EntityTrait>::Model: GetEntityDataGroupId + CheckEditable,
+{
+    id: i32,
+    phantom: PhantomData<T>,
+}
+
+impl<T> UpdateDeleteGuard<T>
+where
+    T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
+    <T as EntityTrait>::Model: GetEntityDataGroupId + CheckEditable,
+{
+    pub fn new(entity_id: i32) -> Self {
+        Self {
+            id: entity_id,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Guard for UpdateDeleteGuard<T>
+where
+    T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
+    <T as EntityTrait>::Model: GetEntityDataGroupId + CheckEditable,
+{
+    async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
         let transaction = db.begin().await?;
 
-        let id_mbe_group = data_group::Entity::find()
-            .filter(data_group::Column::Id.eq(self.data_group_id))
+        let model = <T as EntityTrait>::find()
+            .filter(<T as GetEntityId<<T as EntityTrait>::Column>>::get_id_column().eq(self.id))
             .one(&transaction)
             .await?
-            .ok_or(AuthError::Unauthorized)?
-            .id_mbe_group;
+            .ok_or(AuthError::Unauthorized)?;
+
+        DataGroupAccessGuard::write(model.get_data_group_id())
+            .check(ctx)
+            .await?;
+
+        model.check_editable(&transaction).await?;
 
         transaction.commit().await?;
 
-        MbeGroupAccessGuard::new(id_mbe_group).check(ctx).await?;
+        Ok(())
+    }
+}
 
-        // let is_group_member = mbe_group_members::Entity::find()
-        //     .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
-        //     .filter(mbe_group_members::Column::IdMbeGroup.eq(id_mbe_group))
-        //     .one(&transaction)
-        //     .await?;
+impl<T> PartialEq for UpdateDeleteGuard<T>
+where
+    T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
+    <T as EntityTrait>::Model: GetEntityDataGroupId + CheckEditable,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
 
-        // if is_group_member.is_some() {
-        //     Ok(())
-        // } else {
-        //     Err(AuthError::Unauthorized.into())
-        // }
+/// Passes when every guard passes, used by batch mutations to check each item they touch
+pub struct EveryGuard<G>(Vec<G>);
+
+impl<G: PartialEq> EveryGuard<G> {
+    /// Equal guards are only checked once
+    pub fn new(guards: impl IntoIterator<Item = G>) -> Self {
+        let mut unique = Vec::new();
+        for guard in guards {
+            if !unique.contains(&guard) {
+                unique.push(guard);
+            }
+        }
+        Self(unique)
+    }
+}
 
+#[async_trait]
+impl<G> Guard for EveryGuard<G>
+where
+    G: Guard + Send + Sync,
+{
+    async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
+        for guard in &self.0 {
+            guard.check(ctx).await?;
+        }
         Ok(())
     }
 }
 
-pub struct UpdateDeleteGuard<T>
+/// Grants write access to a single entity to members of its data group
+/// without checking whether the entity can still be edited
+pub struct EntityAccessGuard<T>
 where
     T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
     <T as EntityTrait>::Model: GetEntityDataGroupId,
@@ -312,7 +499,7 @@ where
     phantom: PhantomData<T>,
 }
 
-impl<T> UpdateDeleteGuard<T>
+impl<T> EntityAccessGuard<T>
 where
     T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
     <T as EntityTrait>::Model: GetEntityDataGroupId,
@@ -326,7 +513,7 @@ where
 }
 
 #[async_trait]
-impl<T> Guard for UpdateDeleteGuard<T>
+impl<T> Guard for EntityAccessGuard<T>
 where
     T: EntityTrait + GetEntityId<<T as EntityTrait>::Column>,
     <T as EntityTrait>::Model: GetEntityDataGroupId,
@@ -345,9 +532,54 @@ where
 
         transaction.commit().await?;
 
-        DataGroupAccessGuard::new(d_group).check(ctx).await?;
+        DataGroupAccessGuard::write(d_group).check(ctx).await
+    }
+}
 
-        Ok(())
+/// Grants access to administrators of the whole instance, never through personal access tokens
+pub struct AdminGuard;
+
+#[async_trait]
+impl Guard for AdminGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = ctx.data::<SessionData>()?;
+
+        if session_data.token.is_some() {
+            return Err(AuthError::Unauthorized.into());
+        }
+
+        let transaction = db.begin().await?;
+
+        let is_admin = mbe_user::Entity::find_by_id(session_data.user_id)
+            .one(&transaction)
+            .await?
+            .is_some_and(|user| user.is_admin);
+
+        transaction.commit().await?;
+
+        if is_admin {
+            Ok(())
+        } else {
+            Err(AuthError::Unauthorized.into())
+        }
+    }
+}
+
+/// Rejects requests authenticated with a personal access token,
+/// used where the account itself is managed
+pub struct CookieSessionGuard;
+
+#[async_trait]
+impl Guard for CookieSessionGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
+        let session_data = ctx.data::<SessionData>()?;
+
+        if session_data.token.is_none() {
+            Ok(())
+        } else {
+            Err(AuthError::Unauthorized.into())
+        }
     }
 }
 
@@ -359,11 +591,22 @@ pub fn extract_session<'a>(ctx: &Context<'a>) -> Result<&'a SessionData, AuthErr
 
 pub struct MbeGroupAccessGuard {
     id_mbe_group: i32,
+    role: MbeGroupRole,
 }
 
 impl MbeGroupAccessGuard {
+    /// Read access, granted to every member of the mbe group
     pub fn new(id_mbe_group: i32) -> Self {
-        Self { id_mbe_group }
+        Self::with_role(id_mbe_group, MbeGroupRole::Viewer)
+    }
+
+    /// Write access, viewers are rejected
+    pub fn write(id_mbe_group: i32) -> Self {
+        Self::with_role(id_mbe_group, MbeGroupRole::Editor)
+    }
+
+    pub fn with_role(id_mbe_group: i32, role: MbeGroupRole) -> Self {
+        Self { id_mbe_group, role }
     }
 }
 
@@ -372,20 +615,41 @@ impl Guard for MbeGroupAccessGuard {
     async fn check(&self, ctx: &Context<'_>) -> Result<(), async_graphql::Error> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         let session_data = ctx.data::<SessionData>()?;
-        let transaction = db.begin().await?;
-
-        let is_group_member = mbe_group_members::Entity::find()
-            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
-            .filter(mbe_group_members::Column::IdMbeGroup.eq(self.id_mbe_group))
-            .one(&transaction)
-            .await?;
 
-        transaction.commit().await?;
-
-        if is_group_member.is_some() {
-            Ok(())
-        } else {
-            Err(AuthError::Unauthorized.into())
+        match mbe_group_role(db, session_data, self.id_mbe_group).await? {
+            Some(role) if role.grants(self.role) => Ok(()),
+            _ => Err(AuthError::Unauthorized.into()),
         }
     }
 }
+
+/// Role of the user in the mbe group, `None` if the user is not a member.
+/// Personal access tokens can not act with a higher role than their scope allows
+pub async fn mbe_group_role(
+    db: &DatabaseConnection,
+    session_data: &SessionData,
+    id_mbe_group: i32,
+) -> Result<Option<MbeGroupRole>, DbErr> {
+    let limit = match &session_data.token {
+        Some(token) => match token.role_limit(id_mbe_group) {
+            Some(limit) => Some(limit),
+            None => return Ok(None),
+        },
+        None => None,
+    };
+
+    let transaction = db.begin().await?;
+
+    let member = mbe_group_members::Entity::find()
+        .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
+        .filter(mbe_group_members::Column::IdMbeGroup.eq(id_mbe_group))
+        .one(&transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(member.map(|member| match limit {
+        Some(limit) => member.role.limit_to(limit),
+        None => member.role,
+    }))
+}
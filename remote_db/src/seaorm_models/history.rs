@@ -0,0 +1,202 @@
+use anyhow::anyhow;
+use async_graphql::{ComplexObject, Context, Guard, Object, SimpleObject};
+use sea_orm::{
+    entity::prelude::*, DatabaseTransaction, DbBackend, FromQueryResult, Statement,
+    TransactionTrait,
+};
+
+use crate::{user_models::mbe_user, SeaOrmPool};
+
+use super::{
+    audit_log::{AuditAction, AuditEntityType},
+    graphql_schema::DataGroupAccessGuard,
+};
+
+/// Versioned table of the entity and the column holding its data group,
+/// weight and dispatch note types belong to mbe groups and are not versioned
+fn history_table(entity_type: AuditEntityType) -> Option<(&'static str, &'static str)> {
+    match entity_type {
+        AuditEntityType::Article => Some(("article_history", "d_group")),
+        AuditEntityType::Buyer => Some(("buyer_history", "d_group")),
+        AuditEntityType::Cell => Some(("cell_history", "d_group")),
+        AuditEntityType::CellCulturePair => Some(("cell_culture_pair_history", "d_group")),
+        AuditEntityType::Culture => Some(("culture_history", "d_group")),
+        AuditEntityType::DataGroup => Some(("data_group_history", "id")),
+        AuditEntityType::DispatchNote => Some(("dispatch_note_history", "d_group")),
+        AuditEntityType::DispatchNoteArticle => Some(("dispatch_note_article_history", "d_group")),
+        AuditEntityType::Entry => Some(("entry_history", "d_group")),
+        _ => None,
+    }
+}
+
+/// Closes the current version of the entity and, unless it was deleted,
+/// copies the row as it is now into a new version authored by `id_mbe_user`,
+/// versions without an author are written by the server itself
+pub(super) async fn record_version<C: ConnectionTrait>(
+    db: &C,
+    entity_type: AuditEntityType,
+    id: i32,
+    action: AuditAction,
+    id_mbe_user: Option<i32>,
+) -> Result<(), DbErr> {
+    let (history_table, _) = match history_table(entity_type) {
+        Some(table) => table,
+        None => return Ok(()),
+    };
+    let entity_table = history_table.trim_end_matches("_history");
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        &format!(
+            r#"UPDATE "{}" SET "valid_to" = clock_timestamp() WHERE "id" = $1 AND "valid_to" IS NULL"#,
+            history_table
+        ),
+        [id.into()],
+    ))
+    .await?;
+
+    if action == AuditAction::Delete {
+        return Ok(());
+    }
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        &format!(
+            r#"
+            INSERT INTO "{history}"
+            SELECT (jsonb_populate_record(
+                NULL::"{history}",
+                to_jsonb("{entity}") || jsonb_build_object(
+                    'valid_from', clock_timestamp(),
+                    'id_mbe_user', $2::INT
+                )
+            )).*
+            FROM "{entity}"
+            WHERE "{entity}"."id" = $1
+            "#,
+            history = history_table,
+            entity = entity_table
+        ),
+        [id.into(), id_mbe_user.into()],
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Starts a transaction that reads versioned tables as they were at `as_of`,
+/// tables without history are read as they are now
+pub async fn begin_as_of(
+    db: &DatabaseConnection,
+    as_of: Option<DateTimeWithTimeZone>,
+) -> Result<DatabaseTransaction, DbErr> {
+    let transaction = db.begin().await?;
+
+    if let Some(as_of) = as_of {
+        transaction
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    set_config('search_path', 'as_of, ' || current_setting('search_path'), true),
+                    set_config('mbe.as_of', $1, true)
+                "#,
+                [as_of.to_rfc3339().into()],
+            ))
+            .await?;
+    }
+
+    Ok(transaction)
+}
+
+#[derive(Debug, FromQueryResult, SimpleObject)]
+#[graphql(complex)]
+pub struct EntityVersion {
+    /// Starts at 1 with the oldest known version
+    pub version: i64,
+    pub valid_from: DateTimeWithTimeZone,
+    /// Missing for the current version
+    pub valid_to: Option<DateTimeWithTimeZone>,
+    /// Missing for versions that existed before history was kept
+    pub id_mbe_user: Option<i32>,
+    #[graphql(skip)]
+    pub d_group: i32,
+    #[graphql(skip)]
+    pub data: Json,
+}
+
+#[ComplexObject]
+impl EntityVersion {
+    /// Entity as it was stored in this version
+    async fn data(&self) -> async_graphql::Json<Json> {
+        async_graphql::Json(self.data.clone())
+    }
+
+    async fn author(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<mbe_user::Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        Ok(match self.id_mbe_user {
+            Some(id_mbe_user) => {
+                mbe_user::Entity::find_by_id(id_mbe_user)
+                    .one(db.get_ref())
+                    .await?
+            }
+            None => None,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct HistoryQuery;
+
+#[Object]
+impl HistoryQuery {
+    /// Every version of the entity, oldest first
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        entity: AuditEntityType,
+        id: i32,
+    ) -> async_graphql::Result<Vec<EntityVersion>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let (history_table, data_group_column) =
+            history_table(entity).ok_or_else(|| anyhow!("History is not kept for this entity"))?;
+
+        let transaction = db.begin().await?;
+
+        let versions = EntityVersion::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT
+                    row_number() OVER (ORDER BY "valid_from") AS "version",
+                    "valid_from",
+                    "valid_to",
+                    "id_mbe_user",
+                    "{data_group}" AS "d_group",
+                    to_jsonb("{history}") - 'valid_from' - 'valid_to' - 'id_mbe_user' AS "data"
+                FROM "{history}"
+                WHERE "id" = $1
+                ORDER BY "valid_from"
+                "#,
+                history = history_table,
+                data_group = data_group_column
+            ),
+            [id.into()],
+        ))
+        .all(&transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        let mut d_groups: Vec<i32> = versions.iter().map(|version| version.d_group).collect();
+        d_groups.sort_unstable();
+        d_groups.dedup();
+        for d_group in d_groups {
+            DataGroupAccessGuard::new(d_group).check(ctx).await?;
+        }
+
+        Ok(versions)
+    }
+}
@@ -0,0 +1,218 @@
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use sea_orm::{
+    entity::prelude::*, sea_query::Expr, FromQueryResult, Order, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::SeaOrmPool;
+
+use super::{
+    entry::{self, EntryFields, ENTRY_WEIGHT_KG},
+    graphql_schema::{DataGroupAccessGuard, Filter},
+    QueryDatabase,
+};
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum MassBalanceDimension {
+    Cell,
+    Culture,
+    CellCulturePair,
+    Buyer,
+}
+
+#[derive(InputObject)]
+pub struct MassBalanceReportOptions {
+    pub d_group: i32,
+    /// Entries are grouped by every listed dimension,
+    /// an empty list returns a single row with the totals of the data group
+    pub group_by: Vec<MassBalanceDimension>,
+    pub date_from: Option<DateTimeWithTimeZone>,
+    pub date_to: Option<DateTimeWithTimeZone>,
+    pub filters: Option<Vec<Filter<EntryFields>>>,
+}
+
+#[derive(Debug, Clone, FromQueryResult, SimpleObject)]
+pub struct MassBalanceReportRow {
+    pub id_cell: Option<i32>,
+    pub name_cell: Option<String>,
+    pub id_culture: Option<i32>,
+    pub name_culture: Option<String>,
+    pub id_cell_culture_pair: Option<i32>,
+    pub id_buyer: Option<i32>,
+    pub name_buyer: Option<String>,
+
+    /// Weights are converted to kilograms,
+    /// entries in units that can't be converted to kilograms are left out
+    pub total_weight: f64,
+    /// Entries included in the total weight
+    pub entry_count: i64,
+    /// Entries left out because their unit can't be converted to kilograms
+    pub unconverted_count: i64,
+    pub min_weight: Option<f64>,
+    pub max_weight: Option<f64>,
+    /// Share of the rows total weight in the total weight of all rows, between 0 and 1
+    pub share_of_total: f64,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct MassBalanceTotals {
+    total_weight: f64,
+    entry_count: i64,
+    unconverted_count: i64,
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct MassBalanceReport {
+    pub rows: Vec<MassBalanceReportRow>,
+    pub total_weight: f64,
+    pub entry_count: i64,
+    pub unconverted_count: i64,
+}
+
+impl MassBalanceReportOptions {
+    fn includes(&self, dimension: MassBalanceDimension) -> bool {
+        self.group_by.contains(&dimension)
+    }
+}
+
+fn add_date_and_filters(
+    mut query: Select<entry::Entity>,
+    options: &mut MassBalanceReportOptions,
+) -> Select<entry::Entity> {
+    query = query.filter(entry::Column::DGroup.eq(options.d_group));
+    if let Some(date_from) = options.date_from {
+        query = query.filter(entry::Column::Date.gte(date_from.date_naive()));
+    }
+    if let Some(date_to) = options.date_to {
+        query = query.filter(entry::Column::Date.lte(date_to.date_naive()));
+    }
+    entry::Entity::add_filters(query, options.filters.take())
+}
+
+fn add_dimensions(
+    mut query: Select<entry::Entity>,
+    options: &MassBalanceReportOptions,
+) -> Select<entry::Entity> {
+    let by_pair = options.includes(MassBalanceDimension::CellCulturePair);
+
+    query = if by_pair || options.includes(MassBalanceDimension::Cell) {
+        query
+            .column_as(super::cell::Column::Id, "id_cell")
+            .column_as(super::cell::Column::Name, "name_cell")
+            .group_by(super::cell::Column::Id)
+            .group_by(super::cell::Column::Name)
+    } else {
+        query
+            .column_as(Expr::cust("NULL::INT"), "id_cell")
+            .column_as(Expr::cust("NULL::TEXT"), "name_cell")
+    };
+
+    query = if by_pair || options.includes(MassBalanceDimension::Culture) {
+        query
+            .column_as(super::culture::Column::Id, "id_culture")
+            .column_as(super::culture::Column::Name, "name_culture")
+            .group_by(super::culture::Column::Id)
+            .group_by(super::culture::Column::Name)
+    } else {
+        query
+            .column_as(Expr::cust("NULL::INT"), "id_culture")
+            .column_as(Expr::cust("NULL::TEXT"), "name_culture")
+    };
+
+    query = if by_pair {
+        query
+            .column_as(entry::Column::IdCellCulturePair, "id_cell_culture_pair")
+            .group_by(entry::Column::IdCellCulturePair)
+    } else {
+        query.column_as(Expr::cust("NULL::INT"), "id_cell_culture_pair")
+    };
+
+    if options.includes(MassBalanceDimension::Buyer) {
+        query
+            .column_as(super::buyer::Column::Id, "id_buyer")
+            .column_as(super::buyer::Column::Name, "name_buyer")
+            .group_by(super::buyer::Column::Id)
+            .group_by(super::buyer::Column::Name)
+    } else {
+        query
+            .column_as(Expr::cust("NULL::INT"), "id_buyer")
+            .column_as(Expr::cust("NULL::TEXT"), "name_buyer")
+    }
+}
+
+/// Total weight in kilograms and the number of entries with and without a weight in kilograms
+fn add_totals(query: Select<entry::Entity>) -> Select<entry::Entity> {
+    query
+        .column_as(
+            Expr::cust(&format!("COALESCE(SUM({}), 0)", ENTRY_WEIGHT_KG)),
+            "total_weight",
+        )
+        .column_as(
+            Expr::cust(&format!("COUNT({})", ENTRY_WEIGHT_KG)),
+            "entry_count",
+        )
+        .column_as(
+            Expr::cust(&format!("COUNT(*) - COUNT({})", ENTRY_WEIGHT_KG)),
+            "unconverted_count",
+        )
+}
+
+fn add_aggregates(query: Select<entry::Entity>) -> Select<entry::Entity> {
+    add_totals(query)
+        .column_as(Expr::cust(&format!("MIN({})", ENTRY_WEIGHT_KG)), "min_weight")
+        .column_as(Expr::cust(&format!("MAX({})", ENTRY_WEIGHT_KG)), "max_weight")
+        .column_as(
+            Expr::cust(&format!(
+                "COALESCE(SUM({0}) / NULLIF(SUM(SUM({0})) OVER (), 0), 0)",
+                ENTRY_WEIGHT_KG
+            )),
+            "share_of_total",
+        )
+}
+
+#[derive(Default)]
+pub struct MassBalanceQuery;
+
+#[Object]
+impl MassBalanceQuery {
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn mass_balance_report(
+        &self,
+        ctx: &Context<'_>,
+        mut options: MassBalanceReportOptions,
+    ) -> Result<MassBalanceReport> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let mut query = entry::Entity::get_query().select_only();
+        query = add_date_and_filters(query, &mut options);
+
+        let totals_query = add_totals(query.clone());
+
+        query = add_dimensions(query, &options);
+        query = add_aggregates(query).order_by(Expr::cust("total_weight"), Order::Desc);
+
+        let transaction = db.begin().await?;
+
+        let rows = query
+            .into_model::<MassBalanceReportRow>()
+            .all(&transaction)
+            .await?;
+
+        let totals = totals_query
+            .into_model::<MassBalanceTotals>()
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Mass balance totals not found"))?;
+
+        transaction.commit().await?;
+
+        Ok(MassBalanceReport {
+            rows,
+            total_weight: totals.total_weight,
+            entry_count: totals.entry_count,
+            unconverted_count: totals.unconverted_count,
+        })
+    }
+}
@@ -1,35 +1,112 @@
-use async_graphql::{InputType, OutputType, SimpleObject};
+use std::{env, sync::OnceLock};
+
+use async_graphql::{connection::Edge, InputType, OutputType, SimpleObject};
 use async_trait::async_trait;
 
 use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
     ColumnTrait, DatabaseConnection, DatabaseTransaction, DeleteResult, EntityTrait,
     FromQueryResult, ItemsAndPagesNumber, ModelTrait, Order, Paginator, PaginatorTrait,
-    QueryFilter, QueryOrder, Select, SelectModel, TransactionTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, SelectModel, TransactionTrait,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use self::graphql_schema::{
-    DeleteOptions, FetchOptions, Filter, OrderingOptions, Pagination, QueryResults,
-};
+use crate::auth::SessionData;
 
-pub mod prelude;
+use self::{
+    batch::BatchError,
+    connection::{
+        reverse, ConnectionArgs, ConnectionFields, CursorRow, OrderingColumn, QueryConnection,
+        CURSOR_ID, CURSOR_VALUE,
+    },
+    graphql_schema::{
+        DeleteOptions, FetchOptions, Filter, OrderingOptions, Pagination, QueryResults,
+    },
+};
 
 pub mod article;
+pub mod audit_log;
+pub mod batch;
 pub mod buyer;
 pub mod cell;
 pub mod cell_culture_pair;
+pub mod connection;
 pub mod culture;
 pub mod data_group;
+pub mod data_group_events;
 pub mod dispatch_note;
 pub mod dispatch_note_article;
 pub mod dispatch_note_ident_tracker;
+pub mod dispatch_note_type;
 pub mod entry;
 pub mod graphql_schema;
+pub mod history;
+pub mod mass_balance;
+pub mod reconciliation;
+pub mod trash;
+pub mod unit_conversion;
 pub mod weight_type;
 
-const MAX_PAGE_SIZE: u64 = 100;
 const DEFAULT_PAGE_SIZE: u64 = 10;
+const MAX_PAGE_SIZE: u64 = 500;
+
+static PAGE_SIZE_LIMITS: OnceLock<PageSizeLimits> = OnceLock::new();
+
+/// Page sizes clients can ask for, the same limits apply to pages and connection chunks
+#[derive(Clone, Copy, Debug)]
+pub struct PageSizeLimits {
+    pub default: u64,
+    /// Most rows a single page or chunk returns, larger sizes are clamped to it
+    pub max: u64,
+}
+
+impl PageSizeLimits {
+    /// Reads `DEFAULT_PAGE_SIZE` and `MAX_PAGE_SIZE`, defaults to 10 and 500 rows
+    fn from_env() -> Self {
+        let limits = Self {
+            default: page_size_from_env("DEFAULT_PAGE_SIZE", DEFAULT_PAGE_SIZE),
+            max: page_size_from_env("MAX_PAGE_SIZE", MAX_PAGE_SIZE),
+        };
+        assert!(
+            limits.default <= limits.max,
+            "DEFAULT_PAGE_SIZE must not be larger than MAX_PAGE_SIZE"
+        );
+        limits
+    }
+
+    /// Limits read from the environment on startup
+    pub fn get() -> &'static Self {
+        PAGE_SIZE_LIMITS.get_or_init(Self::from_env)
+    }
+
+    /// Missing or zero sizes get the default, sizes over the maximum are clamped to it
+    pub fn clamp(&self, page_size: Option<u64>) -> u64 {
+        match page_size {
+            None | Some(0) => self.default,
+            Some(page_size) => page_size.min(self.max),
+        }
+    }
+
+    /// Same as `clamp` for the `first` or `last` of a connection, except that zero returns no edges
+    pub fn clamp_connection(&self, page_size: Option<usize>) -> u64 {
+        match page_size {
+            Some(0) => 0,
+            page_size => self.clamp(page_size.map(|size| size as u64)),
+        }
+    }
+}
+
+fn page_size_from_env(name: &str, default: u64) -> u64 {
+    match env::var(name) {
+        Ok(page_size) => page_size
+            .parse()
+            .ok()
+            .filter(|page_size| *page_size > 0)
+            .unwrap_or_else(|| panic!("{} must be a positive number of rows", name)),
+        Err(_) => default,
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct Page {
@@ -71,6 +148,18 @@ impl From<DeleteResult> for RowsDeleted {
 
 pub type QueryResultsHelperType<T> = (Vec<T>, ItemsAndPagesNumber, Page, PageSize);
 
+impl Pagination {
+    pub fn new(page: Page, page_size: PageSize, items_and_pages: ItemsAndPagesNumber) -> Self {
+        Self {
+            page: page.page,
+            page_size: page_size.0,
+            effective_page_size: page_size.0,
+            total_items: items_and_pages.number_of_items,
+            total_pages: items_and_pages.number_of_pages,
+        }
+    }
+}
+
 impl<T> From<QueryResultsHelperType<T>> for QueryResults<T>
 where
     T: OutputType,
@@ -79,12 +168,7 @@ where
         let (results, items_and_page_number, page, page_size) = inp;
         Self {
             results,
-            pagination: Pagination {
-                page: page.page,
-                page_size: page_size.0,
-                total_items: items_and_page_number.number_of_items,
-                total_pages: items_and_page_number.number_of_pages,
-            },
+            pagination: Pagination::new(page, page_size, items_and_page_number),
         }
     }
 }
@@ -117,13 +201,30 @@ where
 
     type FilterValueType: InputType;
 
+    /// Set on soft deleted entities, their rows are hidden from `get_query`
+    /// and only listed in the trash until they are purged
+    fn get_deleted_at_column() -> Option<<Self as EntityTrait>::Column> {
+        None
+    }
+
+    /// Query with everything needed for `FetchModel`, including soft deleted rows
+    fn get_query_with_deleted() -> Select<Self> {
+        <Self as EntityTrait>::find()
+    }
+
     // TODO: better name
     fn get_query() -> Select<Self> {
-        <Self as EntityTrait>::find()
+        let query = Self::get_query_with_deleted();
+        match Self::get_deleted_at_column() {
+            Some(deleted_at) => query.filter(deleted_at.is_null()),
+            None => query,
+        }
     }
 
+    /// Deletes are recorded in the audit log by the implementation
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult>;
 
@@ -132,6 +233,11 @@ where
         ordering_options: Option<OrderingOptions<Self::InputFields>>,
     ) -> Select<Self>;
 
+    /// Column and direction `add_ordering` orders by, connections page through it
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order);
+
     fn add_id_and_data_group_filters(
         query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType, Self::FilterValueType>,
@@ -141,7 +247,7 @@ where
         query: Select<Self>,
         transaction: &DatabaseTransaction,
         page_size: PageSize,
-    ) -> Paginator<DatabaseTransaction, SelectModel<<Self as QueryDatabase>::FetchModel>> {
+    ) -> Paginator<'_, DatabaseTransaction, SelectModel<<Self as QueryDatabase>::FetchModel>> {
         query
             .into_model::<Self::FetchModel>()
             .paginate(transaction, page_size.0)
@@ -180,6 +286,38 @@ where
         query = Self::add_ordering(query, fetch_options.ordering);
         query = Self::add_filters(query, fetch_options.filters);
 
+        let transaction = history::begin_as_of(db, fetch_options.as_of).await?;
+
+        let paginator = Self::paginate_query(query, &transaction, page_size);
+        let res = paginator.fetch_page(page.index).await?;
+        let num_items_and_pages = paginator.num_items_and_pages().await?;
+
+        transaction.commit().await?;
+        Ok((res, num_items_and_pages, page, page_size).into())
+    }
+
+    /// Soft deleted rows of the data group, most recently deleted first
+    async fn fetch_deleted(
+        db: &DatabaseConnection,
+        d_group: i32,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<Self::QueryResultType>
+    where
+        Self: GetEntityDataGroupColumnTrait<<Self as EntityTrait>::Column>,
+        Self::QueryResultType: From<QueryResultsHelperType<Self::FetchModel>>,
+    {
+        let deleted_at = Self::get_deleted_at_column()
+            .ok_or_else(|| anyhow::anyhow!("Entity can not be soft deleted"))?;
+
+        let page_size = PageSize(calculate_page_size(page_size));
+        let page: Page = page.into();
+
+        let query = Self::get_query_with_deleted()
+            .filter(Self::get_data_group_column().eq(d_group))
+            .filter(deleted_at.is_not_null())
+            .order_by_desc(deleted_at);
+
         let transaction = db.begin().await?;
 
         let paginator = Self::paginate_query(query, &transaction, page_size);
@@ -190,18 +328,145 @@ where
         Ok((res, num_items_and_pages, page, page_size).into())
     }
 
+    /// Connection over the same results as `fetch`, `page` and `page_size` of the options are
+    /// ignored in favour of the connection arguments
+    async fn fetch_connection(
+        db: &DatabaseConnection,
+        fetch_options: FetchOptions<Self::InputFields, Self::FetchIdType, Self::FilterValueType>,
+        args: ConnectionArgs,
+    ) -> Result<QueryConnection<Self::InnerQueryResultType>>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+    {
+        let mut query = Self::get_query();
+
+        query = Self::add_id_and_data_group_filters(query, &fetch_options);
+        query = Self::add_filters(query, fetch_options.filters);
+
+        let transaction = history::begin_as_of(db, fetch_options.as_of).await?;
+
+        let connection =
+            Self::fetch_connection_page(&transaction, query, fetch_options.ordering, args).await?;
+
+        transaction.commit().await?;
+        Ok(connection)
+    }
+
+    /// Page of a connection over the filtered but not yet ordered `query`,
+    /// rows are found by their cursors instead of an offset
+    async fn fetch_connection_page(
+        transaction: &DatabaseTransaction,
+        query: Select<Self>,
+        ordering_options: Option<OrderingOptions<Self::InputFields>>,
+        args: ConnectionArgs,
+    ) -> Result<QueryConnection<Self::InnerQueryResultType>>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+    {
+        let total_count = if args.with_total_count {
+            Some(query.clone().count(transaction).await?)
+        } else {
+            None
+        };
+
+        let (column, order) = Self::get_ordering_column(&ordering_options);
+        let id: SimpleExpr = Expr::col((Self::default(), Self::get_id_column())).into();
+        let page_size = PageSizeLimits::get().clamp_connection(args.first.or(args.last));
+
+        let mut query = query
+            .column_as(column.as_text(), CURSOR_VALUE)
+            .column_as(id.clone(), CURSOR_ID);
+        for condition in args.conditions(&column, id.clone(), &order) {
+            query = query.filter(condition);
+        }
+
+        let order = if args.is_backward() {
+            reverse(&order)
+        } else {
+            order
+        };
+        let mut rows = query
+            .order_by(column.expr(), order.clone())
+            .order_by(id, order)
+            .limit(page_size + 1)
+            .into_model::<CursorRow<Self::FetchModel>>()
+            .all(transaction)
+            .await?;
+
+        let has_more = rows.len() as u64 > page_size;
+        rows.truncate(page_size as usize);
+        let (has_previous_page, has_next_page) = if args.is_backward() {
+            rows.reverse();
+            (has_more, args.before.is_some())
+        } else {
+            (args.after.is_some(), has_more)
+        };
+
+        let (cursors, models): (Vec<_>, Vec<_>) =
+            rows.into_iter().map(|row| (row.cursor, row.model)).unzip();
+
+        let mut connection = QueryConnection::with_additional_fields(
+            has_previous_page,
+            has_next_page,
+            ConnectionFields {
+                total_count,
+                effective_page_size: page_size,
+            },
+        );
+        connection.edges.extend(
+            cursors
+                .into_iter()
+                .zip(Self::results_from_models(models))
+                .map(|(cursor, node)| Edge::new(cursor, node)),
+        );
+        Ok(connection)
+    }
+
+    /// Results of fetched models outside of a paginated query
+    fn results_from_models(models: Vec<Self::FetchModel>) -> Vec<Self::InnerQueryResultType> {
+        let number_of_items = models.len() as u64;
+        let results: Self::QueryResultType = (
+            models,
+            ItemsAndPagesNumber {
+                number_of_items,
+                number_of_pages: 1,
+            },
+            Page::from(None),
+            PageSize(number_of_items),
+        )
+            .into();
+        results.into_results()
+    }
+
     async fn update_entity(
         db: &DatabaseConnection,
+        session_data: &SessionData,
         update_options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType>;
+    ) -> Result<Self::InnerQueryResultType>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+        Self::UpdateOptions: Send,
+    {
+        let transaction = db.begin().await?;
+
+        let res = Self::update_query(&transaction, session_data, update_options).await?;
+        let res = Self::fetch_by_ids(&transaction, &[model_id::<Self>(&res)])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Updated entity not found"))?;
+
+        transaction.commit().await?;
+        Ok(res)
+    }
 
     async fn delete_entity(
         db: &DatabaseConnection,
+        session_data: &SessionData,
         delete_options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<RowsDeleted> {
         let transaction = db.begin().await?;
 
-        let res = Self::delete_query(&transaction, delete_options).await?;
+        let res = Self::delete_query(&transaction, session_data, delete_options).await?;
 
         transaction.commit().await?;
 
@@ -214,21 +479,208 @@ where
 
     async fn insert_entity(
         db: &DatabaseConnection,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType>;
+    ) -> Result<Self::InnerQueryResultType>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+        Self::InsertOptions: Send,
+    {
+        let transaction = db.begin().await?;
+
+        let res = Self::insert_query(&transaction, session_data, options).await?;
+        let res = Self::fetch_by_ids(&transaction, &[model_id::<Self>(&res)])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Inserted entity not found"))?;
+
+        transaction.commit().await?;
+        Ok(res)
+    }
+
+    /// Inserts are recorded in the audit log by the implementation
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
+        options: Self::InsertOptions,
+    ) -> Result<<Self as EntityTrait>::Model>;
+
+    /// Updates are recorded in the audit log by the implementation
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
+        options: Self::UpdateOptions,
+    ) -> Result<<Self as EntityTrait>::Model>;
+
+    /// Results for `ids` in the same order, read through `transaction`
+    /// so rows written by it are included
+    async fn fetch_by_ids(
+        transaction: &DatabaseTransaction,
+        ids: &[i32],
+    ) -> Result<Vec<Self::InnerQueryResultType>>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+    {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let positions = ids
+            .iter()
+            .map(i32::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let res = Self::get_query()
+            .filter(Self::get_id_column().is_in(ids.iter().copied()))
+            .order_by(
+                Expr::cust_with_expr(
+                    &format!("array_position(ARRAY[{}], $1)", positions),
+                    Expr::col((Self::default(), Self::get_id_column())),
+                ),
+                Order::Asc,
+            )
+            .into_model::<Self::FetchModel>()
+            .all(transaction)
+            .await?;
+
+        Ok(Self::results_from_models(res))
+    }
+
+    /// Inserts all items in one transaction, nothing is saved unless every item succeeds
+    async fn insert_entities(
+        db: &DatabaseConnection,
+        session_data: &SessionData,
+        options: Vec<Self::InsertOptions>,
+    ) -> Result<Vec<Self::InnerQueryResultType>, BatchError>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+        Self::InsertOptions: Send,
+    {
+        let transaction = db.begin().await?;
+        let mut ids = Vec::with_capacity(options.len());
+        let mut errors = Vec::new();
+
+        for (index, options) in options.into_iter().enumerate() {
+            // Failed items roll back to their savepoint so the remaining items are still checked
+            let savepoint = transaction.begin().await?;
+            match Self::insert_query(&savepoint, session_data, options).await {
+                Ok(res) => {
+                    savepoint.commit().await?;
+                    ids.push(model_id::<Self>(&res));
+                }
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    errors.push((index, e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            transaction.rollback().await?;
+            return Err(BatchError::Items(errors));
+        }
+
+        let res = Self::fetch_by_ids(&transaction, &ids).await?;
+
+        transaction.commit().await?;
+        Ok(res)
+    }
+
+    /// Updates all items in one transaction, nothing is saved unless every item succeeds
+    async fn update_entities(
+        db: &DatabaseConnection,
+        session_data: &SessionData,
+        options: Vec<Self::UpdateOptions>,
+    ) -> Result<Vec<Self::InnerQueryResultType>, BatchError>
+    where
+        Self: GetEntityId<<Self as EntityTrait>::Column>,
+        Self::UpdateOptions: Send,
+    {
+        let transaction = db.begin().await?;
+        let mut ids = Vec::with_capacity(options.len());
+        let mut errors = Vec::new();
+
+        for (index, options) in options.into_iter().enumerate() {
+            let savepoint = transaction.begin().await?;
+            match Self::update_query(&savepoint, session_data, options).await {
+                Ok(res) => {
+                    savepoint.commit().await?;
+                    ids.push(model_id::<Self>(&res));
+                }
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    errors.push((index, e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            transaction.rollback().await?;
+            return Err(BatchError::Items(errors));
+        }
+
+        let res = Self::fetch_by_ids(&transaction, &ids).await?;
+
+        transaction.commit().await?;
+        Ok(res)
+    }
+
+    /// Deletes all items in one transaction, nothing is deleted unless every item succeeds
+    async fn delete_entities(
+        db: &DatabaseConnection,
+        session_data: &SessionData,
+        options: Vec<DeleteOptions<Self::DeleteOptionsType>>,
+    ) -> Result<RowsDeleted, BatchError> {
+        let transaction = db.begin().await?;
+        let mut rows_affected = 0;
+        let mut errors = Vec::new();
+
+        for (index, options) in options.into_iter().enumerate() {
+            let savepoint = transaction.begin().await?;
+            match Self::delete_query(&savepoint, session_data, options).await {
+                Ok(res) if res.rows_affected > 0 => {
+                    savepoint.commit().await?;
+                    rows_affected += res.rows_affected;
+                }
+                Ok(_) => {
+                    savepoint.rollback().await?;
+                    errors.push((index, anyhow!("No rows were deleted")));
+                }
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    errors.push((index, e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            transaction.rollback().await?;
+            return Err(BatchError::Items(errors));
+        }
+
+        transaction.commit().await?;
+        Ok(RowsDeleted {
+            num_rows: rows_affected,
+        })
+    }
 }
 
 pub trait QueryResultsTrait<T> {
+    #[allow(dead_code)]
     fn get_results(&self) -> &[T];
+
+    fn into_results(self) -> Vec<T>;
 }
 
 fn calculate_page_size(page_size: Option<u64>) -> u64 {
-    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
-    if page_size < MAX_PAGE_SIZE {
-        page_size
-    } else {
-        DEFAULT_PAGE_SIZE
-    }
+    PageSizeLimits::get().clamp(page_size)
+}
+
+fn model_id<E>(model: &<E as EntityTrait>::Model) -> i32
+where
+    E: EntityTrait + GetEntityId<<E as EntityTrait>::Column>,
+{
+    model.get(E::get_id_column()).unwrap()
 }
 
 pub fn common_add_ordering<E, T>(
@@ -257,6 +709,26 @@ where
     query
 }
 
+pub fn common_get_ordering_column<E, T>(
+    ordering_options: &Option<OrderingOptions<T>>,
+) -> (OrderingColumn, Order)
+where
+    T: InputType + Copy,
+    E: EntityTrait,
+    <E as EntityTrait>::Column: From<T> + Default,
+{
+    match ordering_options {
+        Some(options) => (
+            OrderingColumn::new(<E as EntityTrait>::Column::from(options.order_by)),
+            options.order.into(),
+        ),
+        None => (
+            OrderingColumn::new(<E as EntityTrait>::Column::default()),
+            Order::Asc,
+        ),
+    }
+}
+
 pub fn common_add_id_and_data_group_filters<E, T, V>(
     mut query: Select<E>,
     fetch_options: &FetchOptions<T, Option<i32>, V>,
@@ -299,3 +771,45 @@ where
 {
     fn get_data_group_id(&self) -> i32;
 }
+
+#[async_trait]
+pub trait CheckEditable
+where
+    Self: ModelTrait + Sync,
+{
+    /// Checked by `UpdateDeleteGuard` once data group access is granted,
+    /// entities are editable unless the implementation says otherwise
+    async fn check_editable(&self, _transaction: &DatabaseTransaction) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: PageSizeLimits = PageSizeLimits {
+        default: 10,
+        max: 500,
+    };
+
+    #[test]
+    fn missing_page_size_gets_default() {
+        assert_eq!(LIMITS.clamp(None), 10);
+        assert_eq!(LIMITS.clamp(Some(0)), 10);
+    }
+
+    #[test]
+    fn page_size_is_clamped_to_max() {
+        assert_eq!(LIMITS.clamp(Some(20)), 20);
+        assert_eq!(LIMITS.clamp(Some(500)), 500);
+        assert_eq!(LIMITS.clamp(Some(10_000)), 500);
+    }
+
+    #[test]
+    fn zero_connection_page_size_stays_zero() {
+        assert_eq!(LIMITS.clamp_connection(Some(0)), 0);
+        assert_eq!(LIMITS.clamp_connection(None), 10);
+        assert_eq!(LIMITS.clamp_connection(Some(10_000)), 500);
+    }
+}
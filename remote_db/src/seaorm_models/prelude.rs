@@ -1,7 +0,0 @@
-pub use super::buyer::Entity as Buyer;
-pub use super::cell::Entity as Cell;
-pub use super::cell_culture_pair::Entity as CellCulturePair;
-pub use super::culture::Entity as Culture;
-pub use super::data_group::Entity as DataGroup;
-pub use super::entry::Entity as Entry;
-pub use super::weight_type::Entity as WeightTypes;
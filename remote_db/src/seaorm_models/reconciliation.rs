@@ -0,0 +1,442 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use sea_orm::{
+    entity::prelude::*,
+    sea_query::{Expr, SimpleExpr},
+    FromQueryResult, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use anyhow::Result;
+
+use crate::SeaOrmPool;
+
+use super::{
+    dispatch_note::{self, DispatchNoteStatus},
+    dispatch_note_article,
+    entry::{self, ENTRY_WEIGHT_KG},
+    graphql_schema::DataGroupAccessGuard,
+    QueryDatabase,
+};
+
+/// Dispatch notes without an issuing date are counted on the day they were created
+const DISPATCH_DATE: &str =
+    r#"COALESCE("dispatch_note"."issuing_date", CAST("dispatch_note"."created_at" AS DATE))"#;
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum ReconciliationPeriod {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl ReconciliationPeriod {
+    fn as_str(&self) -> &str {
+        match self {
+            ReconciliationPeriod::Day => "day",
+            ReconciliationPeriod::Week => "week",
+            ReconciliationPeriod::Month => "month",
+            ReconciliationPeriod::Year => "year",
+        }
+    }
+
+    fn truncate(&self, date_sql: &str) -> SimpleExpr {
+        Expr::cust(&format!(
+            "CAST(date_trunc('{}', {}) AS DATE)",
+            self.as_str(),
+            date_sql
+        ))
+    }
+}
+
+#[derive(InputObject)]
+pub struct ReconciliationOptions {
+    pub d_group: i32,
+    pub period: ReconciliationPeriod,
+    pub date_from: Option<DateTimeWithTimeZone>,
+    pub date_to: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct EntryPeriodTotal {
+    period_start: Date,
+    weight: f64,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct DispatchPeriodTotal {
+    period_start: Date,
+    weight: f64,
+    unconverted_quantity: f64,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ReconciliationRow {
+    pub period_start: Date,
+    /// Summed entry weight in kilograms, entries in units that can't be converted are left out
+    pub entry_weight: f64,
+    /// Summed dispatch note article quantities converted to kilograms
+    pub dispatched_weight: f64,
+    /// Quantities whose weight type isn't a convertible mass, not included in `dispatchedWeight`
+    pub unconverted_quantity: f64,
+    /// `entryWeight - dispatchedWeight`
+    pub difference: f64,
+    /// More was dispatched than was entered
+    pub is_deficit: bool,
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct Reconciliation {
+    pub rows: Vec<ReconciliationRow>,
+    pub entry_weight: f64,
+    pub dispatched_weight: f64,
+    pub unconverted_quantity: f64,
+    pub difference: f64,
+    pub is_deficit: bool,
+}
+
+impl From<Vec<ReconciliationRow>> for Reconciliation {
+    fn from(rows: Vec<ReconciliationRow>) -> Self {
+        let entry_weight: f64 = rows.iter().map(|row| row.entry_weight).sum();
+        let dispatched_weight: f64 = rows.iter().map(|row| row.dispatched_weight).sum();
+        let unconverted_quantity = rows.iter().map(|row| row.unconverted_quantity).sum();
+        let difference = entry_weight - dispatched_weight;
+
+        Self {
+            rows,
+            entry_weight,
+            dispatched_weight,
+            unconverted_quantity,
+            difference,
+            is_deficit: difference < 0.0,
+        }
+    }
+}
+
+fn entry_totals_query(options: &ReconciliationOptions) -> Select<entry::Entity> {
+    let period_start = options.period.truncate(r#""entry"."date""#);
+
+    let mut query = entry::Entity::get_query()
+        .select_only()
+        .column_as(period_start.clone(), "period_start")
+        .column_as(
+            Expr::cust(&format!("COALESCE(SUM({}), 0)", ENTRY_WEIGHT_KG)),
+            "weight",
+        )
+        .filter(entry::Column::DGroup.eq(options.d_group))
+        .group_by(period_start.clone())
+        .order_by_asc(period_start);
+
+    if let Some(date_from) = options.date_from {
+        query = query.filter(entry::Column::Date.gte(date_from.date_naive()));
+    }
+    if let Some(date_to) = options.date_to {
+        query = query.filter(entry::Column::Date.lte(date_to.date_naive()));
+    }
+    query
+}
+
+fn dispatch_totals_query(options: &ReconciliationOptions) -> Select<dispatch_note_article::Entity> {
+    let period_start = options.period.truncate(DISPATCH_DATE);
+
+    let mut query = dispatch_note_article::Entity::get_query()
+        .select_only()
+        .column_as(period_start.clone(), "period_start")
+        .column_as(
+            Expr::cust(
+                r#"COALESCE(SUM("dispatch_note_article"."quantity" * "weight_type"."conversion_factor") FILTER (WHERE "weight_type"."dimension" = 'mass'), 0)"#,
+            ),
+            "weight",
+        )
+        .column_as(
+            Expr::cust(
+                r#"COALESCE(SUM("dispatch_note_article"."quantity") FILTER (WHERE "weight_type"."conversion_factor" IS NULL OR "weight_type"."dimension" <> 'mass'), 0)"#,
+            ),
+            "unconverted_quantity",
+        )
+        .filter(dispatch_note_article::Column::DGroup.eq(options.d_group))
+        .filter(dispatch_note::Column::Status.eq(DispatchNoteStatus::Issued))
+        .group_by(period_start.clone())
+        .order_by_asc(period_start);
+
+    if let Some(date_from) = options.date_from {
+        query = query.filter(Expr::expr(Expr::cust(DISPATCH_DATE)).gte(date_from.date_naive()));
+    }
+    if let Some(date_to) = options.date_to {
+        query = query.filter(Expr::expr(Expr::cust(DISPATCH_DATE)).lte(date_to.date_naive()));
+    }
+    query
+}
+
+/// Entry and dispatched weights of the data group summed per period
+async fn reconcile<C: ConnectionTrait>(
+    db: &C,
+    options: &ReconciliationOptions,
+) -> Result<Reconciliation> {
+    let entry_totals = entry_totals_query(options)
+        .into_model::<EntryPeriodTotal>()
+        .all(db)
+        .await?;
+
+    let dispatch_totals = dispatch_totals_query(options)
+        .into_model::<DispatchPeriodTotal>()
+        .all(db)
+        .await?;
+
+    let mut periods: BTreeMap<Date, (f64, f64, f64)> = BTreeMap::new();
+    for total in entry_totals {
+        periods.entry(total.period_start).or_default().0 += total.weight;
+    }
+    for total in dispatch_totals {
+        let period = periods.entry(total.period_start).or_default();
+        period.1 += total.weight;
+        period.2 += total.unconverted_quantity;
+    }
+
+    Ok(periods
+        .into_iter()
+        .map(
+            |(period_start, (entry_weight, dispatched_weight, unconverted_quantity))| {
+                let difference = entry_weight - dispatched_weight;
+                ReconciliationRow {
+                    period_start,
+                    entry_weight,
+                    dispatched_weight,
+                    unconverted_quantity,
+                    difference,
+                    is_deficit: difference < 0.0,
+                }
+            },
+        )
+        .collect::<Vec<ReconciliationRow>>()
+        .into())
+}
+
+#[derive(Default)]
+pub struct ReconciliationQuery;
+
+#[Object]
+impl ReconciliationQuery {
+    #[graphql(guard = "DataGroupAccessGuard::new(options.d_group)")]
+    async fn entry_dispatch_reconciliation(
+        &self,
+        ctx: &Context<'_>,
+        options: ReconciliationOptions,
+    ) -> Result<Reconciliation> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let transaction = db.begin().await?;
+        let reconciliation = reconcile(&transaction, &options).await?;
+        transaction.commit().await?;
+
+        Ok(reconciliation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+    use crate::test_database::TestDatabase;
+
+    struct Fixture {
+        test_db: TestDatabase,
+        d_group: i32,
+        pair: i32,
+        buyer: i32,
+        article: i32,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let test_db = TestDatabase::new().await;
+            let mbe_group = test_db.mbe_group().await;
+            let d_group = test_db.data_group(mbe_group).await;
+            let cell = test_db
+                .insert(&format!(
+                    "INSERT INTO cell (name, d_group) VALUES ('Cell', {})",
+                    d_group
+                ))
+                .await;
+            let culture = test_db
+                .insert(&format!(
+                    "INSERT INTO culture (name, d_group) VALUES ('Culture', {})",
+                    d_group
+                ))
+                .await;
+            let pair = test_db
+                .insert(&format!(
+                    "INSERT INTO cell_culture_pair (id_cell, id_culture, d_group) VALUES ({}, {}, {})",
+                    cell, culture, d_group
+                ))
+                .await;
+            let buyer = test_db
+                .insert(&format!(
+                    "INSERT INTO buyer (name, d_group) VALUES ('Buyer', {})",
+                    d_group
+                ))
+                .await;
+            let article = test_db
+                .insert(&format!(
+                    "INSERT INTO article (name, d_group) VALUES ('Article', {})",
+                    d_group
+                ))
+                .await;
+
+            Self {
+                test_db,
+                d_group,
+                pair,
+                buyer,
+                article,
+            }
+        }
+
+        async fn entry(&self, date: &str, weight: f64, weight_type: i32) {
+            self.test_db
+                .insert(&format!(
+                    "INSERT INTO entry (weight, weight_type, date, id_buyer, id_cell_culture_pair, d_group) \
+                     VALUES ({}, {}, '{}', {}, {}, {})",
+                    weight, weight_type, date, self.buyer, self.pair, self.d_group
+                ))
+                .await;
+        }
+
+        async fn weight_type(&self, unit_short: &str, conversion_factor: Option<f64>) -> i32 {
+            self.test_db
+                .insert(&format!(
+                    "INSERT INTO weight_type (unit_short, unit, conversion_factor) VALUES ('{}', '{}', {})",
+                    unit_short,
+                    unit_short,
+                    conversion_factor.map_or("NULL".to_string(), |factor| factor.to_string())
+                ))
+                .await
+        }
+
+        async fn dispatch(&self, issuing_date: &str, quantity: f64, weight_type: i32) {
+            self.dispatch_with_status(issuing_date, quantity, weight_type, "issued")
+                .await
+        }
+
+        async fn dispatch_with_status(
+            &self,
+            issuing_date: &str,
+            quantity: f64,
+            weight_type: i32,
+            status: &str,
+        ) {
+            let dispatch_note = self
+                .test_db
+                .insert(&format!(
+                    "INSERT INTO dispatch_note (issuing_date, status, d_group) VALUES ('{}', '{}', {})",
+                    issuing_date, status, self.d_group
+                ))
+                .await;
+            self.test_db
+                .insert(&format!(
+                    "INSERT INTO dispatch_note_article (id_dispatch_note, id_article, weight_type, quantity, d_group) \
+                     VALUES ({}, {}, {}, {}, {})",
+                    dispatch_note, self.article, weight_type, quantity, self.d_group
+                ))
+                .await;
+        }
+
+        fn options(&self, date_from: Option<&str>) -> ReconciliationOptions {
+            ReconciliationOptions {
+                d_group: self.d_group,
+                period: ReconciliationPeriod::Month,
+                date_from: date_from.map(|date| {
+                    Utc.from_utc_datetime(
+                        &NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                            .unwrap()
+                            .and_hms_opt(0, 0, 0)
+                            .unwrap(),
+                    )
+                    .into()
+                }),
+                date_to: None,
+            }
+        }
+    }
+
+    fn date(date: &str) -> Date {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn sums_entries_and_converted_dispatches_per_period() {
+        let fixture = Fixture::new().await;
+        let kg = fixture.weight_type("kg", Some(1.0)).await;
+        let g = fixture.weight_type("g", Some(0.001)).await;
+        let crate_unit = fixture.weight_type("crate", None).await;
+
+        fixture.entry("2026-01-05", 100.0, kg).await;
+        fixture.entry("2026-01-20", 50000.0, g).await;
+        fixture.entry("2026-02-03", 10.0, kg).await;
+        fixture.entry("2026-02-04", 2.0, crate_unit).await;
+        fixture.dispatch("2026-01-10", 40.0, kg).await;
+        fixture.dispatch("2026-01-25", 5000.0, g).await;
+        fixture.dispatch("2026-02-04", 3.0, crate_unit).await;
+        fixture.dispatch("2026-02-05", 25.0, kg).await;
+        // Only issued notes count as dispatched
+        fixture
+            .dispatch_with_status("2026-01-12", 7.0, kg, "draft")
+            .await;
+
+        let reconciliation = reconcile(&fixture.test_db.db, &fixture.options(None))
+            .await
+            .unwrap();
+
+        let rows: Vec<_> = reconciliation
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.period_start,
+                    row.entry_weight,
+                    row.dispatched_weight,
+                    row.unconverted_quantity,
+                    row.is_deficit,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (date("2026-01-01"), 150.0, 45.0, 0.0, false),
+                (date("2026-02-01"), 10.0, 25.0, 3.0, true),
+            ]
+        );
+        assert_eq!(reconciliation.entry_weight, 160.0);
+        assert_eq!(reconciliation.dispatched_weight, 70.0);
+        assert_eq!(reconciliation.unconverted_quantity, 3.0);
+        assert_eq!(reconciliation.difference, 90.0);
+        assert!(!reconciliation.is_deficit);
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn leaves_out_periods_before_date_from() {
+        let fixture = Fixture::new().await;
+        let kg = fixture.weight_type("kg", Some(1.0)).await;
+
+        fixture.entry("2026-01-05", 100.0, kg).await;
+        fixture.entry("2026-02-03", 10.0, kg).await;
+        fixture.dispatch("2026-01-10", 40.0, kg).await;
+
+        let reconciliation = reconcile(&fixture.test_db.db, &fixture.options(Some("2026-02-01")))
+            .await
+            .unwrap();
+
+        assert_eq!(reconciliation.rows.len(), 1);
+        assert_eq!(reconciliation.entry_weight, 10.0);
+        assert_eq!(reconciliation.dispatched_weight, 0.0);
+
+        fixture.test_db.close().await;
+    }
+}
@@ -0,0 +1,610 @@
+use std::{env, time::Duration};
+
+use anyhow::{anyhow, Result};
+use async_graphql::{Context, InputObject, InputType, Object};
+use chrono::Local;
+use log::{error, info};
+use sea_orm::{
+    entity::prelude::*,
+    sea_query::{Expr, Query},
+    Condition, DatabaseTransaction, DeleteResult, TransactionTrait,
+};
+
+use crate::{auth::SessionData, SeaOrmPool};
+
+use super::{
+    article,
+    audit_log::{log_delete, log_purge, log_update, Auditable},
+    buyer, cell, cell_culture_pair, culture, dispatch_note, dispatch_note_article, entry,
+    graphql_schema::{DataGroupAccessGuard, Filter, OrderingOptions, QueryResults},
+    GetEntityId, QueryDatabase,
+};
+
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(InputObject)]
+pub struct RestoreOptions {
+    pub id: i32,
+}
+
+/// Marks the entity as deleted, already deleted entities are not deleted again
+pub async fn soft_delete<E>(
+    transaction: &DatabaseTransaction,
+    session_data: &SessionData,
+    id: i32,
+) -> Result<DeleteResult>
+where
+    E: QueryDatabase + GetEntityId<<E as EntityTrait>::Column>,
+    <E as EntityTrait>::Model: Auditable + Sync,
+    <E as EntityTrait>::Column: Default,
+    Filter<E::InputFields, E::FilterValueType>: InputType,
+    OrderingOptions<E::InputFields>: InputType,
+{
+    let deleted_at =
+        E::get_deleted_at_column().ok_or_else(|| anyhow!("Entity can not be soft deleted"))?;
+
+    let model = match E::find()
+        .filter(E::get_id_column().eq(id))
+        .filter(deleted_at.is_null())
+        .one(transaction)
+        .await?
+    {
+        Some(model) => model,
+        None => return Ok(DeleteResult { rows_affected: 0 }),
+    };
+    log_delete(transaction, session_data, &model).await?;
+
+    let now: DateTimeWithTimeZone = Local::now().into();
+    let res = E::update_many()
+        .col_expr(deleted_at, Expr::value(now))
+        .filter(E::get_id_column().eq(id))
+        .exec(transaction)
+        .await?;
+
+    Ok(DeleteResult {
+        rows_affected: res.rows_affected,
+    })
+}
+
+/// Takes the entity out of the trash, recorded as an update in the audit log
+pub async fn restore<E>(
+    transaction: &DatabaseTransaction,
+    session_data: &SessionData,
+    id: i32,
+) -> Result<<E as EntityTrait>::Model>
+where
+    E: QueryDatabase + GetEntityId<<E as EntityTrait>::Column>,
+    <E as EntityTrait>::Model: Auditable + Sync,
+    <E as EntityTrait>::Column: Default,
+    Filter<E::InputFields, E::FilterValueType>: InputType,
+    OrderingOptions<E::InputFields>: InputType,
+{
+    let deleted_at =
+        E::get_deleted_at_column().ok_or_else(|| anyhow!("Entity can not be soft deleted"))?;
+
+    let before = E::find()
+        .filter(E::get_id_column().eq(id))
+        .filter(deleted_at.is_not_null())
+        .one(transaction)
+        .await?
+        .ok_or_else(|| anyhow!("Entity is not in the trash"))?;
+
+    E::update_many()
+        .col_expr(
+            deleted_at,
+            Expr::value(Option::<DateTimeWithTimeZone>::None),
+        )
+        .filter(E::get_id_column().eq(id))
+        .exec(transaction)
+        .await?;
+
+    let after = E::find()
+        .filter(E::get_id_column().eq(id))
+        .one(transaction)
+        .await?
+        .ok_or_else(|| anyhow!("Entity not found!"))?;
+    log_update(transaction, session_data, &before, &after).await?;
+
+    Ok(after)
+}
+
+/// Used by `CheckEditable`, entities in the trash have to be restored before they are edited
+pub fn check_not_deleted(deleted_at: Option<DateTimeWithTimeZone>) -> Result<()> {
+    match deleted_at {
+        Some(_) => Err(anyhow!("Entity is deleted, restore it first")),
+        None => Ok(()),
+    }
+}
+
+/// Soft deleted entities of a data group
+pub struct Trash {
+    d_group: i32,
+}
+
+#[Object]
+impl Trash {
+    async fn cells(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<cell::Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        cell::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+
+    async fn cultures(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<culture::Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        culture::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+
+    async fn buyers(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<buyer::Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        buyer::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+
+    async fn articles(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<article::Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        article::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+
+    async fn entries(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<entry::Entry>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        entry::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+
+    async fn dispatch_notes(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u64>,
+        page: Option<u64>,
+    ) -> Result<QueryResults<dispatch_note::DispatchNote>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        dispatch_note::Entity::fetch_deleted(db, self.d_group, page_size, page).await
+    }
+}
+
+#[derive(Default)]
+pub struct TrashQuery;
+
+#[Object]
+impl TrashQuery {
+    /// Deleted entities are kept for `SOFT_DELETE_RETENTION_DAYS` days before they are purged
+    #[graphql(guard = "DataGroupAccessGuard::new(d_group)")]
+    async fn trash(&self, d_group: i32) -> Trash {
+        Trash { d_group }
+    }
+}
+
+/// Reads `SOFT_DELETE_RETENTION_DAYS`, defaults to 30 days
+fn retention_days() -> i64 {
+    match env::var("SOFT_DELETE_RETENTION_DAYS") {
+        Ok(days) => days
+            .parse()
+            .expect("SOFT_DELETE_RETENTION_DAYS must be a number of days"),
+        Err(_) => DEFAULT_RETENTION_DAYS,
+    }
+}
+
+/// Deletes the rows matching `condition`, every row is recorded in the audit log
+async fn purge<E>(transaction: &DatabaseTransaction, condition: Condition) -> Result<u64, DbErr>
+where
+    E: EntityTrait + GetEntityId<<E as EntityTrait>::Column>,
+    <E as EntityTrait>::Model: Auditable,
+{
+    let models = E::find().filter(condition).all(transaction).await?;
+    if models.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ids = Vec::with_capacity(models.len());
+    for model in models.iter() {
+        log_purge(transaction, model).await?;
+        ids.push(model.audit_id());
+    }
+
+    Ok(E::delete_many()
+        .filter(E::get_id_column().is_in(ids))
+        .exec(transaction)
+        .await?
+        .rows_affected)
+}
+
+/// Permanently deletes entities that were deleted before `deleted_before`.
+/// Rows still referenced by other rows are kept until the referencing rows are purged
+pub async fn purge_deleted(
+    db: &DatabaseConnection,
+    deleted_before: DateTimeWithTimeZone,
+) -> Result<u64> {
+    let transaction = db.begin().await?;
+    let mut purged = 0;
+
+    purged += purge::<entry::Entity>(
+        &transaction,
+        Condition::all().add(entry::Column::DeletedAt.lt(deleted_before)),
+    )
+    .await?;
+
+    let expired_dispatch_notes = Condition::all()
+        .add(dispatch_note::Column::DeletedAt.lt(deleted_before))
+        .add(
+            dispatch_note::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(dispatch_note::Column::CorrectsDispatchNote)
+                    .from(dispatch_note::Entity)
+                    .and_where(dispatch_note::Column::CorrectsDispatchNote.is_not_null())
+                    .to_owned(),
+            ),
+        );
+    // Articles would be removed by the cascade, purged first so they are recorded as well
+    purged += purge::<dispatch_note_article::Entity>(
+        &transaction,
+        Condition::all().add(
+            dispatch_note_article::Column::IdDispatchNote.in_subquery(
+                Query::select()
+                    .column(dispatch_note::Column::Id)
+                    .from(dispatch_note::Entity)
+                    .cond_where(expired_dispatch_notes.clone())
+                    .to_owned(),
+            ),
+        ),
+    )
+    .await?;
+    purged += purge::<dispatch_note::Entity>(&transaction, expired_dispatch_notes).await?;
+
+    purged += purge::<cell_culture_pair::Entity>(
+        &transaction,
+        Condition::all()
+            .add(
+                Condition::any()
+                    .add(
+                        cell_culture_pair::Column::IdCell.in_subquery(
+                            Query::select()
+                                .column(cell::Column::Id)
+                                .from(cell::Entity)
+                                .and_where(cell::Column::DeletedAt.lt(deleted_before))
+                                .to_owned(),
+                        ),
+                    )
+                    .add(
+                        cell_culture_pair::Column::IdCulture.in_subquery(
+                            Query::select()
+                                .column(culture::Column::Id)
+                                .from(culture::Entity)
+                                .and_where(culture::Column::DeletedAt.lt(deleted_before))
+                                .to_owned(),
+                        ),
+                    ),
+            )
+            .add(
+                cell_culture_pair::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(entry::Column::IdCellCulturePair)
+                        .from(entry::Entity)
+                        .to_owned(),
+                ),
+            ),
+    )
+    .await?;
+
+    purged += purge::<buyer::Entity>(
+        &transaction,
+        Condition::all()
+            .add(buyer::Column::DeletedAt.lt(deleted_before))
+            .add(
+                buyer::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(entry::Column::IdBuyer)
+                        .from(entry::Entity)
+                        .to_owned(),
+                ),
+            )
+            .add(
+                buyer::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(dispatch_note::Column::IdBuyer)
+                        .from(dispatch_note::Entity)
+                        .and_where(dispatch_note::Column::IdBuyer.is_not_null())
+                        .to_owned(),
+                ),
+            ),
+    )
+    .await?;
+
+    purged += purge::<article::Entity>(
+        &transaction,
+        Condition::all()
+            .add(article::Column::DeletedAt.lt(deleted_before))
+            .add(
+                article::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(dispatch_note_article::Column::IdArticle)
+                        .from(dispatch_note_article::Entity)
+                        .to_owned(),
+                ),
+            ),
+    )
+    .await?;
+
+    purged += purge::<cell::Entity>(
+        &transaction,
+        Condition::all()
+            .add(cell::Column::DeletedAt.lt(deleted_before))
+            .add(
+                cell::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(cell_culture_pair::Column::IdCell)
+                        .from(cell_culture_pair::Entity)
+                        .to_owned(),
+                ),
+            ),
+    )
+    .await?;
+
+    purged += purge::<culture::Entity>(
+        &transaction,
+        Condition::all()
+            .add(culture::Column::DeletedAt.lt(deleted_before))
+            .add(
+                culture::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(cell_culture_pair::Column::IdCulture)
+                        .from(cell_culture_pair::Entity)
+                        .to_owned(),
+                ),
+            ),
+    )
+    .await?;
+
+    transaction.commit().await?;
+    Ok(purged)
+}
+
+/// Purges expired trash once an hour for as long as the server runs
+pub fn spawn_purge_task(db: SeaOrmPool) {
+    let retention = chrono::Duration::days(retention_days());
+
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let deleted_before: DateTimeWithTimeZone = (Local::now() - retention).into();
+            match purge_deleted(&db, deleted_before).await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} deleted rows", purged),
+                Err(e) => error!("Purging deleted rows failed: {:#?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::Statement;
+
+    use super::*;
+    use crate::test_database::TestDatabase;
+
+    struct Fixture {
+        test_db: TestDatabase,
+        d_group: i32,
+        weight_type: i32,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let test_db = TestDatabase::new().await;
+            let mbe_group = test_db.mbe_group().await;
+            let d_group = test_db.data_group(mbe_group).await;
+            let weight_type = test_db
+                .insert("INSERT INTO weight_type (unit_short, unit, conversion_factor) VALUES ('kg', 'Kilogram', 1)")
+                .await;
+
+            Self {
+                test_db,
+                d_group,
+                weight_type,
+            }
+        }
+
+        async fn insert(&self, table: &str, columns: &str, values: &str) -> i32 {
+            self.test_db
+                .insert(&format!(
+                    "INSERT INTO {} ({}, d_group) VALUES ({}, {})",
+                    table, columns, values, self.d_group
+                ))
+                .await
+        }
+
+        /// Pair of a fresh cell and culture
+        async fn pair(&self) -> (i32, i32, i32) {
+            let cell = self.insert("cell", "name", "'Cell'").await;
+            let culture = self.insert("culture", "name", "'Culture'").await;
+            let pair = self
+                .insert(
+                    "cell_culture_pair",
+                    "id_cell, id_culture",
+                    &format!("{}, {}", cell, culture),
+                )
+                .await;
+            (cell, culture, pair)
+        }
+
+        async fn entry(&self, buyer: i32, pair: i32) -> i32 {
+            self.insert(
+                "entry",
+                "weight, weight_type, date, id_buyer, id_cell_culture_pair",
+                &format!("10, {}, now(), {}, {}", self.weight_type, buyer, pair),
+            )
+            .await
+        }
+
+        async fn delete(&self, table: &str, id: i32, days_ago: i32) {
+            self.test_db
+                .db
+                .execute_unprepared(&format!(
+                    "UPDATE {} SET deleted_at = now() - interval '{} days' WHERE id = {}",
+                    table, days_ago, id
+                ))
+                .await
+                .unwrap();
+        }
+
+        async fn exists(&self, table: &str, id: i32) -> bool {
+            self.test_db
+                .db
+                .query_one(Statement::from_string(
+                    self.test_db.db.get_database_backend(),
+                    format!("SELECT id FROM {} WHERE id = {}", table, id),
+                ))
+                .await
+                .unwrap()
+                .is_some()
+        }
+
+        async fn purge(&self) -> u64 {
+            let deleted_before: DateTimeWithTimeZone =
+                (Local::now() - chrono::Duration::days(DEFAULT_RETENTION_DAYS)).into();
+            purge_deleted(&self.test_db.db, deleted_before)
+                .await
+                .unwrap()
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn purges_only_rows_deleted_before_the_retention() {
+        let fixture = Fixture::new().await;
+        let recent = fixture.insert("buyer", "name", "'Recent'").await;
+        let expired = fixture.insert("buyer", "name", "'Expired'").await;
+        let live = fixture.insert("buyer", "name", "'Live'").await;
+        fixture.delete("buyer", recent, 1).await;
+        fixture.delete("buyer", expired, 31).await;
+
+        assert_eq!(fixture.purge().await, 1);
+        assert!(fixture.exists("buyer", recent).await);
+        assert!(!fixture.exists("buyer", expired).await);
+        assert!(fixture.exists("buyer", live).await);
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn pairs_with_trashed_cells_or_cultures_are_not_found() {
+        let fixture = Fixture::new().await;
+        let (cell, culture, _) = fixture.pair().await;
+        let (trashed_cell, other_culture, _) = fixture.pair().await;
+        let (other_cell, trashed_culture, _) = fixture.pair().await;
+        fixture.delete("cell", trashed_cell, 1).await;
+        fixture.delete("culture", trashed_culture, 1).await;
+
+        let transaction = fixture.test_db.db.begin().await.unwrap();
+        let find = |id_cell, id_culture| {
+            cell_culture_pair::Entity::find_for_data_group(
+                &transaction,
+                id_cell,
+                id_culture,
+                fixture.d_group,
+            )
+        };
+        assert!(find(cell, culture).await.is_ok());
+        assert!(find(trashed_cell, other_culture).await.is_err());
+        assert!(find(other_cell, trashed_culture).await.is_err());
+        transaction.rollback().await.unwrap();
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn keeps_rows_referenced_by_live_rows() {
+        let fixture = Fixture::new().await;
+        let buyer = fixture.insert("buyer", "name", "'Buyer'").await;
+        let (cell, culture, pair) = fixture.pair().await;
+        fixture.entry(buyer, pair).await;
+        fixture.delete("buyer", buyer, 31).await;
+        fixture.delete("cell", cell, 31).await;
+
+        assert_eq!(fixture.purge().await, 0);
+        assert!(fixture.exists("buyer", buyer).await);
+        assert!(fixture.exists("cell_culture_pair", pair).await);
+        assert!(fixture.exists("cell", cell).await);
+        assert!(fixture.exists("culture", culture).await);
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn purges_referenced_rows_together_with_their_references() {
+        let fixture = Fixture::new().await;
+        let buyer = fixture.insert("buyer", "name", "'Buyer'").await;
+        let (cell, culture, pair) = fixture.pair().await;
+        let entry = fixture.entry(buyer, pair).await;
+        fixture.delete("entry", entry, 31).await;
+        fixture.delete("buyer", buyer, 31).await;
+        fixture.delete("cell", cell, 31).await;
+
+        // Entry, buyer, pair and cell, the culture itself was never deleted
+        assert_eq!(fixture.purge().await, 4);
+        assert!(!fixture.exists("entry", entry).await);
+        assert!(!fixture.exists("buyer", buyer).await);
+        assert!(!fixture.exists("cell_culture_pair", pair).await);
+        assert!(!fixture.exists("cell", cell).await);
+        assert!(fixture.exists("culture", culture).await);
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn keeps_dispatch_notes_with_corrections() {
+        let fixture = Fixture::new().await;
+        let corrected = fixture
+            .insert("dispatch_note", "issuing_date", "now()")
+            .await;
+        let correction = fixture
+            .insert(
+                "dispatch_note",
+                "issuing_date, corrects_dispatch_note",
+                &format!("now(), {}", corrected),
+            )
+            .await;
+        fixture.delete("dispatch_note", corrected, 31).await;
+
+        assert_eq!(fixture.purge().await, 0);
+        assert!(fixture.exists("dispatch_note", corrected).await);
+
+        fixture.delete("dispatch_note", correction, 31).await;
+
+        // The correction goes first, the corrected note on the next run
+        assert_eq!(fixture.purge().await, 1);
+        assert!(!fixture.exists("dispatch_note", correction).await);
+        assert_eq!(fixture.purge().await, 1);
+        assert!(!fixture.exists("dispatch_note", corrected).await);
+
+        fixture.test_db.close().await;
+    }
+}
@@ -0,0 +1,139 @@
+use async_graphql::ErrorExtensions;
+use thiserror::Error;
+
+use super::weight_type::{self, WeightTypeDimension};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    #[error("Unit {0} has no conversion factor!")]
+    MissingConversionFactor(String),
+    #[error("Can't convert {from} ({from_dimension:?}) to {to} ({to_dimension:?})!")]
+    IncompatibleDimensions {
+        from: String,
+        from_dimension: WeightTypeDimension,
+        to: String,
+        to_dimension: WeightTypeDimension,
+    },
+}
+
+impl ErrorExtensions for ConversionError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| match self {
+            ConversionError::MissingConversionFactor(unit) => {
+                extensions.set("code", "MISSING_CONVERSION_FACTOR");
+                extensions.set("unit", unit.as_str());
+            }
+            ConversionError::IncompatibleDimensions { from, to, .. } => {
+                extensions.set("code", "INCOMPATIBLE_DIMENSIONS");
+                extensions.set("from", from.as_str());
+                extensions.set("to", to.as_str());
+            }
+        })
+    }
+}
+
+fn conversion_factor(unit: &weight_type::Model) -> Result<f64, ConversionError> {
+    unit.conversion_factor
+        .ok_or_else(|| ConversionError::MissingConversionFactor(unit.unit_short.clone()))
+}
+
+/// Converts `quantity` into the base unit of the units dimension (kg, l or piece)
+pub fn to_base_unit(quantity: f64, unit: &weight_type::Model) -> Result<f64, ConversionError> {
+    Ok(quantity * conversion_factor(unit)?)
+}
+
+/// Converts `quantity` between two units of the same dimension
+pub fn convert(
+    quantity: f64,
+    from: &weight_type::Model,
+    to: &weight_type::Model,
+) -> Result<f64, ConversionError> {
+    if from.id == to.id {
+        return Ok(quantity);
+    }
+
+    if from.dimension != to.dimension {
+        return Err(ConversionError::IncompatibleDimensions {
+            from: from.unit_short.clone(),
+            from_dimension: from.dimension,
+            to: to.unit_short.clone(),
+            to_dimension: to.dimension,
+        });
+    }
+
+    Ok(to_base_unit(quantity, from)? / conversion_factor(to)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn unit(
+        id: i32,
+        unit_short: &str,
+        dimension: WeightTypeDimension,
+        conversion_factor: Option<f64>,
+    ) -> weight_type::Model {
+        weight_type::Model {
+            id,
+            unit_short: unit_short.to_string(),
+            unit: unit_short.to_string(),
+            created_at: Utc::now().into(),
+            conversion_factor,
+            dimension,
+        }
+    }
+
+    #[test]
+    fn converts_within_dimension() {
+        let kg = unit(1, "kg", WeightTypeDimension::Mass, Some(1.0));
+        let g = unit(2, "g", WeightTypeDimension::Mass, Some(0.001));
+
+        assert_eq!(convert(2.5, &kg, &g), Ok(2500.0));
+        assert_eq!(convert(500.0, &g, &kg), Ok(0.5));
+    }
+
+    #[test]
+    fn same_unit_needs_no_conversion_factor() {
+        let piece = unit(1, "kom", WeightTypeDimension::Count, None);
+
+        assert_eq!(convert(3.0, &piece, &piece), Ok(3.0));
+    }
+
+    #[test]
+    fn rejects_other_dimension() {
+        let kg = unit(1, "kg", WeightTypeDimension::Mass, Some(1.0));
+        let l = unit(2, "l", WeightTypeDimension::Volume, Some(1.0));
+
+        assert_eq!(
+            convert(1.0, &kg, &l),
+            Err(ConversionError::IncompatibleDimensions {
+                from: "kg".to_string(),
+                from_dimension: WeightTypeDimension::Mass,
+                to: "l".to_string(),
+                to_dimension: WeightTypeDimension::Volume,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unit_without_conversion_factor() {
+        let kg = unit(1, "kg", WeightTypeDimension::Mass, Some(1.0));
+        let bag = unit(2, "vreća", WeightTypeDimension::Mass, None);
+
+        assert_eq!(
+            convert(1.0, &kg, &bag),
+            Err(ConversionError::MissingConversionFactor(
+                "vreća".to_string()
+            ))
+        );
+        assert_eq!(
+            to_base_unit(1.0, &bag),
+            Err(ConversionError::MissingConversionFactor(
+                "vreća".to_string()
+            ))
+        );
+    }
+}
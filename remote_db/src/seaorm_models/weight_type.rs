@@ -1,28 +1,50 @@
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
 use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
 use async_trait::async_trait;
+use futures::lock::Mutex;
 use sea_orm::{
     entity::prelude::*,
     sea_query::{Expr, Func},
-    ActiveValue, DatabaseTransaction, DeleteResult, TransactionTrait,
+    ActiveValue, DatabaseTransaction, DeleteResult, Order, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    seaorm_models::graphql_schema::extract_session, user_models::mbe_groups_weight_types,
-    SeaOrmPool,
+    auth::SessionData, seaorm_models::graphql_schema::extract_session,
+    user_models::mbe_groups_weight_types, SeaOrmPool,
 };
 
 use super::{
-    calculate_page_size, common_add_ordering,
+    audit_log::{log_delete, log_insert, log_update, AuditEntityType, Auditable},
+    calculate_page_size, common_add_ordering, common_get_ordering_column,
+    connection::{query_connection, ConnectionArgs, OrderingColumn, QueryConnection},
+    dispatch_note::DispatchNoteStatus,
     graphql_schema::{
         DeleteOptions, FetchOptions, Filter, MbeGroupAccessGuard, OrderingOptions, QueryResults,
         WeightTypeFetchOptions,
     },
-    Page, PageSize, QueryDatabase, QueryResultsHelperType, RowsDeleted,
+    GetEntityId, Page, PageSize, QueryDatabase, QueryResultsHelperType, RowsDeleted,
 };
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum WeightTypeDimension {
+    /// Base unit is the kilogram
+    #[sea_orm(string_value = "mass")]
+    Mass,
+    /// Base unit is the litre
+    #[sea_orm(string_value = "volume")]
+    Volume,
+    /// Base unit is a single piece
+    #[sea_orm(string_value = "count")]
+    Count,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
 #[graphql(name = "WeightType")]
 #[sea_orm(table_name = "weight_type")]
 pub struct Model {
@@ -31,12 +53,18 @@ pub struct Model {
     pub unit_short: String,
     pub unit: String,
     pub created_at: DateTimeWithTimeZone,
+    /// Factor that converts a quantity in this unit into the base unit of its dimension,
+    /// units without one can't be converted
+    pub conversion_factor: Option<f64>,
+    pub dimension: WeightTypeDimension,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::dispatch_note_article::Entity")]
     DispatchNoteArticle,
+    #[sea_orm(has_many = "super::entry::Entity")]
+    Entry,
     #[sea_orm(has_many = "crate::user_models::mbe_groups_weight_types::Entity")]
     MbeGroupsWeightTypes,
 }
@@ -47,6 +75,12 @@ impl Related<super::dispatch_note_article::Entity> for Entity {
     }
 }
 
+impl Related<super::entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Entry.def()
+    }
+}
+
 impl Related<crate::user_models::mbe_groups_weight_types::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::MbeGroupsWeightTypes.def()
@@ -83,6 +117,10 @@ impl Default for Column {
 pub struct WeightTypeInsertOptions {
     pub unit_short: String,
     pub unit: String,
+    /// Factor that converts a quantity in this unit into the base unit of its dimension
+    pub conversion_factor: Option<f64>,
+    /// Defaults to mass
+    pub dimension: Option<WeightTypeDimension>,
     pub mbe_group: i32,
 }
 
@@ -96,6 +134,8 @@ pub struct WeightTypeUpdateOptions {
     pub id: i32,
     pub unit_short: Option<String>,
     pub unit: Option<String>,
+    pub conversion_factor: Option<f64>,
+    pub dimension: Option<WeightTypeDimension>,
     pub mbe_group: i32,
 }
 
@@ -112,7 +152,42 @@ pub struct WeightTypeDeleteOptions {
 //     id_created_by: i32,
 // }
 
+fn validate_conversion_factor(conversion_factor: Option<f64>) -> Result<Option<f64>> {
+    match conversion_factor {
+        Some(factor) if !(factor.is_finite() && factor > 0.0) => {
+            Err(anyhow!("Conversion factor must be a positive number!"))
+        }
+        _ => Ok(conversion_factor),
+    }
+}
+
 impl Entity {
+    /// Weight types available to the mbe group
+    fn get_mbe_group_query(mbe_group_id: i32) -> Select<Self> {
+        Self::get_query()
+            .inner_join(mbe_groups_weight_types::Entity)
+            .filter(mbe_groups_weight_types::Column::IdMbeGroup.eq(mbe_group_id))
+    }
+
+    async fn fetch_connection_for_mbe_group(
+        db: &DatabaseConnection,
+        options: WeightTypeFetchOptions<WeightTypeFields>,
+        args: ConnectionArgs,
+    ) -> Result<QueryConnection<Model>> {
+        let mut query = Self::get_mbe_group_query(options.mbe_group_id);
+
+        query = Self::add_id_filter(query, &options);
+        query = Self::add_filters(query, options.filters);
+
+        let transaction = db.begin().await?;
+
+        let connection =
+            Self::fetch_connection_page(&transaction, query, options.ordering, args).await?;
+
+        transaction.commit().await?;
+        Ok(connection)
+    }
+
     fn add_id_filter(
         mut query: Select<Self>,
         fetch_options: &WeightTypeFetchOptions<WeightTypeFields>,
@@ -122,6 +197,68 @@ impl Entity {
         }
         query
     }
+
+    /// Finds the weight type only if it is available to the mbe group
+    pub async fn find_for_mbe_group<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        id_mbe_group: i32,
+    ) -> Result<Model> {
+        Entity::find_by_id(id)
+            .inner_join(mbe_groups_weight_types::Entity)
+            .filter(mbe_groups_weight_types::Column::IdMbeGroup.eq(id_mbe_group))
+            .one(db)
+            .await?
+            .ok_or_else(|| anyhow!("Weight type not found in the mbe group!"))
+    }
+
+    /// Conversion factor and dimension give meaning to the quantities recorded in the weight type,
+    /// they can only change while no other mbe group, entry or issued dispatch note relies on them
+    async fn check_conversion_changeable(
+        transaction: &DatabaseTransaction,
+        id: i32,
+        id_mbe_group: i32,
+    ) -> Result<()> {
+        let linked_elsewhere = mbe_groups_weight_types::Entity::find()
+            .filter(mbe_groups_weight_types::Column::IdWeightType.eq(id))
+            .filter(mbe_groups_weight_types::Column::IdMbeGroup.ne(id_mbe_group))
+            .count(transaction)
+            .await?
+            > 0;
+        let used_by_entries = super::entry::Entity::find()
+            .filter(super::entry::Column::WeightType.eq(id))
+            .count(transaction)
+            .await?
+            > 0;
+        let used_by_issued_notes = super::dispatch_note_article::Entity::find()
+            .inner_join(super::dispatch_note::Entity)
+            .filter(super::dispatch_note_article::Column::WeightType.eq(id))
+            .filter(super::dispatch_note::Column::Status.ne(DispatchNoteStatus::Draft))
+            .count(transaction)
+            .await?
+            > 0;
+
+        if linked_elsewhere || used_by_entries || used_by_issued_notes {
+            return Err(anyhow!(
+                "Weight type is in use, create a new one with the changed conversion instead!"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Finds the weight type only if it is available to the mbe group owning the data group
+    pub async fn find_for_data_group<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        d_group: i32,
+    ) -> Result<Model> {
+        let data_group = super::data_group::Entity::find_by_id(d_group)
+            .one(db)
+            .await?
+            .ok_or_else(|| anyhow!("Data group not found"))?;
+
+        Self::find_for_mbe_group(db, id, data_group.id_mbe_group).await
+    }
 }
 
 #[async_trait]
@@ -144,6 +281,7 @@ impl QueryDatabase for Entity {
 
     type FilterValueType = String;
 
+    #[allow(clippy::diverging_sub_expression)]
     async fn fetch(
         _db: &DatabaseConnection,
         _fetch_options: FetchOptions<Self::InputFields, Self::FetchIdType, Self::FilterValueType>,
@@ -161,24 +299,39 @@ impl QueryDatabase for Entity {
 
     async fn delete_query(
         transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: DeleteOptions<Self::DeleteOptionsType>,
     ) -> Result<DeleteResult> {
-        // TODO: user with proper permissions should be able to delete the weight type not only the
-        // owner (user that created the type)
-        // TODO: below
-        // unimplemented!("Implement proper permissions for deletion when it comes to mbe groups")
-        let model = ActiveModel {
-            id: ActiveValue::Set(options.id.id),
-            ..Default::default()
-        };
+        let before =
+            Self::find_for_mbe_group(transaction, options.id.id, options.id.mbe_group).await?;
 
-        mbe_groups_weight_types::Entity::delete_many()
+        let unlinked = mbe_groups_weight_types::Entity::delete_many()
             .filter(mbe_groups_weight_types::Column::IdMbeGroup.eq(options.id.mbe_group))
             .filter(mbe_groups_weight_types::Column::IdWeightType.eq(options.id.id))
             .exec(transaction)
             .await?;
 
-        Ok(model.delete(transaction).await?)
+        log_delete(
+            transaction,
+            session_data,
+            &MbeGroupWeightType {
+                model: &before,
+                mbe_group: options.id.mbe_group,
+            },
+        )
+        .await?;
+
+        // Other mbe groups still use the weight type, only this groups link to it is removed
+        let still_linked = mbe_groups_weight_types::Entity::find()
+            .filter(mbe_groups_weight_types::Column::IdWeightType.eq(options.id.id))
+            .count(transaction)
+            .await?
+            > 0;
+        if still_linked {
+            return Ok(unlinked);
+        }
+
+        Ok(before.delete(transaction).await?)
     }
 
     fn add_ordering(
@@ -188,6 +341,12 @@ impl QueryDatabase for Entity {
         common_add_ordering(query, ordering_options)
     }
 
+    fn get_ordering_column(
+        ordering_options: &Option<OrderingOptions<Self::InputFields>>,
+    ) -> (OrderingColumn, Order) {
+        common_get_ordering_column::<Self, _>(ordering_options)
+    }
+
     fn add_id_and_data_group_filters(
         mut query: Select<Self>,
         fetch_options: &FetchOptions<Self::InputFields, Self::FetchIdType>,
@@ -209,41 +368,73 @@ impl QueryDatabase for Entity {
         }
     }
 
-    async fn update_entity(
-        db: &DatabaseConnection,
+    async fn update_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::UpdateOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             id: ActiveValue::Set(options.id),
             unit_short: options
                 .unit_short
                 .map_or(ActiveValue::NotSet, ActiveValue::Set),
             unit: options.unit.map_or(ActiveValue::NotSet, ActiveValue::Set),
+            conversion_factor: validate_conversion_factor(options.conversion_factor)?
+                .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
+            dimension: options
+                .dimension
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
             ..Default::default()
         };
 
-        let transaction = db.begin().await?;
-
-        let res = model.update(&transaction).await?;
+        let before = Entity::find_for_mbe_group(transaction, options.id, options.mbe_group).await?;
+        let changes_conversion = options
+            .conversion_factor
+            .is_some_and(|factor| before.conversion_factor != Some(factor))
+            || options
+                .dimension
+                .is_some_and(|dimension| before.dimension != dimension);
+        if changes_conversion {
+            Entity::check_conversion_changeable(transaction, options.id, options.mbe_group).await?;
+        }
 
-        transaction.commit().await?;
+        let res = model.update(transaction).await?;
+        log_update(
+            transaction,
+            session_data,
+            &MbeGroupWeightType {
+                model: &before,
+                mbe_group: options.mbe_group,
+            },
+            &MbeGroupWeightType {
+                model: &res,
+                mbe_group: options.mbe_group,
+            },
+        )
+        .await?;
 
         Ok(res)
     }
 
-    async fn insert_entity(
-        db: &DatabaseConnection,
+    async fn insert_query(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
         options: Self::InsertOptions,
-    ) -> Result<Self::InnerQueryResultType> {
+    ) -> Result<Model> {
         let model = ActiveModel {
             unit: ActiveValue::Set(options.weight_type_insert_options.unit),
             unit_short: ActiveValue::Set(options.weight_type_insert_options.unit_short),
+            conversion_factor: ActiveValue::Set(validate_conversion_factor(
+                options.weight_type_insert_options.conversion_factor,
+            )?),
+            dimension: options
+                .weight_type_insert_options
+                .dimension
+                .map_or(ActiveValue::NotSet, ActiveValue::Set),
             ..Default::default()
         };
 
-        let transaction = db.begin().await?;
-
-        let res = model.insert(&transaction).await?;
+        let res = model.insert(transaction).await?;
 
         mbe_groups_weight_types::ActiveModel {
             id_weight_type: ActiveValue::Set(res.id),
@@ -251,14 +442,47 @@ impl QueryDatabase for Entity {
             id_created_by: ActiveValue::Set(options.created_by),
             ..Default::default()
         }
-        .insert(&transaction)
+        .insert(transaction)
+        .await?;
+
+        log_insert(
+            transaction,
+            session_data,
+            &MbeGroupWeightType {
+                model: &res,
+                mbe_group: options.weight_type_insert_options.mbe_group,
+            },
+        )
         .await?;
 
-        transaction.commit().await?;
         Ok(res)
     }
 }
 
+/// Weight types found while resolving a single request. Fields converting every row of a list
+/// into the same weight type query it once instead of once per row
+#[derive(Default)]
+pub struct WeightTypeCache(Mutex<HashMap<(i32, i32), Model>>);
+
+impl WeightTypeCache {
+    /// Same as `Entity::find_for_mbe_group`, concurrent lookups wait for the first one
+    pub async fn find_for_mbe_group<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        id: i32,
+        id_mbe_group: i32,
+    ) -> Result<Model> {
+        let mut cache = self.0.lock().await;
+        if let Some(model) = cache.get(&(id, id_mbe_group)) {
+            return Ok(model.clone());
+        }
+
+        let model = Entity::find_for_mbe_group(db, id, id_mbe_group).await?;
+        cache.insert((id, id_mbe_group), model.clone());
+        Ok(model)
+    }
+}
+
 #[derive(Default)]
 pub struct WeightTypeQuery;
 
@@ -275,12 +499,7 @@ impl WeightTypeQuery {
         let page_size = PageSize(calculate_page_size(options.page_size));
         let page: Page = options.page.into();
 
-        let mut query = Entity::get_query()
-            .inner_join(crate::user_models::mbe_groups_weight_types::Entity)
-            .filter(
-                crate::user_models::mbe_groups_weight_types::Column::IdMbeGroup
-                    .eq(options.mbe_group_id),
-            );
+        let mut query = Entity::get_mbe_group_query(options.mbe_group_id);
 
         query = Entity::add_id_filter(query, &options);
         query = Entity::add_ordering(query, options.ordering);
@@ -295,6 +514,24 @@ impl WeightTypeQuery {
         transaction.commit().await?;
         Ok((res, num_items_and_pages, page, page_size).into())
     }
+
+    /// Cursor paged `weightTypes`, ignores `page` and `pageSize` of the options
+    #[graphql(guard = "MbeGroupAccessGuard::new(options.mbe_group_id)")]
+    async fn weight_types_connection(
+        &self,
+        ctx: &Context<'_>,
+        options: WeightTypeFetchOptions<WeightTypeFields>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<QueryConnection<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        query_connection(ctx, after, before, first, last, |args| {
+            Entity::fetch_connection_for_mbe_group(db, options, args)
+        })
+        .await
+    }
 }
 
 #[derive(Default)]
@@ -302,7 +539,7 @@ pub struct WeightTypeMutation;
 
 #[Object]
 impl WeightTypeMutation {
-    #[graphql(guard = "MbeGroupAccessGuard::new(options.mbe_group)")]
+    #[graphql(guard = "MbeGroupAccessGuard::write(options.mbe_group)")]
     async fn insert_weight_types(
         &self,
         ctx: &Context<'_>,
@@ -312,6 +549,7 @@ impl WeightTypeMutation {
         let user_session = extract_session(ctx)?;
         Entity::insert_entity(
             db,
+            user_session,
             WeightTypeInsertOptionsExt {
                 weight_type_insert_options: options,
                 created_by: user_session.user_id,
@@ -320,23 +558,226 @@ impl WeightTypeMutation {
         .await
     }
 
-    #[graphql(guard = "MbeGroupAccessGuard::new(options.mbe_group)")]
+    #[graphql(guard = "MbeGroupAccessGuard::write(options.mbe_group)")]
     async fn update_weight_types(
         &self,
         ctx: &Context<'_>,
         options: WeightTypeUpdateOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::update_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        Entity::update_entity(db, session_data, options).await
     }
 
-    #[graphql(guard = "MbeGroupAccessGuard::new(options.id.mbe_group)")]
+    #[graphql(guard = "MbeGroupAccessGuard::write(options.id.mbe_group)")]
     async fn delete_weight_types(
         &self,
         ctx: &Context<'_>,
         options: DeleteOptions<WeightTypeDeleteOptions>,
     ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        Entity::delete_entity(db, options).await
+        let session_data = extract_session(ctx)?;
+        Entity::delete_entity(db, session_data, options).await
+    }
+}
+
+impl GetEntityId<Column> for Entity {
+    fn get_id_column() -> Column {
+        Column::Id
+    }
+}
+
+/// Weight types are shared between mbe groups, changes are logged in the group they were made from
+#[derive(Serialize)]
+struct MbeGroupWeightType<'a> {
+    #[serde(flatten)]
+    model: &'a Model,
+    #[serde(skip)]
+    mbe_group: i32,
+}
+
+impl Auditable for MbeGroupWeightType<'_> {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::WeightType;
+
+    fn audit_id(&self) -> i32 {
+        self.model.id
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.mbe_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_database::TestDatabase;
+
+    struct Fixture {
+        test_db: TestDatabase,
+        mbe_group: i32,
+        d_group: i32,
+        weight_type: i32,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let test_db = TestDatabase::new().await;
+            let mbe_group = test_db.mbe_group().await;
+            let d_group = test_db.data_group(mbe_group).await;
+            let weight_type = test_db
+                .insert("INSERT INTO weight_type (unit_short, unit, conversion_factor) VALUES ('kg', 'Kilogram', 1)")
+                .await;
+
+            let fixture = Self {
+                test_db,
+                mbe_group,
+                d_group,
+                weight_type,
+            };
+            fixture.link(mbe_group).await;
+            fixture
+        }
+
+        async fn link(&self, mbe_group: i32) {
+            self.test_db
+                .db
+                .execute_unprepared(&format!(
+                    "INSERT INTO mbe_groups_weight_types (id_weight_type, id_created_by, id_mbe_group) \
+                     SELECT {}, owner, id FROM mbe_group WHERE id = {}",
+                    self.weight_type, mbe_group
+                ))
+                .await
+                .unwrap();
+        }
+
+        async fn insert(&self, table: &str, columns: &str, values: &str) -> i32 {
+            self.test_db
+                .insert(&format!(
+                    "INSERT INTO {} ({}, d_group) VALUES ({}, {})",
+                    table, columns, values, self.d_group
+                ))
+                .await
+        }
+
+        async fn dispatch(&self, status: &str) {
+            let dispatch_note = self
+                .insert("dispatch_note", "status", &format!("'{}'", status))
+                .await;
+            let article = self.insert("article", "name", "'Article'").await;
+            self.insert(
+                "dispatch_note_article",
+                "id_dispatch_note, id_article, weight_type, quantity",
+                &format!("{}, {}, {}, 1", dispatch_note, article, self.weight_type),
+            )
+            .await;
+        }
+
+        async fn check(&self) -> Result<()> {
+            let transaction = self.test_db.db.begin().await.unwrap();
+            Entity::check_conversion_changeable(&transaction, self.weight_type, self.mbe_group)
+                .await
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn unused_conversion_can_change() {
+        let fixture = Fixture::new().await;
+        fixture.dispatch("draft").await;
+
+        assert!(fixture.check().await.is_ok());
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn conversion_shared_with_other_groups_is_fixed() {
+        let fixture = Fixture::new().await;
+        let other = fixture
+            .test_db
+            .insert(&format!(
+                "INSERT INTO mbe_group (name, owner) SELECT 'Other', owner FROM mbe_group WHERE id = {}",
+                fixture.mbe_group
+            ))
+            .await;
+        fixture.link(other).await;
+
+        assert!(fixture.check().await.is_err());
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn conversion_used_by_entries_is_fixed() {
+        let fixture = Fixture::new().await;
+        let buyer = fixture.insert("buyer", "name", "'Buyer'").await;
+        let cell = fixture.insert("cell", "name", "'Cell'").await;
+        let culture = fixture.insert("culture", "name", "'Culture'").await;
+        let pair = fixture
+            .insert(
+                "cell_culture_pair",
+                "id_cell, id_culture",
+                &format!("{}, {}", cell, culture),
+            )
+            .await;
+        fixture
+            .insert(
+                "entry",
+                "weight, weight_type, date, id_buyer, id_cell_culture_pair",
+                &format!("10, {}, now(), {}, {}", fixture.weight_type, buyer, pair),
+            )
+            .await;
+
+        assert!(fixture.check().await.is_err());
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn conversion_used_by_issued_notes_is_fixed() {
+        let fixture = Fixture::new().await;
+        fixture.dispatch("issued").await;
+
+        assert!(fixture.check().await.is_err());
+
+        fixture.test_db.close().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn cache_finds_weight_types_once() {
+        let fixture = Fixture::new().await;
+        let cache = WeightTypeCache::default();
+
+        let found = cache
+            .find_for_mbe_group(&fixture.test_db.db, fixture.weight_type, fixture.mbe_group)
+            .await
+            .unwrap();
+        fixture
+            .test_db
+            .db
+            .execute_unprepared("DELETE FROM mbe_groups_weight_types")
+            .await
+            .unwrap();
+        let cached = cache
+            .find_for_mbe_group(&fixture.test_db.db, fixture.weight_type, fixture.mbe_group)
+            .await
+            .unwrap();
+
+        assert_eq!(found, cached);
+        assert!(cache
+            .find_for_mbe_group(
+                &fixture.test_db.db,
+                fixture.weight_type,
+                fixture.mbe_group + 1
+            )
+            .await
+            .is_err());
+
+        fixture.test_db.close().await;
     }
 }
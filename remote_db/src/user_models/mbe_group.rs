@@ -5,7 +5,13 @@ use sea_orm::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{seaorm_models::graphql_schema::extract_session, SeaOrmPool};
+use crate::{
+    seaorm_models::{
+        audit_log::{log_insert, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, CookieSessionGuard},
+    },
+    SeaOrmPool,
+};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "mbe_group")]
@@ -71,7 +77,7 @@ impl MbeGroupQuery {
 
         let transaction = db.begin().await?;
 
-        let res = Entity::find()
+        let mut res = Entity::find()
             .from_raw_sql(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
@@ -101,6 +107,8 @@ impl MbeGroupQuery {
 
         transaction.commit().await?;
 
+        res.retain(|mbe_group| session_data.can_access_mbe_group(mbe_group.id));
+
         Ok(res)
     }
 }
@@ -110,6 +118,7 @@ pub struct MbeGroupMutation;
 
 #[Object]
 impl MbeGroupMutation {
+    #[graphql(guard = "CookieSessionGuard")]
     async fn insert_mbe_group(
         &self,
         ctx: &Context<'_>,
@@ -133,18 +142,23 @@ impl MbeGroupMutation {
         let group_member_model = super::mbe_group_members::ActiveModel {
             id_mbe_user: ActiveValue::Set(session_data.user_id),
             id_mbe_group: ActiveValue::Set(res.id),
+            role: ActiveValue::Set(super::mbe_group_members::MbeGroupRole::Owner),
         };
 
-        let _inserted_group_member_res =
+        let inserted_group_member_res =
             super::mbe_group_members::Entity::insert(group_member_model)
                 .exec_with_returning(&transaction)
                 .await?;
 
+        log_insert(&transaction, session_data, &res).await?;
+        log_insert(&transaction, session_data, &inserted_group_member_res).await?;
+
         transaction.commit().await?;
 
         Ok(res)
     }
 
+    #[graphql(guard = "CookieSessionGuard")]
     async fn update_mbe_group(
         &self,
         ctx: &Context<'_>,
@@ -158,6 +172,11 @@ impl MbeGroupMutation {
             .filter(|name| !name.trim().is_empty())
             .ok_or_else(|| anyhow!("Invalid name!"))?;
         let transaction = db.begin().await?;
+        let before = Entity::find()
+            .filter(Column::Owner.eq(session_data.user_id))
+            .filter(Column::Id.eq(options.id_group))
+            .one(&transaction)
+            .await?;
         let res = Entity::update_many()
             .filter(Column::Owner.eq(session_data.user_id))
             .filter(Column::Id.eq(options.id_group))
@@ -180,9 +199,24 @@ impl MbeGroupMutation {
                 .one(&transaction)
                 .await?
                 .ok_or_else(|| anyhow!("Updated mbe group not found"))?;
+            if let Some(before) = before {
+                log_update(&transaction, session_data, &before, &mbe_group).await?;
+            }
             transaction.commit().await?;
 
             Ok(mbe_group)
         }
     }
 }
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::MbeGroup;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id)
+    }
+}
@@ -0,0 +1,505 @@
+use anyhow::{anyhow, Result};
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use chrono::{Duration, Local};
+use sea_orm::{
+    entity::prelude::*, sea_query::Expr, ActiveValue, Condition, DatabaseTransaction,
+    FromQueryResult, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{generate_token, hash_token, SessionData},
+    seaorm_models::{
+        audit_log::{log_insert, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, CookieSessionGuard, MbeGroupAccessGuard},
+    },
+    SeaOrmPool,
+};
+
+use super::mbe_group_members::{self, MbeGroupRole};
+
+const INVITATION_EXPIRY_DAYS: i64 = 7;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum MbeGroupInvitationStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "accepted")]
+    Accepted,
+    #[sea_orm(string_value = "declined")]
+    Declined,
+    /// Withdrawn by an admin of the mbe group before the invitee responded
+    #[sea_orm(string_value = "revoked")]
+    Revoked,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "mbe_group_invitation")]
+#[graphql(name = "MbeGroupInvitation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub id_mbe_group: i32,
+    #[sea_orm(column_type = "Text")]
+    pub email: String,
+    pub role: MbeGroupRole,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub token_hash: String,
+    pub invited_by: i32,
+    pub status: MbeGroupInvitationStatus,
+    pub expires_at: DateTimeWithTimeZone,
+    pub responded_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::mbe_group::Entity",
+        from = "Column::IdMbeGroup",
+        to = "super::mbe_group::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    MbeGroup,
+    #[sea_orm(
+        belongs_to = "super::mbe_user::Entity",
+        from = "Column::InvitedBy",
+        to = "super::mbe_user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    MbeUser,
+}
+
+impl Related<super::mbe_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeGroup.def()
+    }
+}
+
+impl Related<super::mbe_user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeUser.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    fn is_open(&self) -> bool {
+        self.status == MbeGroupInvitationStatus::Pending
+            && self.expires_at > DateTimeWithTimeZone::from(Local::now())
+    }
+}
+
+impl Entity {
+    fn find_pending_for_email(email: &str) -> Select<Self> {
+        Entity::find()
+            .filter(Column::Email.eq(email.to_lowercase()))
+            .filter(Column::Status.eq(MbeGroupInvitationStatus::Pending))
+            .filter(Column::ExpiresAt.gt(DateTimeWithTimeZone::from(Local::now())))
+    }
+
+    async fn find_locked(transaction: &DatabaseTransaction, query: Select<Self>) -> Result<Model> {
+        query
+            .lock_exclusive()
+            .one(transaction)
+            .await?
+            .filter(|invitation| invitation.is_open())
+            .ok_or_else(|| anyhow!("Invitation not found or expired!"))
+    }
+
+    async fn respond(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
+        invitation: Model,
+        status: MbeGroupInvitationStatus,
+    ) -> Result<Model> {
+        let mut model: ActiveModel = invitation.clone().into();
+        model.status = ActiveValue::Set(status);
+        model.responded_at = ActiveValue::Set(Some(Local::now().into()));
+        let res = model.update(transaction).await?;
+        log_update(transaction, session_data, &invitation, &res).await?;
+        Ok(res)
+    }
+
+    async fn accept(
+        transaction: &DatabaseTransaction,
+        session_data: &SessionData,
+        invitation: Model,
+    ) -> Result<mbe_group_members::Model> {
+        let is_member = mbe_group_members::Entity::find()
+            .filter(mbe_group_members::Column::IdMbeGroup.eq(invitation.id_mbe_group))
+            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
+            .one(transaction)
+            .await?
+            .is_some();
+        if is_member {
+            return Err(anyhow!("Already a member of the group!"));
+        }
+
+        let member = mbe_group_members::Entity::insert(mbe_group_members::ActiveModel {
+            id_mbe_group: ActiveValue::Set(invitation.id_mbe_group),
+            id_mbe_user: ActiveValue::Set(session_data.user_id),
+            role: ActiveValue::Set(invitation.role),
+        })
+        .exec_with_returning(transaction)
+        .await?;
+        log_insert(transaction, session_data, &member).await?;
+
+        Self::respond(
+            transaction,
+            session_data,
+            invitation,
+            MbeGroupInvitationStatus::Accepted,
+        )
+        .await?;
+
+        Ok(member)
+    }
+
+    /// Checks for an open invitation sent to the email or issued for the token
+    pub async fn is_invited<C: ConnectionTrait>(
+        db: &C,
+        email: &str,
+        token: Option<&str>,
+    ) -> Result<bool, DbErr> {
+        let mut condition = Condition::any().add(Column::Email.eq(email.to_lowercase()));
+        if let Some(token) = token {
+            condition = condition.add(Column::TokenHash.eq(hash_token(token)));
+        }
+
+        Ok(Entity::find()
+            .filter(condition)
+            .filter(Column::Status.eq(MbeGroupInvitationStatus::Pending))
+            .filter(Column::ExpiresAt.gt(DateTimeWithTimeZone::from(Local::now())))
+            .one(db)
+            .await?
+            .is_some())
+    }
+
+    /// Accepts the invitation the token was issued for, used when logging in through an invitation link.
+    /// The token alone authorizes the user, so invitations also work when the login email differs
+    pub async fn accept_with_token(
+        db: &DatabaseConnection,
+        token: &str,
+        id_mbe_user: i32,
+    ) -> Result<mbe_group_members::Model> {
+        // Accepted while logging in, before the session of the user exists
+        let session_data = SessionData {
+            user_id: id_mbe_user,
+            session_id: None,
+            token: None,
+        };
+
+        let transaction = db.begin().await?;
+
+        let invitation = Self::find_locked(
+            &transaction,
+            Entity::find().filter(Column::TokenHash.eq(hash_token(token))),
+        )
+        .await?;
+        let member = Self::accept(&transaction, &session_data, invitation).await?;
+
+        transaction.commit().await?;
+
+        Ok(member)
+    }
+}
+
+async fn find_user_email(transaction: &DatabaseTransaction, id_mbe_user: i32) -> Result<String> {
+    Ok(super::mbe_user::Entity::find_by_id(id_mbe_user)
+        .one(transaction)
+        .await?
+        .ok_or_else(|| anyhow!("User not found!"))?
+        .email)
+}
+
+#[derive(InputObject)]
+struct MbeGroupInvitationFetchOptions {
+    id_mbe_group: i32,
+}
+
+#[derive(InputObject)]
+struct MbeGroupInvitationInsertOptions {
+    id_mbe_group: i32,
+    email: String,
+    /// Defaults to viewer
+    role: Option<MbeGroupRole>,
+}
+
+#[derive(InputObject)]
+struct MbeGroupInvitationRevokeOptions {
+    id: i32,
+    id_mbe_group: i32,
+}
+
+#[derive(InputObject)]
+struct MbeGroupInvitationResponseOptions {
+    id: i32,
+}
+
+/// The token is only returned once, when the invitation is created
+#[derive(SimpleObject)]
+struct MbeGroupInvitationCreated {
+    invitation: Model,
+    token: String,
+}
+
+#[derive(Debug, Clone, FromQueryResult, SimpleObject)]
+struct PendingMbeGroupInvitation {
+    id: i32,
+    id_mbe_group: i32,
+    group_name: String,
+    role: MbeGroupRole,
+    invited_by_email: String,
+    expires_at: DateTimeWithTimeZone,
+    created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Default)]
+pub struct MbeGroupInvitationQuery;
+
+#[Object]
+impl MbeGroupInvitationQuery {
+    /// Open invitations sent to the email of the logged in user
+    async fn pending_group_invitations(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<PendingMbeGroupInvitation>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let email = find_user_email(&transaction, session_data.user_id).await?;
+
+        let res = Entity::find_pending_for_email(&email)
+            .inner_join(super::mbe_group::Entity)
+            .inner_join(super::mbe_user::Entity)
+            .column_as(super::mbe_group::Column::Name, "group_name")
+            .column_as(super::mbe_user::Column::Email, "invited_by_email")
+            .order_by_desc(Column::CreatedAt)
+            .into_model::<PendingMbeGroupInvitation>()
+            .all(&transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn mbe_group_invitations(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupInvitationFetchOptions,
+    ) -> Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let transaction = db.begin().await?;
+
+        let res = Entity::find()
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .filter(Column::Status.eq(MbeGroupInvitationStatus::Pending))
+            .order_by_desc(Column::CreatedAt)
+            .all(&transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+#[derive(Default)]
+pub struct MbeGroupInvitationMutation;
+
+#[Object]
+impl MbeGroupInvitationMutation {
+    /// Admins can invite editors and viewers, only the owner can invite admins
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn invite_group_member(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupInvitationInsertOptions,
+    ) -> Result<MbeGroupInvitationCreated> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let email = options.email.trim().to_lowercase();
+        if email.is_empty() {
+            return Err(anyhow!("Invalid email!"));
+        }
+        let role = options.role.unwrap_or(MbeGroupRole::Viewer);
+
+        let transaction = db.begin().await?;
+
+        let caller = mbe_group_members::Entity::find()
+            .filter(mbe_group_members::Column::IdMbeGroup.eq(options.id_mbe_group))
+            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Not authorized to invite to group!"))?;
+        if !caller.role.manages(role) {
+            return Err(anyhow!("Not authorized to invite members with this role!"));
+        }
+
+        let is_member = mbe_group_members::Entity::find()
+            .filter(mbe_group_members::Column::IdMbeGroup.eq(options.id_mbe_group))
+            .inner_join(super::mbe_user::Entity)
+            .filter(super::mbe_user::Column::Email.eq(email.as_str()))
+            .one(&transaction)
+            .await?
+            .is_some();
+        if is_member {
+            return Err(anyhow!("User is already a member of the group!"));
+        }
+
+        if Entity::find_pending_for_email(&email)
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .one(&transaction)
+            .await?
+            .is_some()
+        {
+            return Err(anyhow!("User has already been invited to the group!"));
+        }
+
+        // Expired invitations would still block the unique pending index
+        Entity::update_many()
+            .col_expr(
+                Column::Status,
+                Expr::value(MbeGroupInvitationStatus::Revoked),
+            )
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .filter(Column::Email.eq(email.as_str()))
+            .filter(Column::Status.eq(MbeGroupInvitationStatus::Pending))
+            .exec(&transaction)
+            .await?;
+
+        let token = generate_token()?;
+        let now: DateTimeWithTimeZone = Local::now().into();
+
+        let invitation = ActiveModel {
+            id_mbe_group: ActiveValue::Set(options.id_mbe_group),
+            email: ActiveValue::Set(email),
+            role: ActiveValue::Set(role),
+            token_hash: ActiveValue::Set(hash_token(&token)),
+            invited_by: ActiveValue::Set(session_data.user_id),
+            expires_at: ActiveValue::Set(now + Duration::days(INVITATION_EXPIRY_DAYS)),
+            ..Default::default()
+        }
+        .insert(&transaction)
+        .await?;
+        log_insert(&transaction, session_data, &invitation).await?;
+
+        transaction.commit().await?;
+
+        Ok(MbeGroupInvitationCreated { invitation, token })
+    }
+
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn revoke_group_invitation(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupInvitationRevokeOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let invitation = Entity::find_by_id(options.id)
+            .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
+            .filter(Column::Status.eq(MbeGroupInvitationStatus::Pending))
+            .lock_exclusive()
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Invitation not found!"))?;
+
+        let res = Entity::respond(
+            &transaction,
+            session_data,
+            invitation,
+            MbeGroupInvitationStatus::Revoked,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn accept_group_invitation(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupInvitationResponseOptions,
+    ) -> Result<mbe_group_members::Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let email = find_user_email(&transaction, session_data.user_id).await?;
+        let invitation = Entity::find_locked(
+            &transaction,
+            Entity::find_by_id(options.id).filter(Column::Email.eq(email)),
+        )
+        .await?;
+
+        let res = Entity::accept(&transaction, session_data, invitation).await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn decline_group_invitation(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupInvitationResponseOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let email = find_user_email(&transaction, session_data.user_id).await?;
+        let invitation = Entity::find_locked(
+            &transaction,
+            Entity::find_by_id(options.id).filter(Column::Email.eq(email)),
+        )
+        .await?;
+
+        let res = Entity::respond(
+            &transaction,
+            session_data,
+            invitation,
+            MbeGroupInvitationStatus::Declined,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::MbeGroupInvitation;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id_mbe_group)
+    }
+}
@@ -1,13 +1,75 @@
 use anyhow::{anyhow, Result};
-use async_graphql::{Context, InputObject, Object, SimpleObject};
-use sea_orm::{entity::prelude::*, ActiveValue, FromQueryResult, QuerySelect, TransactionTrait};
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use sea_orm::{
+    entity::prelude::*, ActiveValue, DatabaseTransaction, FromQueryResult, QuerySelect,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    seaorm_models::{graphql_schema::extract_session, RowsDeleted},
+    redis_connection_manager::RedisConnectionManagerExt,
+    seaorm_models::{
+        audit_log::{log_delete, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, CookieSessionGuard, MbeGroupAccessGuard},
+        RowsDeleted,
+    },
+    user_models::mbe_user_session,
     SeaOrmPool,
 };
 
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum MbeGroupRole {
+    /// Creator of the mbe group, every group has exactly one owner
+    #[sea_orm(string_value = "owner")]
+    Owner,
+    /// Manages members and settings of the mbe group
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    /// Reads and changes data
+    #[sea_orm(string_value = "editor")]
+    Editor,
+    /// Only reads data
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+}
+
+impl MbeGroupRole {
+    fn rank(self) -> u8 {
+        match self {
+            MbeGroupRole::Owner => 3,
+            MbeGroupRole::Admin => 2,
+            MbeGroupRole::Editor => 1,
+            MbeGroupRole::Viewer => 0,
+        }
+    }
+
+    /// Checks if the role has at least the permissions of `required`
+    pub fn grants(self, required: MbeGroupRole) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    /// Lowers the role to `limit` if it is higher
+    pub fn limit_to(self, limit: MbeGroupRole) -> MbeGroupRole {
+        if self.grants(limit) {
+            limit
+        } else {
+            self
+        }
+    }
+
+    /// Owners manage all other members, admins manage editors and viewers
+    pub fn manages(self, other: MbeGroupRole) -> bool {
+        match self {
+            MbeGroupRole::Owner => other != MbeGroupRole::Owner,
+            MbeGroupRole::Admin => other.rank() < MbeGroupRole::Admin.rank(),
+            MbeGroupRole::Editor | MbeGroupRole::Viewer => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "mbe_group_members")]
 #[graphql(name = "MbeGroupMembers")]
@@ -16,6 +78,7 @@ pub struct Model {
     pub id_mbe_user: i32,
     #[sea_orm(primary_key, auto_increment = false)]
     pub id_mbe_group: i32,
+    pub role: MbeGroupRole,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -52,6 +115,31 @@ impl Related<super::mbe_user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Entity {
+    async fn find_member(
+        transaction: &DatabaseTransaction,
+        id_mbe_group: i32,
+        id_mbe_user: i32,
+    ) -> Result<Option<Model>> {
+        Ok(Entity::find()
+            .filter(Column::IdMbeGroup.eq(id_mbe_group))
+            .filter(Column::IdMbeUser.eq(id_mbe_user))
+            .one(transaction)
+            .await?)
+    }
+}
+
+async fn find_user_by_email(
+    transaction: &DatabaseTransaction,
+    email: &str,
+) -> Result<super::mbe_user::Model> {
+    super::mbe_user::Entity::find()
+        .filter(super::mbe_user::Column::Email.eq(email.to_lowercase()))
+        .one(transaction)
+        .await?
+        .ok_or_else(|| anyhow!("User not found!"))
+}
+
 #[derive(InputObject)]
 struct MbeGroupMembersQueryOptions {
     id_mbe_group: i32,
@@ -61,6 +149,16 @@ struct MbeGroupMembersQueryOptions {
 struct MbeGroupMembersOptions {
     id_mbe_group: i32,
     member_email: String,
+    /// Also revokes every session of the removed member unless they are a member of other groups,
+    /// only allowed for the group owner
+    force_logout: Option<bool>,
+}
+
+#[derive(InputObject)]
+struct MbeGroupMemberRoleOptions {
+    id_mbe_group: i32,
+    member_email: String,
+    role: MbeGroupRole,
 }
 
 #[derive(Debug, Clone, FromQueryResult, SimpleObject)]
@@ -70,6 +168,8 @@ struct MbeGroupMembersFlattened {
 
     id_group: i32,
     group_name: String,
+
+    role: MbeGroupRole,
 }
 
 #[derive(Default)]
@@ -77,43 +177,27 @@ pub struct MbeGroupMembersQuery;
 
 #[Object]
 impl MbeGroupMembersQuery {
+    #[graphql(guard = "MbeGroupAccessGuard::new(options.id_mbe_group)")]
     async fn mbe_group_members(
         &self,
         ctx: &Context<'_>,
         options: MbeGroupMembersQueryOptions,
     ) -> Result<Vec<MbeGroupMembersFlattened>> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        let session_data = extract_session(ctx)?;
 
         let transaction = db.begin().await?;
 
-        let res = if Entity::find()
+        let res = Entity::find()
             .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
-            .filter(Column::IdMbeUser.eq(session_data.user_id))
-            .one(&transaction)
-            .await?
-            .is_some()
-            || super::mbe_group::Entity::find()
-                .filter(super::mbe_group::Column::Id.eq(options.id_mbe_group))
-                .filter(super::mbe_group::Column::Owner.eq(session_data.user_id))
-                .one(&transaction)
-                .await?
-                .is_some()
-        {
-            Entity::find()
-                .filter(Column::IdMbeGroup.eq(options.id_mbe_group))
-                .inner_join(super::mbe_user::Entity)
-                .inner_join(super::mbe_group::Entity)
-                .column_as(super::mbe_user::Column::Id, "id_user")
-                .column_as(super::mbe_user::Column::Email, "email")
-                .column_as(super::mbe_group::Column::Id, "id_group")
-                .column_as(super::mbe_group::Column::Name, "group_name")
-                .into_model::<MbeGroupMembersFlattened>()
-                .all(&transaction)
-                .await?
-        } else {
-            return Err(anyhow!("Unauthorized"));
-        };
+            .inner_join(super::mbe_user::Entity)
+            .inner_join(super::mbe_group::Entity)
+            .column_as(super::mbe_user::Column::Id, "id_user")
+            .column_as(super::mbe_user::Column::Email, "email")
+            .column_as(super::mbe_group::Column::Id, "id_group")
+            .column_as(super::mbe_group::Column::Name, "group_name")
+            .into_model::<MbeGroupMembersFlattened>()
+            .all(&transaction)
+            .await?;
 
         transaction.commit().await?;
 
@@ -126,109 +210,152 @@ pub struct MbeGroupMembersMutation;
 
 #[Object]
 impl MbeGroupMembersMutation {
-    async fn insert_group_member(
+    /// Members can leave the group on their own, the owner can not leave
+    /// and others can only be removed by members managing their role
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn remove_group_member(
         &self,
         ctx: &Context<'_>,
         options: MbeGroupMembersOptions,
-    ) -> Result<Model> {
+    ) -> Result<RowsDeleted> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         let session_data = extract_session(ctx)?;
 
         let transaction = db.begin().await?;
 
-        let mbe_user = match super::mbe_user::Entity::find()
-            .filter(super::mbe_user::Column::Email.eq(options.member_email.to_lowercase()))
-            .one(&transaction)
+        let mbe_user = find_user_by_email(&transaction, &options.member_email).await?;
+
+        let member = Entity::find_member(&transaction, options.id_mbe_group, mbe_user.id)
             .await?
-        {
-            Some(user) => {
-                if user.id != session_data.user_id {
-                    user
-                } else {
-                    return Err(anyhow!("Cannot add yourself to group!"));
-                }
+            .ok_or_else(|| anyhow!("User is not a member of the group!"))?;
+
+        if member.role == MbeGroupRole::Owner {
+            return Err(anyhow!("The group owner can not be removed!"));
+        }
+
+        let force_logout = options.force_logout.unwrap_or(false);
+        if mbe_user.id != session_data.user_id || force_logout {
+            let caller =
+                Entity::find_member(&transaction, options.id_mbe_group, session_data.user_id)
+                    .await?;
+            if !caller
+                .as_ref()
+                .is_some_and(|caller| caller.role.manages(member.role))
+            {
+                return Err(anyhow!("Not authorized to remove from group!"));
             }
-            None => return Err(anyhow!("User not found!")),
-        };
+            if force_logout && !caller.is_some_and(|caller| caller.role == MbeGroupRole::Owner) {
+                return Err(anyhow!("Only the group owner can log out removed members!"));
+            }
+        }
 
-        let mbe_group = super::mbe_group::Entity::find()
-            .filter(super::mbe_group::Column::Id.eq(options.id_mbe_group))
-            .one(&transaction)
-            .await?
-            .ok_or_else(|| anyhow!("Group not found"))?;
-
-        let res = if mbe_group.owner == session_data.user_id
-            || Entity::find()
-                .filter(Column::IdMbeGroup.eq(mbe_group.id))
-                .filter(Column::IdMbeUser.eq(session_data.user_id))
-                .one(&transaction)
-                .await?
-                .is_some()
-        {
-            let model = ActiveModel {
-                id_mbe_group: ActiveValue::Set(mbe_group.id),
-                id_mbe_user: ActiveValue::Set(mbe_user.id),
-            };
-
-            Entity::insert(model)
-                .exec_with_returning(&transaction)
-                .await?
-        } else {
-            return Err(anyhow!("Not authorized to add to group!"));
-        };
+        log_delete(&transaction, session_data, &member).await?;
+        let res = member.delete(&transaction).await?;
+
+        // Sessions are shared by all groups of the member, they stay while other groups remain
+        let other_memberships = Entity::find()
+            .filter(Column::IdMbeUser.eq(mbe_user.id))
+            .count(&transaction)
+            .await?;
 
         transaction.commit().await?;
 
-        Ok(res)
+        if force_logout && other_memberships == 0 {
+            let RedisConnectionManagerExt(redis_cache) = ctx
+                .data::<RedisConnectionManagerExt>()
+                .expect("Redis connection manager must exist");
+            mbe_user_session::revoke_all(&mut redis_cache.clone(), mbe_user.id).await?;
+        }
+
+        Ok(res.into())
     }
 
-    async fn remove_group_member(
+    /// Ownership can not be transferred by changing roles
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn update_group_member_role(
         &self,
         ctx: &Context<'_>,
-        options: MbeGroupMembersOptions,
-    ) -> Result<RowsDeleted> {
+        options: MbeGroupMemberRoleOptions,
+    ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
         let session_data = extract_session(ctx)?;
 
         let transaction = db.begin().await?;
 
-        let mbe_user = match super::mbe_user::Entity::find()
-            .filter(super::mbe_user::Column::Email.eq(options.member_email.to_lowercase()))
-            .one(&transaction)
+        let mbe_user = find_user_by_email(&transaction, &options.member_email).await?;
+        if mbe_user.id == session_data.user_id {
+            return Err(anyhow!("Cannot change your own role!"));
+        }
+
+        let member = Entity::find_member(&transaction, options.id_mbe_group, mbe_user.id)
             .await?
-        {
-            Some(user) => user,
-            None => return Err(anyhow!("User not found!")),
-        };
+            .ok_or_else(|| anyhow!("User is not a member of the group!"))?;
 
-        let mbe_group = super::mbe_group::Entity::find()
-            .filter(super::mbe_group::Column::Id.eq(options.id_mbe_group))
-            .one(&transaction)
+        let caller = Entity::find_member(&transaction, options.id_mbe_group, session_data.user_id)
             .await?
-            .ok_or_else(|| anyhow!("Group not found"))?;
-
-        // TODO: Add permissions
-        // TODO: Currently all group members can remove eachother
-        let res = if mbe_group.owner == session_data.user_id
-            || Entity::find()
-                .filter(Column::IdMbeGroup.eq(mbe_group.id))
-                .filter(Column::IdMbeUser.eq(session_data.user_id))
-                .one(&transaction)
-                .await?
-                .is_some()
-        {
-            let model = ActiveModel {
-                id_mbe_group: ActiveValue::Set(mbe_group.id),
-                id_mbe_user: ActiveValue::Set(mbe_user.id),
-            };
-
-            Entity::delete(model).exec(&transaction).await?
-        } else {
-            return Err(anyhow!("Not authorized to remove from group!"));
-        };
+            .ok_or_else(|| anyhow!("Not authorized to change roles!"))?;
+        if !caller.role.manages(member.role) || !caller.role.manages(options.role) {
+            return Err(anyhow!("Not authorized to change roles!"));
+        }
+
+        let mut model: ActiveModel = member.clone().into();
+        model.role = ActiveValue::Set(options.role);
+        let res = model.update(&transaction).await?;
+        log_update(&transaction, session_data, &member, &res).await?;
 
         transaction.commit().await?;
 
-        Ok(res.into())
+        Ok(res)
+    }
+}
+
+/// Memberships have no id of their own, they are logged under the id of the member
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::MbeGroupMember;
+
+    fn audit_id(&self) -> i32 {
+        self.id_mbe_user
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id_mbe_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MbeGroupRole::{self, *};
+
+    #[test]
+    fn higher_roles_grant_lower_ones() {
+        assert!(Owner.grants(Admin));
+        assert!(Admin.grants(Editor));
+        assert!(Editor.grants(Viewer));
+        assert!(Editor.grants(Editor));
+        assert!(!Viewer.grants(Editor));
+        assert!(!Admin.grants(Owner));
+    }
+
+    #[test]
+    fn managers_only_manage_lower_roles() {
+        let roles = [Owner, Admin, Editor, Viewer];
+        let managed = |role: MbeGroupRole| {
+            roles
+                .into_iter()
+                .filter(|other| role.manages(*other))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(managed(Owner), vec![Admin, Editor, Viewer]);
+        assert_eq!(managed(Admin), vec![Editor, Viewer]);
+        assert!(managed(Editor).is_empty());
+        assert!(managed(Viewer).is_empty());
+    }
+
+    #[test]
+    fn limit_lowers_only_higher_roles() {
+        assert_eq!(Owner.limit_to(Editor), Editor);
+        assert_eq!(Editor.limit_to(Editor), Editor);
+        assert_eq!(Viewer.limit_to(Editor), Viewer);
     }
 }
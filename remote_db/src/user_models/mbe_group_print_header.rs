@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use async_graphql::{Context, InputObject, Object, SimpleObject};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sea_orm::{entity::prelude::*, ActiveValue, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    seaorm_models::{
+        audit_log::{log_insert, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, MbeGroupAccessGuard},
+    },
+    SeaOrmPool,
+};
+
+use super::mbe_group_members::MbeGroupRole;
+
+/// Header printed on top of every dispatch note of the mbe group
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "mbe_group_print_header")]
+#[graphql(name = "MbeGroupPrintHeader")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id_mbe_group: i32,
+    pub company_name: Option<String>,
+    pub address: Option<String>,
+    /// PNG or JPEG image bytes, left out of audit log snapshots
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub logo: Option<Vec<u8>>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::mbe_group::Entity",
+        from = "Column::IdMbeGroup",
+        to = "super::mbe_group::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    MbeGroup,
+}
+
+impl Related<super::mbe_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeGroup.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(InputObject)]
+struct MbeGroupPrintHeaderFetchOptions {
+    id_mbe_group: i32,
+}
+
+#[derive(InputObject)]
+struct MbeGroupPrintHeaderSetOptions {
+    id_mbe_group: i32,
+    company_name: Option<String>,
+    address: Option<String>,
+    /// Base64 encoded PNG or JPEG image
+    logo: Option<String>,
+    /// Removes the stored logo, ignored if `logo` is set
+    remove_logo: Option<bool>,
+}
+
+fn decode_logo(logo: String) -> Result<Vec<u8>> {
+    let bytes = STANDARD.decode(logo)?;
+    printpdf::image_crate::load_from_memory(&bytes)
+        .map_err(|_| anyhow!("Logo must be a valid PNG or JPEG image!"))?;
+    Ok(bytes)
+}
+
+#[derive(Default)]
+pub struct MbeGroupPrintHeaderQuery;
+
+#[Object]
+impl MbeGroupPrintHeaderQuery {
+    #[graphql(guard = "MbeGroupAccessGuard::new(options.id_mbe_group)")]
+    async fn mbe_group_print_header(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupPrintHeaderFetchOptions,
+    ) -> Result<Option<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+
+        let transaction = db.begin().await?;
+
+        let res = Entity::find_by_id(options.id_mbe_group)
+            .one(&transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+#[derive(Default)]
+pub struct MbeGroupPrintHeaderMutation;
+
+#[Object]
+impl MbeGroupPrintHeaderMutation {
+    #[graphql(guard = "MbeGroupAccessGuard::with_role(options.id_mbe_group, MbeGroupRole::Admin)")]
+    async fn set_mbe_group_print_header(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeGroupPrintHeaderSetOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let logo = match (options.logo, options.remove_logo) {
+            (Some(logo), _) => ActiveValue::Set(Some(decode_logo(logo)?)),
+            (None, Some(true)) => ActiveValue::Set(None),
+            (None, _) => ActiveValue::NotSet,
+        };
+
+        let model = ActiveModel {
+            id_mbe_group: ActiveValue::Set(options.id_mbe_group),
+            company_name: options
+                .company_name
+                .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
+            address: options
+                .address
+                .map_or(ActiveValue::NotSet, |val| ActiveValue::Set(Some(val))),
+            logo,
+            ..Default::default()
+        };
+
+        let transaction = db.begin().await?;
+
+        let before = Entity::find_by_id(options.id_mbe_group)
+            .one(&transaction)
+            .await?;
+
+        let res = match before {
+            Some(before) => {
+                let res = model.update(&transaction).await?;
+                log_update(&transaction, session_data, &before, &res).await?;
+                res
+            }
+            None => {
+                let res = model.insert(&transaction).await?;
+                log_insert(&transaction, session_data, &res).await?;
+                res
+            }
+        };
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::MbeGroupPrintHeader;
+
+    fn audit_id(&self) -> i32 {
+        self.id_mbe_group
+    }
+
+    fn audit_mbe_group(&self) -> Option<i32> {
+        Some(self.id_mbe_group)
+    }
+}
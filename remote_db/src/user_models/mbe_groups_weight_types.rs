@@ -1,18 +1,7 @@
-use anyhow::Result;
-use async_graphql::{Context, InputObject, Object, SimpleObject};
-use sea_orm::{
-    entity::prelude::*, ActiveValue, DatabaseTransaction, DeleteResult, TransactionTrait,
-};
+use async_graphql::SimpleObject;
+use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    seaorm_models::{
-        graphql_schema::{extract_session, MbeGroupAccessGuard},
-        RowsDeleted,
-    },
-    SeaOrmPool,
-};
-
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "mbe_groups_weight_types")]
 pub struct Model {
@@ -72,103 +61,3 @@ impl Related<crate::seaorm_models::weight_type::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
-
-struct MbeGroupsWeightTypesOptionsExt {
-    id_mbe_group: i32,
-    id_created_by: i32,
-    id_weight_type: i32,
-}
-
-#[derive(InputObject)]
-pub struct MbeGroupWeightTypesOptions {
-    pub id_weight_type: i32,
-    pub id_mbe_group: i32,
-}
-
-impl Entity {
-    async fn add_weight_to_group(
-        transaction: &DatabaseTransaction,
-        options: MbeGroupsWeightTypesOptionsExt,
-    ) -> Result<Model, DbErr> {
-        let model = ActiveModel {
-            id_weight_type: ActiveValue::Set(options.id_weight_type),
-            id_created_by: ActiveValue::Set(options.id_created_by),
-            id_mbe_group: ActiveValue::Set(options.id_mbe_group),
-            ..Default::default()
-        };
-
-        model.insert(transaction).await
-    }
-
-    async fn remove_weight_from_group(
-        transaction: &DatabaseTransaction,
-        options: MbeGroupsWeightTypesOptionsExt,
-    ) -> Result<DeleteResult, DbErr> {
-        let model = ActiveModel {
-            id_weight_type: ActiveValue::Set(options.id_weight_type),
-            id_created_by: ActiveValue::Set(options.id_created_by),
-            id_mbe_group: ActiveValue::Set(options.id_mbe_group),
-            ..Default::default()
-        };
-
-        model.delete(transaction).await
-    }
-}
-
-#[derive(Default)]
-pub struct MbeGroupsWeightTypeMutation;
-
-#[Object]
-impl MbeGroupsWeightTypeMutation {
-    #[graphql(guard = "MbeGroupAccessGuard::new(options.id_mbe_group)")]
-    async fn insert_weight_type_into_group(
-        &self,
-        ctx: &Context<'_>,
-        options: MbeGroupWeightTypesOptions,
-    ) -> Result<Model> {
-        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        let user_session = extract_session(ctx)?;
-
-        let transaction = db.begin().await?;
-
-        let res = Entity::add_weight_to_group(
-            &transaction,
-            MbeGroupsWeightTypesOptionsExt {
-                id_mbe_group: options.id_mbe_group,
-                id_created_by: user_session.user_id,
-                id_weight_type: options.id_weight_type,
-            },
-        )
-        .await?;
-
-        transaction.commit().await?;
-
-        Ok(res)
-    }
-
-    #[graphql(guard = "MbeGroupAccessGuard::new(options.id_mbe_group)")]
-    async fn remove_weight_type_from_group(
-        &self,
-        ctx: &Context<'_>,
-        options: MbeGroupWeightTypesOptions,
-    ) -> Result<RowsDeleted> {
-        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        let user_session = extract_session(ctx)?;
-
-        let transaction = db.begin().await?;
-
-        let res = Entity::remove_weight_from_group(
-            &transaction,
-            MbeGroupsWeightTypesOptionsExt {
-                id_mbe_group: options.id_mbe_group,
-                id_created_by: user_session.user_id,
-                id_weight_type: options.id_weight_type,
-            },
-        )
-        .await?;
-
-        transaction.commit().await?;
-
-        Ok(res.into())
-    }
-}
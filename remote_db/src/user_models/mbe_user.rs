@@ -1,9 +1,21 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_graphql::{Context, InputObject, Object, SimpleObject};
+use redis::Cmd;
 use sea_orm::{entity::prelude::*, ActiveValue, TransactionTrait};
 use serde::{Deserialize, Serialize};
 
-use crate::{seaorm_models::graphql_schema::extract_session, SeaOrmPool};
+use crate::{
+    auth::generate_token,
+    password_auth::{
+        hash_password, password_reset_cache_key, verify_password, PASSWORD_RESET_CODE_EXPIRY,
+    },
+    redis_connection_manager::RedisConnectionManagerExt,
+    seaorm_models::{
+        audit_log::{log_insert, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, AdminGuard, CookieSessionGuard},
+    },
+    SeaOrmPool,
+};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "mbe_user")]
@@ -14,6 +26,18 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub email: String,
     pub created_at: DateTimeWithTimeZone,
+    /// Administrators can create users regardless of the registration policy
+    pub is_admin: bool,
+    /// Argon2 hash, users without a password can only log in with a login provider
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub password_hash: Option<String>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub failed_login_attempts: i32,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub locked_until: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -38,19 +62,31 @@ struct MbeUserInsertOptions {
     email: String,
 }
 
+#[derive(InputObject)]
+struct PasswordResetCodeOptions {
+    id_mbe_user: i32,
+}
+
+#[derive(InputObject)]
+struct SetPasswordOptions {
+    /// Required when the user already has a password
+    current_password: Option<String>,
+    new_password: String,
+}
+
 #[derive(Default)]
 pub struct MbeUserMutation;
 
 #[Object]
 impl MbeUserMutation {
+    #[graphql(guard = "AdminGuard")]
     async fn insert_mbe_user(
         &self,
         ctx: &Context<'_>,
         options: MbeUserInsertOptions,
     ) -> Result<Model> {
         let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
-        let _session_data = extract_session(ctx)?;
-
+        let session_data = extract_session(ctx)?;
         let model = ActiveModel {
             email: ActiveValue::Set(options.email.to_lowercase()),
             ..Default::default()
@@ -61,9 +97,83 @@ impl MbeUserMutation {
         let res = Entity::insert(model)
             .exec_with_returning(&transaction)
             .await?;
+        log_insert(&transaction, session_data, &res).await?;
 
         transaction.commit().await?;
 
         Ok(res)
     }
+
+    /// One-time code for the `/password-reset` route, handed to the user by an administrator
+    #[graphql(guard = "AdminGuard")]
+    async fn create_password_reset_code(
+        &self,
+        ctx: &Context<'_>,
+        options: PasswordResetCodeOptions,
+    ) -> Result<String> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let RedisConnectionManagerExt(redis_cache) = ctx
+            .data::<RedisConnectionManagerExt>()
+            .expect("Redis connection manager must exist");
+
+        let transaction = db.begin().await?;
+
+        let user = Entity::find_by_id(options.id_mbe_user)
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("User not found!"))?;
+
+        transaction.commit().await?;
+
+        let code = generate_token()?;
+        Cmd::set_ex(
+            password_reset_cache_key(&code),
+            user.id,
+            PASSWORD_RESET_CODE_EXPIRY,
+        )
+        .query_async::<_, ()>(&mut redis_cache.clone())
+        .await?;
+
+        Ok(code)
+    }
+
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn set_password(&self, ctx: &Context<'_>, options: SetPasswordOptions) -> Result<bool> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let password_hash = hash_password(&options.new_password)?;
+
+        let transaction = db.begin().await?;
+
+        let user = Entity::find_by_id(session_data.user_id)
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| anyhow!("User not found!"))?;
+
+        if let Some(current_hash) = &user.password_hash {
+            let current_password = options.current_password.as_deref().unwrap_or_default();
+            if !verify_password(current_password, current_hash) {
+                return Err(anyhow!("Current password is incorrect!"));
+            }
+        }
+
+        let mut model: ActiveModel = user.clone().into();
+        model.password_hash = ActiveValue::Set(Some(password_hash));
+        let res = model.update(&transaction).await?;
+        // Password hashes are not part of the snapshot, only the change itself is recorded
+        log_update(&transaction, session_data, &user, &res).await?;
+
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+}
+
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::MbeUser;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
 }
@@ -0,0 +1,299 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use actix_web::{http::header::USER_AGENT, HttpRequest};
+use anyhow::Result;
+use async_graphql::{Context, InputObject, Object, SimpleObject};
+use chrono::{Duration, Local};
+use log::error;
+use redis::{aio::ConnectionManager, Cmd, RedisError, Script};
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{generate_session_id, Platform, SessionData},
+    redis_connection_manager::RedisConnectionManagerExt,
+    seaorm_models::graphql_schema::{extract_session, CookieSessionGuard},
+    MONTH,
+};
+
+/// Last seen is only refreshed this often to avoid a write on every request
+const LAST_SEEN_UPDATE_INTERVAL_MINUTES: i64 = 5;
+
+/// Replaces a registered session and renews the registry, unless the session was
+/// revoked since it was read. Returns whether the session was still registered
+const REFRESH_SCRIPT: &str = r#"
+if redis.call('HEXISTS', KEYS[1], ARGV[1]) == 0 then
+    return 0
+end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+redis.call('EXPIRE', KEYS[1], ARGV[3])
+return 1
+"#;
+
+/// Sessions of a user are kept in a redis hash next to the session store,
+/// removing a session from the hash invalidates its cookie
+fn registry_key(user_id: i32) -> String {
+    format!("sessions:{}", user_id)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(name = "MbeUserSession")]
+pub struct SessionInfo {
+    pub id: String,
+    pub platform: Platform,
+    pub created_at: DateTimeWithTimeZone,
+    pub last_seen_at: DateTimeWithTimeZone,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Session of the current request
+    #[serde(skip)]
+    pub current: bool,
+}
+
+impl SessionInfo {
+    fn update_client(&mut self, req: &HttpRequest) {
+        self.last_seen_at = Local::now().into();
+        self.ip = req
+            .connection_info()
+            .realip_remote_addr()
+            .map(str::to_string);
+        self.user_agent = req
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(str::to_string);
+    }
+
+    fn is_expired(&self) -> bool {
+        let now: DateTimeWithTimeZone = Local::now().into();
+        self.last_seen_at + Duration::seconds(MONTH) < now
+    }
+
+    async fn save(
+        &self,
+        redis_cache: &mut ConnectionManager,
+        user_id: i32,
+    ) -> Result<(), RedisError> {
+        let key = registry_key(user_id);
+        redis::pipe()
+            .atomic()
+            .hset(
+                &key,
+                &self.id,
+                serde_json::to_string(self).expect("Serializable session"),
+            )
+            .expire(&key, MONTH as usize)
+            .query_async(redis_cache)
+            .await
+    }
+
+    /// Same as `save` for a session that is already registered, a revoked session stays revoked
+    async fn refresh(
+        &self,
+        redis_cache: &mut ConnectionManager,
+        user_id: i32,
+    ) -> Result<bool, RedisError> {
+        Script::new(REFRESH_SCRIPT)
+            .key(registry_key(user_id))
+            .arg(&self.id)
+            .arg(serde_json::to_string(self).expect("Serializable session"))
+            .arg(MONTH)
+            .invoke_async(redis_cache)
+            .await
+    }
+}
+
+/// Creates the session data of a new login and registers it so it can be listed and revoked
+pub async fn register(
+    redis_cache: &mut ConnectionManager,
+    user_id: i32,
+    platform: Platform,
+    req: &HttpRequest,
+) -> Result<SessionData, RedisError> {
+    let now: DateTimeWithTimeZone = Local::now().into();
+    let mut session_info = SessionInfo {
+        id: generate_session_id(),
+        platform,
+        created_at: now,
+        last_seen_at: now,
+        ip: None,
+        user_agent: None,
+        current: false,
+    };
+    session_info.update_client(req);
+    session_info.save(redis_cache, user_id).await?;
+
+    Ok(SessionData {
+        user_id,
+        session_id: Some(session_info.id),
+        token: None,
+    })
+}
+
+/// Checks that the session has not been revoked and refreshes when it was last seen.
+/// Sessions created before sessions were registered have no id and can't be revoked,
+/// they are rejected so their users log in again
+pub async fn touch(
+    redis_cache: &mut ConnectionManager,
+    session_data: &SessionData,
+    req: &HttpRequest,
+) -> Result<bool, RedisError> {
+    let session_id = match &session_data.session_id {
+        Some(session_id) => session_id,
+        None => return Ok(false),
+    };
+
+    let session_info: Option<String> = Cmd::hget(registry_key(session_data.user_id), session_id)
+        .query_async(redis_cache)
+        .await?;
+    let mut session_info: SessionInfo = match session_info.map(|s| serde_json::from_str(&s)) {
+        Some(Ok(session_info)) => session_info,
+        Some(Err(e)) => {
+            error!("Invalid registered session: {}", e);
+            return Ok(false);
+        }
+        None => return Ok(false),
+    };
+
+    let now: DateTimeWithTimeZone = Local::now().into();
+    if session_info.last_seen_at + Duration::minutes(LAST_SEEN_UPDATE_INTERVAL_MINUTES) < now {
+        session_info.update_client(req);
+        return session_info
+            .refresh(redis_cache, session_data.user_id)
+            .await;
+    }
+
+    Ok(true)
+}
+
+/// Checks that the session has not been revoked, without refreshing it
+pub async fn is_registered(
+    redis_cache: &mut ConnectionManager,
+    session_data: &SessionData,
+) -> Result<bool, RedisError> {
+    match &session_data.session_id {
+        Some(session_id) => {
+            Cmd::hexists(registry_key(session_data.user_id), session_id)
+                .query_async(redis_cache)
+                .await
+        }
+        None => Ok(false),
+    }
+}
+
+/// Active sessions of the user, most recently used first. Expired sessions are cleaned up
+pub async fn list(
+    redis_cache: &mut ConnectionManager,
+    user_id: i32,
+) -> Result<Vec<SessionInfo>, RedisError> {
+    let key = registry_key(user_id);
+    let sessions: HashMap<String, String> = Cmd::hgetall(&key).query_async(redis_cache).await?;
+
+    let mut active = Vec::with_capacity(sessions.len());
+    let mut expired = Vec::new();
+    for (id, session_info) in sessions {
+        match serde_json::from_str::<SessionInfo>(&session_info) {
+            Ok(session_info) if !session_info.is_expired() => active.push(session_info),
+            _ => expired.push(id),
+        }
+    }
+
+    if !expired.is_empty() {
+        Cmd::hdel(&key, expired)
+            .query_async::<_, ()>(redis_cache)
+            .await?;
+    }
+
+    active.sort_unstable_by_key(|session| Reverse(session.last_seen_at));
+
+    Ok(active)
+}
+
+pub async fn revoke(
+    redis_cache: &mut ConnectionManager,
+    user_id: i32,
+    session_id: &str,
+) -> Result<bool, RedisError> {
+    let deleted: i64 = Cmd::hdel(registry_key(user_id), session_id)
+        .query_async(redis_cache)
+        .await?;
+
+    Ok(deleted > 0)
+}
+
+/// Logs the user out everywhere, personal access tokens are not affected
+pub async fn revoke_all(
+    redis_cache: &mut ConnectionManager,
+    user_id: i32,
+) -> Result<(), RedisError> {
+    Cmd::del(registry_key(user_id))
+        .query_async(redis_cache)
+        .await
+}
+
+#[derive(InputObject)]
+struct MbeUserSessionRevokeOptions {
+    id: String,
+}
+
+#[derive(Default)]
+pub struct MbeUserSessionQuery;
+
+#[Object]
+impl MbeUserSessionQuery {
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn active_sessions(&self, ctx: &Context<'_>) -> Result<Vec<SessionInfo>> {
+        let RedisConnectionManagerExt(redis_cache) = ctx
+            .data::<RedisConnectionManagerExt>()
+            .expect("Redis connection manager must exist");
+        let session_data = extract_session(ctx)?;
+
+        let mut res = list(&mut redis_cache.clone(), session_data.user_id).await?;
+        for session_info in res.iter_mut() {
+            session_info.current = session_data.session_id.as_ref() == Some(&session_info.id);
+        }
+
+        Ok(res)
+    }
+}
+
+#[derive(Default)]
+pub struct MbeUserSessionMutation;
+
+#[Object]
+impl MbeUserSessionMutation {
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn revoke_session(
+        &self,
+        ctx: &Context<'_>,
+        options: MbeUserSessionRevokeOptions,
+    ) -> Result<bool> {
+        let RedisConnectionManagerExt(redis_cache) = ctx
+            .data::<RedisConnectionManagerExt>()
+            .expect("Redis connection manager must exist");
+        let session_data = extract_session(ctx)?;
+
+        Ok(revoke(&mut redis_cache.clone(), session_data.user_id, &options.id).await?)
+    }
+
+    /// Logs out every session except the current one
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn revoke_other_sessions(&self, ctx: &Context<'_>) -> Result<i32> {
+        let RedisConnectionManagerExt(redis_cache) = ctx
+            .data::<RedisConnectionManagerExt>()
+            .expect("Redis connection manager must exist");
+        let session_data = extract_session(ctx)?;
+        let mut redis_cache = redis_cache.clone();
+
+        let mut revoked = 0;
+        for session_info in list(&mut redis_cache, session_data.user_id).await? {
+            if session_data.session_id.as_ref() != Some(&session_info.id)
+                && revoke(&mut redis_cache, session_data.user_id, &session_info.id).await?
+            {
+                revoked += 1;
+            }
+        }
+
+        Ok(revoked)
+    }
+}
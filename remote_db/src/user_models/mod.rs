@@ -1,4 +1,9 @@
 pub mod mbe_group;
+pub mod mbe_group_invitation;
 pub mod mbe_group_members;
+pub mod mbe_group_print_header;
 pub mod mbe_groups_weight_types;
 pub mod mbe_user;
+pub mod mbe_user_session;
+pub mod personal_access_token;
+pub mod personal_access_token_mbe_groups;
@@ -0,0 +1,328 @@
+use anyhow::{anyhow, Result};
+use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, SimpleObject};
+use chrono::{Duration, Local};
+use sea_orm::{
+    entity::prelude::*, sea_query::Expr, ActiveValue, DatabaseTransaction, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{generate_token, hash_token, SessionData, TokenScope},
+    seaorm_models::{
+        audit_log::{log_insert, log_update, AuditEntityType, Auditable},
+        graphql_schema::{extract_session, CookieSessionGuard},
+    },
+    SeaOrmPool,
+};
+
+use super::{mbe_group_members, personal_access_token_mbe_groups};
+
+/// Prefix of every token, makes leaked tokens easy to recognize
+const TOKEN_PREFIX: &str = "mbe_pat_";
+const DEFAULT_EXPIRY_DAYS: i64 = 90;
+const MAX_EXPIRY_DAYS: i64 = 365;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Enum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(8))")]
+pub enum TokenAccess {
+    #[sea_orm(string_value = "read")]
+    Read,
+    /// Same as editors, managing the mbe group is never possible with a token
+    #[sea_orm(string_value = "write")]
+    Write,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "personal_access_token")]
+#[graphql(name = "PersonalAccessToken", complex)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub id_mbe_user: i32,
+    pub name: String,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub token_hash: String,
+    pub access: TokenAccess,
+    pub expires_at: DateTimeWithTimeZone,
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::mbe_user::Entity",
+        from = "Column::IdMbeUser",
+        to = "super::mbe_user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    MbeUser,
+    #[sea_orm(has_many = "super::personal_access_token_mbe_groups::Entity")]
+    PersonalAccessTokenMbeGroups,
+}
+
+impl Related<super::mbe_user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeUser.def()
+    }
+}
+
+impl Related<super::personal_access_token_mbe_groups::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PersonalAccessTokenMbeGroups.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+async fn find_mbe_groups<C: ConnectionTrait>(db: &C, id_token: i32) -> Result<Vec<i32>, DbErr> {
+    personal_access_token_mbe_groups::Entity::find()
+        .select_only()
+        .column(personal_access_token_mbe_groups::Column::IdMbeGroup)
+        .filter(personal_access_token_mbe_groups::Column::IdPersonalAccessToken.eq(id_token))
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+#[ComplexObject]
+impl Model {
+    async fn mbe_groups(&self, ctx: &Context<'_>) -> Result<Vec<i32>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        Ok(find_mbe_groups(db.get_ref(), self.id).await?)
+    }
+}
+
+impl Entity {
+    /// Resolves a bearer token into the session of its owner, restricted to the scope of the token.
+    /// Returns `None` for unknown, expired and revoked tokens
+    pub async fn authenticate(
+        db: &DatabaseConnection,
+        token: &str,
+    ) -> Result<Option<SessionData>, DbErr> {
+        let now: DateTimeWithTimeZone = Local::now().into();
+
+        let transaction = db.begin().await?;
+
+        let token = Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token)))
+            .filter(Column::RevokedAt.is_null())
+            .filter(Column::ExpiresAt.gt(now))
+            .one(&transaction)
+            .await?;
+
+        let res = match token {
+            Some(token) => {
+                Entity::update_many()
+                    .col_expr(Column::LastUsedAt, Expr::value(now))
+                    .filter(Column::Id.eq(token.id))
+                    .exec(&transaction)
+                    .await?;
+
+                Some(SessionData {
+                    user_id: token.id_mbe_user,
+                    session_id: None,
+                    token: Some(TokenScope {
+                        id: token.id,
+                        access: token.access,
+                        mbe_groups: find_mbe_groups(&transaction, token.id).await?,
+                    }),
+                })
+            }
+            None => None,
+        };
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+
+    /// Checks that the token was not revoked and has not expired since it authenticated
+    pub async fn is_active(db: &DatabaseConnection, id: i32) -> Result<bool, DbErr> {
+        let now: DateTimeWithTimeZone = Local::now().into();
+
+        Ok(Entity::find_by_id(id)
+            .filter(Column::RevokedAt.is_null())
+            .filter(Column::ExpiresAt.gt(now))
+            .count(db)
+            .await?
+            > 0)
+    }
+
+    async fn find_own(
+        transaction: &DatabaseTransaction,
+        id: i32,
+        id_mbe_user: i32,
+    ) -> Result<Model> {
+        Entity::find_by_id(id)
+            .filter(Column::IdMbeUser.eq(id_mbe_user))
+            .one(transaction)
+            .await?
+            .ok_or_else(|| anyhow!("Token not found!"))
+    }
+}
+
+#[derive(InputObject)]
+struct PersonalAccessTokenInsertOptions {
+    name: String,
+    mbe_groups: Vec<i32>,
+    access: TokenAccess,
+    /// Defaults to 90 days, tokens can be valid for at most a year
+    expires_in_days: Option<i64>,
+}
+
+#[derive(InputObject)]
+struct PersonalAccessTokenRevokeOptions {
+    id: i32,
+}
+
+/// The token is only returned once, when it is created
+#[derive(SimpleObject)]
+struct PersonalAccessTokenCreated {
+    personal_access_token: Model,
+    token: String,
+}
+
+#[derive(Default)]
+pub struct PersonalAccessTokenQuery;
+
+#[Object]
+impl PersonalAccessTokenQuery {
+    /// Tokens of the logged in user that have not been revoked
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn personal_access_tokens(&self, ctx: &Context<'_>) -> Result<Vec<Model>> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let res = Entity::find()
+            .filter(Column::IdMbeUser.eq(session_data.user_id))
+            .filter(Column::RevokedAt.is_null())
+            .order_by_desc(Column::CreatedAt)
+            .all(&transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+#[derive(Default)]
+pub struct PersonalAccessTokenMutation;
+
+#[Object]
+impl PersonalAccessTokenMutation {
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn create_personal_access_token(
+        &self,
+        ctx: &Context<'_>,
+        options: PersonalAccessTokenInsertOptions,
+    ) -> Result<PersonalAccessTokenCreated> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let name = options.name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Invalid name!"));
+        }
+        let expires_in_days = options.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+        if !(1..=MAX_EXPIRY_DAYS).contains(&expires_in_days) {
+            return Err(anyhow!(
+                "Tokens must expire in 1 to {} days!",
+                MAX_EXPIRY_DAYS
+            ));
+        }
+        let mut mbe_groups = options.mbe_groups;
+        mbe_groups.sort_unstable();
+        mbe_groups.dedup();
+        if mbe_groups.is_empty() {
+            return Err(anyhow!("Tokens must be scoped to at least one group!"));
+        }
+
+        let transaction = db.begin().await?;
+
+        let memberships = mbe_group_members::Entity::find()
+            .filter(mbe_group_members::Column::IdMbeUser.eq(session_data.user_id))
+            .filter(mbe_group_members::Column::IdMbeGroup.is_in(mbe_groups.clone()))
+            .count(&transaction)
+            .await?;
+        if memberships != mbe_groups.len() as u64 {
+            return Err(anyhow!("Tokens can only be scoped to your own groups!"));
+        }
+
+        let token = format!("{}{}", TOKEN_PREFIX, generate_token()?);
+        let now: DateTimeWithTimeZone = Local::now().into();
+
+        let personal_access_token = ActiveModel {
+            id_mbe_user: ActiveValue::Set(session_data.user_id),
+            name: ActiveValue::Set(name.to_string()),
+            token_hash: ActiveValue::Set(hash_token(&token)),
+            access: ActiveValue::Set(options.access),
+            expires_at: ActiveValue::Set(now + Duration::days(expires_in_days)),
+            ..Default::default()
+        }
+        .insert(&transaction)
+        .await?;
+
+        personal_access_token_mbe_groups::Entity::insert_many(mbe_groups.into_iter().map(
+            |id_mbe_group| personal_access_token_mbe_groups::ActiveModel {
+                id_personal_access_token: ActiveValue::Set(personal_access_token.id),
+                id_mbe_group: ActiveValue::Set(id_mbe_group),
+            },
+        ))
+        .exec(&transaction)
+        .await?;
+
+        log_insert(&transaction, session_data, &personal_access_token).await?;
+
+        transaction.commit().await?;
+
+        Ok(PersonalAccessTokenCreated {
+            personal_access_token,
+            token,
+        })
+    }
+
+    #[graphql(guard = "CookieSessionGuard")]
+    async fn revoke_personal_access_token(
+        &self,
+        ctx: &Context<'_>,
+        options: PersonalAccessTokenRevokeOptions,
+    ) -> Result<Model> {
+        let db = ctx.data::<SeaOrmPool>().expect("Pool must exist");
+        let session_data = extract_session(ctx)?;
+
+        let transaction = db.begin().await?;
+
+        let token = Entity::find_own(&transaction, options.id, session_data.user_id).await?;
+        if token.revoked_at.is_some() {
+            return Err(anyhow!("Token has already been revoked!"));
+        }
+
+        let mut model: ActiveModel = token.clone().into();
+        model.revoked_at = ActiveValue::Set(Some(Local::now().into()));
+        let res = model.update(&transaction).await?;
+        log_update(&transaction, session_data, &token, &res).await?;
+
+        transaction.commit().await?;
+
+        Ok(res)
+    }
+}
+
+/// Tokens can be scoped to several mbe groups, so they are not listed in the audit log of any of them
+impl Auditable for Model {
+    const ENTITY_TYPE: AuditEntityType = AuditEntityType::PersonalAccessToken;
+
+    fn audit_id(&self) -> i32 {
+        self.id
+    }
+}
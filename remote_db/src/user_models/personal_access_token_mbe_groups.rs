@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Mbe groups a personal access token can be used in
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "personal_access_token_mbe_groups")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id_personal_access_token: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id_mbe_group: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::personal_access_token::Entity",
+        from = "Column::IdPersonalAccessToken",
+        to = "super::personal_access_token::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    PersonalAccessToken,
+    #[sea_orm(
+        belongs_to = "super::mbe_group::Entity",
+        from = "Column::IdMbeGroup",
+        to = "super::mbe_group::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    MbeGroup,
+}
+
+impl Related<super::personal_access_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PersonalAccessToken.def()
+    }
+}
+
+impl Related<super::mbe_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MbeGroup.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
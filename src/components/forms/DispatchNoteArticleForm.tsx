@@ -44,7 +44,7 @@ type FormInput = {
 const LIMIT = 10;
 
 type DispatchNoteArticleFormProps = FormProps<
-    DispatchNoteArticle,
+    Omit<DispatchNoteArticle, "quantityIn">,
     InsertDispatchNoteArticleMutation,
     DispatchNoteArticleInsertOptions,
     UpdateDispatchNoteArticleMutation,
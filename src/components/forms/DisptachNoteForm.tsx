@@ -6,6 +6,7 @@ import {
     Grid,
     HoverCard,
     NumberInput,
+    TextInput,
     Title,
 } from "@mantine/core";
 import { DateInput } from "@mantine/dates";
@@ -75,14 +76,11 @@ export default function DispatchNoteForm({
         data?.dispatchNoteIdent.identifier
     );
 
-    const { handleSubmit, reset, control, setValue } =
+    const { handleSubmit, reset, control } =
         useForm<DispatchNoteInsertOptions>({
             mode: "onChange",
             defaultValues: {
                 noteType: edit?.noteType,
-                numericalIdentifier:
-                    edit?.numericalIdentifier ??
-                    data?.dispatchNoteIdent.identifier,
                 issuingDate: edit?.issuingDate,
             },
         });
@@ -117,10 +115,6 @@ export default function DispatchNoteForm({
         onSuccess: async () => {
             const res = await refetch();
             setIdentValue(res.data?.dispatchNoteIdent.identifier);
-            setValue(
-                "numericalIdentifier",
-                res.data?.dispatchNoteIdent.identifier
-            );
         },
     });
 
@@ -129,9 +123,8 @@ export default function DispatchNoteForm({
             return;
         } else {
             setIdentValue(data?.dispatchNoteIdent.identifier);
-            setValue("numericalIdentifier", data?.dispatchNoteIdent.identifier);
         }
-    }, [data, setValue, edit]);
+    }, [data, edit]);
 
     const isIdentNumber = useCallback(() => {
         if (identValue === "" || identValue === undefined) {
@@ -140,6 +133,21 @@ export default function DispatchNoteForm({
         return true;
     }, [identValue]);
 
+    const setIdentifier = useCallback(() => {
+        if (isIdentNumber() && dataGroupId) {
+            updateIdent.mutate({
+                updateOptions: {
+                    identifier: identValue as number,
+                    idDataGroup: dataGroupId,
+                },
+            });
+        }
+    }, [isIdentNumber, dataGroupId, updateIdent, identValue]);
+
+    const resetIdentValue = useCallback(() => {
+        setIdentValue(data?.dispatchNoteIdent.identifier);
+    }, [data]);
+
     return (
         <BaseForm
             submitDisabled={insert.isLoading || update.isLoading}
@@ -197,132 +205,91 @@ export default function DispatchNoteForm({
                     />
                 </Grid.Col>
                 <Grid.Col sm={12} md={6} lg={6}>
-                    <Controller
-                        name="numericalIdentifier"
-                        control={control}
-                        render={({ field: { value, onChange } }) => (
-                            <NumberInput
-                                value={value ? value : undefined}
-                                onChange={onChange}
-                                label={
-                                    <Flex
-                                        gap="sm"
-                                        justify="center"
-                                        align="center"
-                                    >
-                                        {t("dispatchNote.numericalIdentifier")}
-                                        {edit === undefined ? (
-                                            <HoverCard withArrow>
-                                                <HoverCard.Target>
-                                                    <div>
-                                                        <FaEdit
-                                                            className={
-                                                                classes.faEditIconPointer
-                                                            }
-                                                        />
-                                                    </div>
-                                                </HoverCard.Target>
-                                                <HoverCard.Dropdown
-                                                    onClick={(e) => {
-                                                        e.preventDefault();
-                                                        e.stopPropagation();
-                                                    }}
-                                                >
-                                                    <Title order={5}>
-                                                        {t(
-                                                            "dispatchNote.setIdentifierTitle"
+                    {/* Assigned by the server when the note is issued */}
+                    <TextInput
+                        value={
+                            edit
+                                ? edit.formattedIdentifier ?? ""
+                                : data?.dispatchNoteIdent
+                                      .nextFormattedIdentifier ?? ""
+                        }
+                        readOnly
+                        label={
+                            <Flex gap="sm" justify="center" align="center">
+                                {t("dispatchNote.numericalIdentifier")}
+                                {edit === undefined ? (
+                                    <HoverCard withArrow>
+                                        <HoverCard.Target>
+                                            <div>
+                                                <FaEdit
+                                                    className={
+                                                        classes.faEditIconPointer
+                                                    }
+                                                />
+                                            </div>
+                                        </HoverCard.Target>
+                                        <HoverCard.Dropdown
+                                            onClick={(e) => {
+                                                e.preventDefault();
+                                                e.stopPropagation();
+                                            }}
+                                        >
+                                            <Title order={5}>
+                                                {t(
+                                                    "dispatchNote.setIdentifierTitle"
+                                                )}
+                                            </Title>
+                                            <Divider my="xs" />
+                                            <Grid>
+                                                <Grid.Col>
+                                                    <NumberInput
+                                                        value={identValue ?? 0}
+                                                        onChange={setIdentValue}
+                                                        label={t(
+                                                            "dispatchNote.setIdentifierLabel"
                                                         )}
-                                                    </Title>
-                                                    <Divider my="xs" />
-                                                    <Grid>
-                                                        <Grid.Col>
-                                                            <NumberInput
-                                                                value={
-                                                                    identValue ??
-                                                                    0
-                                                                }
-                                                                onChange={(
-                                                                    val
-                                                                ) => {
-                                                                    setIdentValue(
-                                                                        val
-                                                                    );
-                                                                }}
-                                                                label={t(
-                                                                    "dispatchNote.setIdentifierLabel"
-                                                                )}
-                                                                precision={10}
-                                                                removeTrailingZeros
-                                                            />
-                                                        </Grid.Col>
-                                                        <Grid.Col>
-                                                            <Flex justify="space-between">
-                                                                <Button
-                                                                    color="teal"
-                                                                    disabled={
-                                                                        !isIdentNumber()
-                                                                    }
-                                                                    onClick={() => {
-                                                                        if (
-                                                                            isIdentNumber()
-                                                                        ) {
-                                                                            const identifier =
-                                                                                identValue as number;
-                                                                            if (
-                                                                                dataGroupId
-                                                                            ) {
-                                                                                updateIdent.mutate(
-                                                                                    {
-                                                                                        updateOptions:
-                                                                                            {
-                                                                                                identifier:
-                                                                                                    identifier,
-                                                                                                idDataGroup:
-                                                                                                    dataGroupId,
-                                                                                            },
-                                                                                    }
-                                                                                );
-                                                                            }
-                                                                        }
-                                                                    }}
-                                                                >
-                                                                    {t(
-                                                                        "dispatchNote.setButton"
-                                                                    )}
-                                                                </Button>
-                                                                <Button
-                                                                    color="red"
-                                                                    onClick={() => {
-                                                                        setIdentValue(
-                                                                            data
-                                                                                ?.dispatchNoteIdent
-                                                                                .identifier
-                                                                        );
-                                                                    }}
-                                                                >
-                                                                    {t(
-                                                                        "dispatchNote.resetButton"
-                                                                    )}
-                                                                </Button>
-                                                            </Flex>
-                                                        </Grid.Col>
-                                                    </Grid>
-                                                </HoverCard.Dropdown>
-                                            </HoverCard>
-                                        ) : (
-                                            <></>
-                                        )}
-                                    </Flex>
-                                }
-                                placeholder={t(
-                                    "dispatchNote.numericalIdentifier"
+                                                        precision={10}
+                                                        removeTrailingZeros
+                                                    />
+                                                </Grid.Col>
+                                                <Grid.Col>
+                                                    <Flex justify="space-between">
+                                                        <Button
+                                                            color="teal"
+                                                            disabled={
+                                                                !isIdentNumber()
+                                                            }
+                                                            onClick={
+                                                                setIdentifier
+                                                            }
+                                                        >
+                                                            {t(
+                                                                "dispatchNote.setButton"
+                                                            )}
+                                                        </Button>
+                                                        <Button
+                                                            color="red"
+                                                            onClick={
+                                                                resetIdentValue
+                                                            }
+                                                        >
+                                                            {t(
+                                                                "dispatchNote.resetButton"
+                                                            )}
+                                                        </Button>
+                                                    </Flex>
+                                                </Grid.Col>
+                                            </Grid>
+                                        </HoverCard.Dropdown>
+                                    </HoverCard>
+                                ) : (
+                                    <></>
                                 )}
-                                precision={10}
-                                removeTrailingZeros
-                                autoComplete="off"
-                                spellCheck={false}
-                            />
-                        )}
+                            </Flex>
+                        }
+                        placeholder={t("dispatchNote.numericalIdentifier")}
+                        autoComplete="off"
+                        spellCheck={false}
                     />
                 </Grid.Col>
                 <Grid.Col sm={12} md={6} lg={6}>
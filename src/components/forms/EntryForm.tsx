@@ -17,8 +17,11 @@ import {
     useGetBuyersQuery,
     useGetPairedCellsQuery,
     useGetPairedCulturesQuery,
+    useGetWeightTypesQuery,
     useInsertEntryMutation,
     useUpdateEntryMutation,
+    WeightType,
+    WeightTypeFields,
 } from "../../generated/graphql";
 import BaseForm from "./BaseForm";
 import { useCallback, useContext, useEffect, useMemo, useState } from "react";
@@ -27,6 +30,7 @@ import {
     DEBOUNCE_TIME,
     FormProps,
     makeOptions,
+    makeOptionsDirty,
     onChange,
     SelectOption,
     SelectState,
@@ -34,6 +38,7 @@ import {
 } from "./FormUtils";
 import moment from "moment";
 import { DataGroupContext } from "../../DataGroupProvider";
+import { MbeGroupContext } from "../../MbeGroupProvider";
 import { Grid, Input, NumberInput, useMantineTheme } from "@mantine/core";
 import { DateInput } from "@mantine/dates";
 import displayOnErrorNotification from "../util/deleteNotificationUtil";
@@ -43,6 +48,7 @@ type FormInput = {
     culture: SelectOption<Culture> | undefined;
     weight: number | null;
     buyer: SelectOption<Buyer> | undefined;
+    weightType: SelectOption<WeightType> | undefined;
     date: Date | string | null;
 };
 
@@ -62,6 +68,7 @@ export default function EntryForm({
 >) {
     const { t, i18n } = useTranslation();
     const { selectedGroup: dataGroupId } = useContext(DataGroupContext);
+    const { selectedGroup: mbeGroupId } = useContext(MbeGroupContext);
     const theme = useMantineTheme();
 
     const {
@@ -92,6 +99,12 @@ export default function EntryForm({
                       label: edit?.buyer?.name ?? undefined,
                   }
                 : undefined,
+            weightType: edit
+                ? {
+                      value: edit.weightType,
+                      label: edit.weightType.unitShort,
+                  }
+                : undefined,
             date: edit
                 ? moment(edit.date as Date).format("YYYY-MM-DD")
                 : undefined,
@@ -129,6 +142,7 @@ export default function EntryForm({
                 data.cell &&
                 data.culture &&
                 data.buyer &&
+                data.weightType &&
                 data.date &&
                 dataGroupId
             ) {
@@ -139,6 +153,7 @@ export default function EntryForm({
                         // WARN: Very dumb hacky way to fix a day off value
                         date: new Date(moment(data.date).format("YYYY-MM-DD")),
                         weight: Number(data.weight),
+                        weightType: data.weightType.value.id,
                         idBuyer: data.buyer.value.id,
                         dGroup: dataGroupId,
                     },
@@ -166,6 +181,7 @@ export default function EntryForm({
                         // WARN: Very dumb hacky way to fix a day off value
                         date: new Date(moment(data.date).format("YYYY-MM-DD")),
                         weight: Number(data.weight),
+                        weightType: data.weightType?.value.id,
                         idBuyer: data.buyer.value.id,
                     },
                 });
@@ -228,30 +244,30 @@ export default function EntryForm({
         maxPage: 1,
     });
 
-    // const [weightTypeSelectState, setWeightTypeSelectState] = useState<
-    //     SelectState<WeightType>
-    // >({
-    //     selected:
-    //         edit === undefined
-    //             ? undefined
-    //             : ({
-    //                   value: edit?.weightType,
-    //                   label: edit?.weightType?.unitShort ?? "",
-    //               } as SelectOption<WeightType>),
-    //     page: 1,
-    //     pages: {},
-    //     limit: LIMIT,
-    //     filter: "",
-    //     maxPage: 1,
-    // });
+    const [weightTypeSelectState, setWeightTypeSelectState] = useState<
+        SelectState<WeightType>
+    >({
+        selected:
+            edit === undefined
+                ? undefined
+                : ({
+                      value: edit?.weightType,
+                      label: edit?.weightType?.unitShort ?? "",
+                  } as SelectOption<WeightType>),
+        page: 1,
+        pages: {},
+        limit: LIMIT,
+        filter: "",
+        maxPage: 1,
+    });
 
     const [debouncedCellInputValue, setDebouncedCellInputValue] = useState("");
     const [debouncedCultureInputValue, setDebouncedCultureInputValue] =
         useState("");
     const [debouncedBuyerInputValue, setDebouncedBuyerInputValue] =
         useState("");
-    // const [debouncedWeightTypeInputValue, setDebouncedWeightTypeInputValue] =
-    //     useState("");
+    const [debouncedWeightTypeInputValue, setDebouncedWeightTypeInputValue] =
+        useState("");
 
     const cellOptions = useMemo(() => {
         return makeOptions(cellSelectState.page, cellSelectState.pages);
@@ -265,12 +281,12 @@ export default function EntryForm({
         return makeOptions(buyerSelectState.page, buyerSelectState.pages);
     }, [buyerSelectState.page, buyerSelectState.pages]);
 
-    // const weightTypeOptions = useMemo(() => {
-    //     return makeOptionsDirty(
-    //         weightTypeSelectState.page,
-    //         weightTypeSelectState.pages
-    //     );
-    // }, [weightTypeSelectState.page, weightTypeSelectState.pages]);
+    const weightTypeOptions = useMemo(() => {
+        return makeOptionsDirty(
+            weightTypeSelectState.page,
+            weightTypeSelectState.pages
+        );
+    }, [weightTypeSelectState.page, weightTypeSelectState.pages]);
 
     const { data: cellData, isFetching: isFetchingCells } =
         useGetPairedCellsQuery(
@@ -383,38 +399,40 @@ export default function EntryForm({
         }
     );
 
-    // const { data: weightTypeData, isFetching: isFetchingWeightTypes } =
-    //     useGetWeightTypesQuery(
-    //         {
-    //             options: {
-    //                 id: undefined,
-    //                 pageSize: weightTypeSelectState.limit,
-    //                 page: weightTypeSelectState.page,
-    //                 ordering: {
-    //                     order: Ordering.Asc,
-    //                     orderBy: WeightTypeFields.Id,
-    //                 },
-    //                 filters:
-    //                     weightTypeSelectState.filter !== ""
-    //                         ? [
-    //                               {
-    //                                   value: weightTypeSelectState.filter,
-    //                                   field: WeightTypeFields.UnitShort,
-    //                               },
-    //                           ]
-    //                         : undefined,
-    //                 mbeGroupId: 1,
-    //             },
-    //         },
-    //         {
-    //             queryKey: [
-    //                 "getWeightTypesForm",
-    //                 weightTypeSelectState.limit,
-    //                 weightTypeSelectState.page,
-    //             ],
-    //             keepPreviousData: true,
-    //         }
-    //     );
+    const { data: weightTypeData, isFetching: isFetchingWeightTypes } =
+        useGetWeightTypesQuery(
+            {
+                options: {
+                    id: undefined,
+                    pageSize: weightTypeSelectState.limit,
+                    page: weightTypeSelectState.page,
+                    ordering: {
+                        order: Ordering.Asc,
+                        orderBy: WeightTypeFields.Id,
+                    },
+                    filters:
+                        weightTypeSelectState.filter !== ""
+                            ? [
+                                  {
+                                      value: weightTypeSelectState.filter,
+                                      field: WeightTypeFields.UnitShort,
+                                  },
+                              ]
+                            : undefined,
+                    mbeGroupId: mbeGroupId ?? -1,
+                },
+            },
+            {
+                queryKey: [
+                    "getWeightTypesForm",
+                    weightTypeSelectState.limit,
+                    weightTypeSelectState.page,
+                    mbeGroupId,
+                ],
+                keepPreviousData: true,
+                enabled: mbeGroupId !== undefined,
+            }
+        );
 
     useEffect(() => {
         if (cellData) {
@@ -456,19 +474,19 @@ export default function EntryForm({
         }
     }, [buyerData, setBuyerSelectState]);
 
-    // useEffect(() => {
-    //     if (weightTypeData) {
-    //         setWeightTypeSelectState((old) => ({
-    //             ...old,
-    //             maxPage: weightTypeData.weightTypes.totalPages,
-    //             pages: {
-    //                 ...old.pages,
-    //                 [weightTypeData.weightTypes.page]:
-    //                     weightTypeData.weightTypes.results,
-    //             },
-    //         }));
-    //     }
-    // }, [weightTypeData, setWeightTypeSelectState]);
+    useEffect(() => {
+        if (weightTypeData) {
+            setWeightTypeSelectState((old) => ({
+                ...old,
+                maxPage: weightTypeData.weightTypes.totalPages,
+                pages: {
+                    ...old.pages,
+                    [weightTypeData.weightTypes.page]:
+                        weightTypeData.weightTypes.results,
+                },
+            }));
+        }
+    }, [weightTypeData, setWeightTypeSelectState]);
 
     useEffect(() => {
         const timeout = setTimeout(() => {
@@ -509,18 +527,18 @@ export default function EntryForm({
         };
     }, [debouncedBuyerInputValue]);
 
-    // useEffect(() => {
-    //     const timeout = setTimeout(() => {
-    //         setWeightTypeSelectState((old) => ({
-    //             ...old,
-    //             page: 1,
-    //             filter: debouncedWeightTypeInputValue.trim(),
-    //         }));
-    //     }, DEBOUNCE_TIME);
-    //     return () => {
-    //         clearTimeout(timeout);
-    //     };
-    // }, [debouncedWeightTypeInputValue]);
+    useEffect(() => {
+        const timeout = setTimeout(() => {
+            setWeightTypeSelectState((old) => ({
+                ...old,
+                page: 1,
+                filter: debouncedWeightTypeInputValue.trim(),
+            }));
+        }, DEBOUNCE_TIME);
+        return () => {
+            clearTimeout(timeout);
+        };
+    }, [debouncedWeightTypeInputValue]);
 
     return (
         <BaseForm
@@ -706,6 +724,82 @@ export default function EntryForm({
                         )}
                     />
                 </Grid.Col>
+                <Grid.Col sm={12} md={6} lg={6}>
+                    <Controller
+                        name="weightType"
+                        control={control}
+                        rules={{ required: t("weight.errors.name") }}
+                        render={() => (
+                            <Input.Wrapper
+                                label={t("measureType.name")}
+                                withAsterisk
+                                error={
+                                    errors.weightType
+                                        ? t("weight.errors.name")
+                                        : undefined
+                                }
+                            >
+                                <Select
+                                    menuPortalTarget={document.body}
+                                    placeholder={t("measureType.name")}
+                                    loadingMessage={() => t("loading")}
+                                    noOptionsMessage={() => t("noOptions")}
+                                    styles={selectStyle(
+                                        errors.weightType,
+                                        theme
+                                    )}
+                                    isMulti={false}
+                                    value={weightTypeSelectState.selected}
+                                    options={weightTypeOptions}
+                                    onMenuClose={() => {
+                                        setWeightTypeSelectState((old) => ({
+                                            ...old,
+                                            page: 1,
+                                        }));
+                                    }}
+                                    onMenuScrollToBottom={
+                                        weightTypeSelectState.page <
+                                        weightTypeSelectState.maxPage
+                                            ? () => {
+                                                  setWeightTypeSelectState(
+                                                      (old) => ({
+                                                          ...old,
+                                                          page: old.page + 1,
+                                                      })
+                                                  );
+                                              }
+                                            : undefined
+                                    }
+                                    onInputChange={(value, actionMeta) => {
+                                        if (
+                                            actionMeta.action === "input-change"
+                                        ) {
+                                            setDebouncedWeightTypeInputValue(
+                                                value
+                                            );
+                                        }
+                                    }}
+                                    onChange={(value, actionMeta) => {
+                                        onChange(
+                                            value,
+                                            actionMeta,
+                                            setWeightTypeSelectState
+                                        );
+                                        setValue(
+                                            "weightType",
+                                            value ?? undefined,
+                                            {
+                                                shouldValidate: true,
+                                            }
+                                        );
+                                    }}
+                                    isLoading={isFetchingWeightTypes}
+                                    isClearable
+                                />
+                            </Input.Wrapper>
+                        )}
+                    />
+                </Grid.Col>
                 <Grid.Col sm={12} md={6} lg={6}>
                     <Controller
                         name="buyer"
@@ -3,8 +3,8 @@ import { useContext, useEffect } from "react";
 import { Controller, useForm } from "react-hook-form";
 import { useTranslation } from "react-i18next";
 import {
-    MbeGroupMembersOptions,
-    useInsertMbeGroupMemberMutation,
+    MbeGroupInvitationInsertOptions,
+    useInviteMbeGroupMemberMutation,
 } from "../../generated/graphql";
 import { MbeGroupContext } from "../../MbeGroupProvider";
 import displayOnErrorNotification from "../util/deleteNotificationUtil";
@@ -25,11 +25,11 @@ export default function MbeGroupMemberForm({
         reset,
         control,
         formState: { errors },
-    } = useForm<MbeGroupMembersOptions>({
+    } = useForm<MbeGroupInvitationInsertOptions>({
         mode: "onChange",
         defaultValues: {
             idMbeGroup: undefined,
-            memberEmail: "",
+            email: "",
         },
     });
 
@@ -38,7 +38,7 @@ export default function MbeGroupMemberForm({
         reset({ idMbeGroup: mbeGroupContextValue.selectedGroup });
     }, [mbeGroupContextValue, reset]);
 
-    const insert = useInsertMbeGroupMemberMutation({
+    const invite = useInviteMbeGroupMemberMutation({
         onError: () => {
             displayOnErrorNotification();
         },
@@ -52,9 +52,9 @@ export default function MbeGroupMemberForm({
 
     return (
         <BaseForm
-            submitDisabled={insert.isLoading}
+            submitDisabled={invite.isLoading}
             onSubmit={handleSubmit((data) => {
-                insert.mutate({
+                invite.mutate({
                     options: {
                         ...data,
                     },
@@ -64,7 +64,7 @@ export default function MbeGroupMemberForm({
             <Grid mb="sm">
                 <Grid.Col>
                     <TextInput
-                        {...register("memberEmail", {
+                        {...register("email", {
                             required: t("dataGroup.errors.name"),
                         })}
                         label={"E-mail"}
@@ -72,7 +72,7 @@ export default function MbeGroupMemberForm({
                         autoComplete="off"
                         withAsterisk
                         error={
-                            errors.memberEmail
+                            errors.email
                                 ? t("dataGroup.errors.name")
                                 : undefined
                         }
@@ -11,8 +11,12 @@ import { DataGroupContext } from "../../DataGroupProvider";
 import {
     CellCulturePair,
     useDeleteCellCulturePairMutation,
-    useGetAllCellCulturePairsQuery,
+    useGetCellCulturePairsConnectionQuery,
 } from "../../generated/graphql";
+import {
+    CONNECTION_CHUNK_SIZE,
+    useConnectionChunks,
+} from "../../hooks/useConnectionChunks";
 import { usePagination } from "../../hooks/usePagination";
 import ActionButtons from "../ActionButtons";
 import DataTable from "../DataTable";
@@ -34,7 +38,6 @@ export default function CellCulturePairTable({
     const { t } = useTranslation();
 
     const { pagination, setPagination } = usePagination();
-    const [tableData, setTableData] = useState<T[]>([]);
     const [sorting, setSorting] = useState<SortingState>([]);
     const [columnFilters, setColumnFilters] = useState<ColumnFiltersState>([]);
 
@@ -52,26 +55,41 @@ export default function CellCulturePairTable({
 
     const { selectedGroup: dataGroupId } = useContext(DataGroupContext);
 
-    const { data, refetch, isInitialLoading, isFetching } =
-        useGetAllCellCulturePairsQuery(
+    const {
+        after,
+        reloads,
+        rows: tableData,
+        addChunk,
+        reload,
+    } = useConnectionChunks<T, number | undefined>(dataGroupId);
+
+    const { data, isInitialLoading, isFetching, isPreviousData } =
+        useGetCellCulturePairsConnectionQuery(
             {
                 options: {
                     id: undefined,
                     dGroup: dataGroupId ?? -1,
                 },
+                after,
+                first: CONNECTION_CHUNK_SIZE,
             },
             {
-                queryKey: ["getAllCellCulturePairs", dataGroupId],
+                queryKey: [
+                    "getCellCulturePairsConnection",
+                    dataGroupId,
+                    after,
+                    reloads,
+                ],
                 keepPreviousData: true,
                 enabled: dataGroupId !== undefined,
             }
         );
 
     useEffect(() => {
-        if (data) {
-            setTableData([...data.allCellCulturePairs.results]);
+        if (data && !isPreviousData) {
+            addChunk(data.cellCulturePairsConnection);
         }
-    }, [data]);
+    }, [data, isPreviousData, addChunk]);
 
     const columns = useMemo<ColumnDef<T>[]>(() => {
         let columns: ColumnDef<T>[] = [
@@ -111,11 +129,11 @@ export default function CellCulturePairTable({
     }, [t, isEditable]);
 
     const onSuccess = useCallback(() => {
-        refetch();
+        reload();
         if (isModalShown) {
             setIsModalShown(false);
         }
-    }, [refetch, isModalShown, setIsModalShown]);
+    }, [reload, isModalShown, setIsModalShown]);
 
     const deleteCellCulturePair = useDeleteCellCulturePairMutation({
         onError: () => {
@@ -124,7 +142,7 @@ export default function CellCulturePairTable({
             );
         },
         onSuccess: () => {
-            refetch();
+            reload();
             setIsDeleteModalShown(false);
         },
     });
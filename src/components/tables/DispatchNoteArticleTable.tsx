@@ -35,7 +35,8 @@ import {
 import moment from "moment";
 import displayOnErrorNotification from "../util/deleteNotificationUtil";
 
-type T = DispatchNoteArticle;
+// `quantityIn` needs a unit to convert to, rows are fetched without it
+type T = Omit<DispatchNoteArticle, "quantityIn">;
 type TFields = DispatchNoteArticleFields;
 
 type DispatchNoteArticleTableProps = TableProps & {
@@ -133,11 +133,9 @@ export default function DispatchNoteTable({
                 meta: { type: ColumnFilterType.Number },
             },
             {
-                accessorKey: "numericalIdentifier",
+                accessorKey: "formattedIdentifier",
                 accessorFn: (originalRow) =>
-                    originalRow.numericalIdentifier
-                        ? originalRow.numericalIdentifier.toString()
-                        : "",
+                    originalRow.formattedIdentifier ?? "",
                 cell: (info) => info.getValue(),
                 header: t("dispatchNote.numericalIdentifier").toString(),
                 id: "numerical_identifier",
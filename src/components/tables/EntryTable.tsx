@@ -17,8 +17,12 @@ import { DataGroupContext } from "../../DataGroupProvider";
 import {
     Entry,
     useDeleteEntryMutation,
-    useGetAllEntriesQuery,
+    useGetEntriesConnectionQuery,
 } from "../../generated/graphql";
+import {
+    CONNECTION_CHUNK_SIZE,
+    useConnectionChunks,
+} from "../../hooks/useConnectionChunks";
 import { usePagination } from "../../hooks/usePagination";
 import ActionButtons from "../ActionButtons";
 import {
@@ -40,7 +44,6 @@ type SelectValue = "disabled" | "cell_name" | "culture_name" | "buyer_name";
 export default function EntryTable({ isInsertable, isEditable }: TableProps) {
     const { t, i18n } = useTranslation();
 
-    const [tableData, setTableData] = useState<Entry[]>([]);
     const { pagination, setPagination } = usePagination({ pageSize: 20 });
 
     const [selectValue, setSelectValue] = useState<SelectValue>("cell_name");
@@ -58,26 +61,41 @@ export default function EntryTable({ isInsertable, isEditable }: TableProps) {
 
     const { selectedGroup: dataGroupId } = useContext(DataGroupContext);
 
-    const { data, refetch, isInitialLoading, isFetching } =
-        useGetAllEntriesQuery(
+    const {
+        after,
+        reloads,
+        rows: tableData,
+        addChunk,
+        reload,
+    } = useConnectionChunks<Entry, number | undefined>(dataGroupId);
+
+    const { data, isInitialLoading, isFetching, isPreviousData } =
+        useGetEntriesConnectionQuery(
             {
                 options: {
                     id: undefined,
                     dGroup: dataGroupId ?? -1,
                 },
+                after,
+                first: CONNECTION_CHUNK_SIZE,
             },
             {
-                queryKey: ["getAllEntries", dataGroupId],
+                queryKey: [
+                    "getEntriesConnection",
+                    dataGroupId,
+                    after,
+                    reloads,
+                ],
                 keepPreviousData: true,
                 enabled: dataGroupId !== undefined,
             }
         );
 
     useEffect(() => {
-        if (data) {
-            setTableData([...data.allEntries.results]);
+        if (data && !isPreviousData) {
+            addChunk(data.entriesConnection);
         }
-    }, [data]);
+    }, [data, isPreviousData, addChunk]);
 
     const columns = useMemo<ColumnDef<Entry>[]>(() => {
         let columns: ColumnDef<Entry>[] = [
@@ -142,12 +160,13 @@ export default function EntryTable({ isInsertable, isEditable }: TableProps) {
             {
                 accessorKey: "weight",
                 cell: (info) => {
+                    const unit = info.row.original.weightType.unitShort;
                     if (info.getValue() !== null) {
                         return `${info
                             .getValue<number>()
-                            .toLocaleString(i18n.language)} kg`;
+                            .toLocaleString(i18n.language)} ${unit}`;
                     }
-                    return "0 kg";
+                    return `0 ${unit}`;
                 },
                 header: t("entry.weight").toString(),
                 enableColumnFilter: true,
@@ -204,18 +223,18 @@ export default function EntryTable({ isInsertable, isEditable }: TableProps) {
     }, [t, isEditable, setSelectedEntry, setIsModalShown, i18n]);
 
     const onSuccess = useCallback(() => {
-        refetch();
+        reload();
         if (isModalShown) {
             setIsModalShown(false);
         }
-    }, [refetch, isModalShown, setIsModalShown]);
+    }, [reload, isModalShown, setIsModalShown]);
 
     const deleteEntry = useDeleteEntryMutation({
         onError: () => {
             displayOnErrorNotification();
         },
         onSuccess: () => {
-            refetch();
+            reload();
             setIsDeleteModalShown(false);
         },
     });
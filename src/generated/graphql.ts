@@ -37,6 +37,8 @@ export type Scalars = {
    * The input/output is a string in RFC3339 format.
    */
   DateTime: any;
+  /** A scalar that can represent any JSON value. */
+  JSON: any;
   /**
    * ISO 8601 calendar date without timezone.
    * Format: %Y-%m-%d
@@ -49,28 +51,43 @@ export type Scalars = {
   NaiveDate: any;
 };
 
-export type AllCellCulturePairs = {
-  __typename?: 'AllCellCulturePairs';
-  results: Array<CellCulturePair>;
-  total: Scalars['Int'];
-};
-
-export type AllEntires = {
-  __typename?: 'AllEntires';
-  results: Array<Entry>;
-  total: Scalars['Int'];
-};
-
 export type Article = {
   __typename?: 'Article';
   createdAt: Scalars['DateTime'];
   dGroup: Scalars['Int'];
+  /** Set while the article is in the trash */
+  deletedAt?: Maybe<Scalars['DateTime']>;
   description?: Maybe<Scalars['String']>;
   id: Scalars['Int'];
   name: Scalars['String'];
 };
 
+export type ArticleConnection = {
+  __typename?: 'ArticleConnection';
+  /** A list of edges. */
+  edges: Array<ArticleEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<Article>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type ArticleEdge = {
+  __typename?: 'ArticleEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: Article;
+};
+
 export type ArticleFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<ArticleFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -103,7 +120,10 @@ export type ArticleOrderingOptions = {
 
 export type ArticleResults = {
   __typename?: 'ArticleResults';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<Article>;
   totalItems: Scalars['Int'];
@@ -116,17 +136,112 @@ export type ArticleUpdateOptions = {
   name?: InputMaybe<Scalars['String']>;
 };
 
+export enum AuditAction {
+  Delete = 'DELETE',
+  Insert = 'INSERT',
+  Update = 'UPDATE'
+}
+
+export enum AuditEntityType {
+  Article = 'ARTICLE',
+  Buyer = 'BUYER',
+  Cell = 'CELL',
+  CellCulturePair = 'CELL_CULTURE_PAIR',
+  Culture = 'CULTURE',
+  DataGroup = 'DATA_GROUP',
+  DispatchNote = 'DISPATCH_NOTE',
+  DispatchNoteArticle = 'DISPATCH_NOTE_ARTICLE',
+  DispatchNoteIdentTracker = 'DISPATCH_NOTE_IDENT_TRACKER',
+  DispatchNoteType = 'DISPATCH_NOTE_TYPE',
+  Entry = 'ENTRY',
+  MbeGroup = 'MBE_GROUP',
+  MbeGroupInvitation = 'MBE_GROUP_INVITATION',
+  MbeGroupMember = 'MBE_GROUP_MEMBER',
+  MbeGroupPrintHeader = 'MBE_GROUP_PRINT_HEADER',
+  MbeUser = 'MBE_USER',
+  PersonalAccessToken = 'PERSONAL_ACCESS_TOKEN',
+  WeightType = 'WEIGHT_TYPE'
+}
+
+export type AuditLog = {
+  __typename?: 'AuditLog';
+  action: AuditAction;
+  /** Snapshot of the entity after the change, missing for deletes */
+  after?: Maybe<Scalars['JSON']>;
+  /** Snapshot of the entity before the change, missing for inserts */
+  before?: Maybe<Scalars['JSON']>;
+  createdAt: Scalars['DateTime'];
+  dGroup?: Maybe<Scalars['Int']>;
+  entityId: Scalars['Int'];
+  entityType: AuditEntityType;
+  id: Scalars['Int'];
+  idMbeGroup?: Maybe<Scalars['Int']>;
+  idMbeUser?: Maybe<Scalars['Int']>;
+};
+
+export type AuditLogOptions = {
+  action?: InputMaybe<AuditAction>;
+  dGroup?: InputMaybe<Scalars['Int']>;
+  entityId?: InputMaybe<Scalars['Int']>;
+  entityType?: InputMaybe<AuditEntityType>;
+  from?: InputMaybe<Scalars['DateTime']>;
+  idMbeGroup: Scalars['Int'];
+  idMbeUser?: InputMaybe<Scalars['Int']>;
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+  to?: InputMaybe<Scalars['DateTime']>;
+};
+
+export type AuditLogResults = {
+  __typename?: 'AuditLogResults';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
+  pageSize: Scalars['Int'];
+  results: Array<AuditLog>;
+  totalItems: Scalars['Int'];
+  totalPages: Scalars['Int'];
+};
+
 export type Buyer = {
   __typename?: 'Buyer';
   address?: Maybe<Scalars['String']>;
   contact?: Maybe<Scalars['String']>;
   createdAt: Scalars['DateTime'];
   dGroup: Scalars['Int'];
+  /** Set while the buyer is in the trash */
+  deletedAt?: Maybe<Scalars['DateTime']>;
   id: Scalars['Int'];
   name?: Maybe<Scalars['String']>;
 };
 
+export type BuyerConnection = {
+  __typename?: 'BuyerConnection';
+  /** A list of edges. */
+  edges: Array<BuyerEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<Buyer>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type BuyerEdge = {
+  __typename?: 'BuyerEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: Buyer;
+};
+
 export type BuyerFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<BuyerFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -161,7 +276,10 @@ export type BuyerOrderingOptions = {
 
 export type BuyerResult = {
   __typename?: 'BuyerResult';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<Buyer>;
   totalItems: Scalars['Int'];
@@ -179,12 +297,30 @@ export type Cell = {
   __typename?: 'Cell';
   createdAt: Scalars['DateTime'];
   dGroup: Scalars['Int'];
+  /** Set while the cell is in the trash */
+  deletedAt?: Maybe<Scalars['DateTime']>;
   description?: Maybe<Scalars['String']>;
   id: Scalars['Int'];
   name: Scalars['String'];
 };
 
+export type CellConnection = {
+  __typename?: 'CellConnection';
+  /** A list of edges. */
+  edges: Array<CellEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<Cell>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
 export type CellCultureFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<CellCultureFilterOptions>>;
   id?: InputMaybe<CellCulturePairIds>;
@@ -212,6 +348,29 @@ export type CellCulturePair = {
   id: Scalars['Int'];
 };
 
+export type CellCulturePairConnection = {
+  __typename?: 'CellCulturePairConnection';
+  /** A list of edges. */
+  edges: Array<CellCulturePairEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<CellCulturePair>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type CellCulturePairEdge = {
+  __typename?: 'CellCulturePairEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: CellCulturePair;
+};
+
 export enum CellCulturePairFields {
   CellDescription = 'CELL_DESCRIPTION',
   CellName = 'CELL_NAME',
@@ -227,7 +386,10 @@ export type CellCulturePairIds = {
 
 export type CellCulturePairResult = {
   __typename?: 'CellCulturePairResult';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<CellCulturePair>;
   totalItems: Scalars['Int'];
@@ -240,7 +402,18 @@ export type CellCulturePairUpdateOptions = {
   idCulture?: InputMaybe<Scalars['Int']>;
 };
 
+/** An edge in a connection. */
+export type CellEdge = {
+  __typename?: 'CellEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: Cell;
+};
+
 export type CellFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<CellFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -277,6 +450,8 @@ export type CellParity = {
 };
 
 export type CellParityFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<CellFilterOptions>>;
   id: CellParity;
@@ -287,7 +462,10 @@ export type CellParityFetchOptions = {
 
 export type CellResult = {
   __typename?: 'CellResult';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<Cell>;
   totalItems: Scalars['Int'];
@@ -313,12 +491,39 @@ export type Culture = {
   __typename?: 'Culture';
   createdAt: Scalars['DateTime'];
   dGroup: Scalars['Int'];
+  /** Set while the culture is in the trash */
+  deletedAt?: Maybe<Scalars['DateTime']>;
   description?: Maybe<Scalars['String']>;
   id: Scalars['Int'];
   name: Scalars['String'];
 };
 
+export type CultureConnection = {
+  __typename?: 'CultureConnection';
+  /** A list of edges. */
+  edges: Array<CultureEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<Culture>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type CultureEdge = {
+  __typename?: 'CultureEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: Culture;
+};
+
 export type CultureFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<CultureFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -355,6 +560,8 @@ export type CultureParity = {
 };
 
 export type CultureParityFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<CultureFilterOptions>>;
   id: CultureParity;
@@ -365,7 +572,10 @@ export type CultureParityFetchOptions = {
 
 export type CultureResult = {
   __typename?: 'CultureResult';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<Culture>;
   totalItems: Scalars['Int'];
@@ -387,6 +597,17 @@ export type DataGroup = {
   name: Scalars['String'];
 };
 
+export type DataGroupEvent = {
+  __typename?: 'DataGroupEvent';
+  /** Restored entities are reported as inserted */
+  action: AuditAction;
+  dGroup: Scalars['Int'];
+  entity: AuditEntityType;
+  id: Scalars['Int'];
+  /** Lets clients skip changes they made themselves */
+  idMbeUser: Scalars['Int'];
+};
+
 export type DataGroupInsertOptions = {
   description?: InputMaybe<Scalars['String']>;
   idMbeGroup: Scalars['Int'];
@@ -409,12 +630,31 @@ export type DeleteOptionsWeightType = {
 
 export type DispatchNote = {
   __typename?: 'DispatchNote';
+  buyer?: Maybe<Buyer>;
+  cancellationReason?: Maybe<Scalars['String']>;
+  cancelledAt?: Maybe<Scalars['DateTime']>;
+  /** Issued or cancelled note this note corrects */
+  correctsDispatchNote?: Maybe<Scalars['Int']>;
   createdAt: Scalars['DateTime'];
   dGroup: Scalars['Int'];
+  /** Set while the note is in the trash */
+  deletedAt?: Maybe<Scalars['DateTime']>;
+  /** Overrides the buyers address on the printed note */
+  deliveryAddress?: Maybe<Scalars['String']>;
+  /** Identifier as printed, e.g. `2026/0042` */
+  formattedIdentifier?: Maybe<Scalars['String']>;
   id: Scalars['Int'];
+  /** Year the identifier was assigned in when numbering resets yearly */
+  identifierYear?: Maybe<Scalars['Int']>;
+  issuedAt?: Maybe<Scalars['DateTime']>;
   issuingDate?: Maybe<Scalars['NaiveDate']>;
   noteType?: Maybe<Scalars['Int']>;
+  /**
+   * Assigned by the server from the data groups `dispatch_note_ident_tracker`
+   * once the note is issued
+   */
   numericalIdentifier?: Maybe<Scalars['Int']>;
+  status: DispatchNoteStatus;
 };
 
 export type DispatchNoteArticle = {
@@ -425,10 +665,45 @@ export type DispatchNoteArticle = {
   dispatchNote: DispatchNote;
   id: Scalars['Int'];
   quantity: Scalars['Float'];
+  /**
+   * Quantity converted into another weight type of the same dimension,
+   * fails with `INCOMPATIBLE_DIMENSIONS` or `MISSING_CONVERSION_FACTOR`
+   */
+  quantityIn: Scalars['Float'];
   weightType: WeightType;
 };
 
+
+export type DispatchNoteArticleQuantityInArgs = {
+  idWeightType: Scalars['Int'];
+};
+
+export type DispatchNoteArticleConnection = {
+  __typename?: 'DispatchNoteArticleConnection';
+  /** A list of edges. */
+  edges: Array<DispatchNoteArticleEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<DispatchNoteArticle>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type DispatchNoteArticleEdge = {
+  __typename?: 'DispatchNoteArticleEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: DispatchNoteArticle;
+};
+
 export type DispatchNoteArticleFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<DispatchNoteArticleFilterOptions>>;
   id?: InputMaybe<DispatchNoteArticleIds>;
@@ -469,7 +744,10 @@ export type DispatchNoteArticleOrderingOptions = {
 
 export type DispatchNoteArticleResults = {
   __typename?: 'DispatchNoteArticleResults';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<DispatchNoteArticle>;
   totalItems: Scalars['Int'];
@@ -484,7 +762,42 @@ export type DispatchNoteArticleUpdateOptions = {
   weightType?: InputMaybe<Scalars['Int']>;
 };
 
+export type DispatchNoteCancelOptions = {
+  id: Scalars['Int'];
+  reason: Scalars['String'];
+};
+
+export type DispatchNoteConnection = {
+  __typename?: 'DispatchNoteConnection';
+  /** A list of edges. */
+  edges: Array<DispatchNoteEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<DispatchNote>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+export enum DispatchNoteDirection {
+  In = 'IN',
+  Out = 'OUT'
+}
+
+/** An edge in a connection. */
+export type DispatchNoteEdge = {
+  __typename?: 'DispatchNoteEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: DispatchNote;
+};
+
 export type DispatchNoteFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<DispatchNoteFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -494,10 +807,14 @@ export type DispatchNoteFetchOptions = {
 };
 
 export enum DispatchNoteFields {
+  BuyerName = 'BUYER_NAME',
+  Direction = 'DIRECTION',
   Id = 'ID',
+  IdBuyer = 'ID_BUYER',
   IssuingDate = 'ISSUING_DATE',
   NoteType = 'NOTE_TYPE',
-  NumericalIdentifier = 'NUMERICAL_IDENTIFIER'
+  NumericalIdentifier = 'NUMERICAL_IDENTIFIER',
+  Status = 'STATUS'
 }
 
 export type DispatchNoteFilterOptions = {
@@ -516,18 +833,37 @@ export type DispatchNoteFilterValue = {
 
 export type DispatchNoteIdentFetchOptions = {
   idDataGroup: Scalars['Int'];
+  /** Counter of a dispatch note type with its own numbering */
+  idDispatchNoteType?: InputMaybe<Scalars['Int']>;
 };
 
 export type DispatchNoteIdentUpdateOptions = {
   idDataGroup: Scalars['Int'];
-  identifier: Scalars['Int'];
+  idDispatchNoteType?: InputMaybe<Scalars['Int']>;
+  identifier?: InputMaybe<Scalars['Int']>;
+  numberWidth?: InputMaybe<Scalars['Int']>;
+  /** Allows moving the identifier backwards, only the owner of the mbe group can override */
+  overrideBackwards?: InputMaybe<Scalars['Boolean']>;
+  resetYearly?: InputMaybe<Scalars['Boolean']>;
 };
 
 export type DispatchNoteInsertOptions = {
+  /**
+   * Issued or cancelled note of the same data group this note corrects,
+   * the correction takes over its buyer unless another one is given
+   */
+  correctsDispatchNote?: InputMaybe<Scalars['Int']>;
   dGroup: Scalars['Int'];
+  deliveryAddress?: InputMaybe<Scalars['String']>;
+  idBuyer?: InputMaybe<Scalars['Int']>;
   issuingDate?: InputMaybe<Scalars['DateTime']>;
   noteType?: InputMaybe<Scalars['Int']>;
-  numericalIdentifier?: InputMaybe<Scalars['Int']>;
+};
+
+export type DispatchNoteIssueOptions = {
+  id: Scalars['Int'];
+  /** Defaults to the notes issuing date or today when the note has none */
+  issuingDate?: InputMaybe<Scalars['DateTime']>;
 };
 
 export type DispatchNoteOrderingOptions = {
@@ -537,18 +873,87 @@ export type DispatchNoteOrderingOptions = {
 
 export type DispatchNoteResults = {
   __typename?: 'DispatchNoteResults';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<DispatchNote>;
   totalItems: Scalars['Int'];
   totalPages: Scalars['Int'];
 };
 
+export enum DispatchNoteStatus {
+  Cancelled = 'CANCELLED',
+  Draft = 'DRAFT',
+  Issued = 'ISSUED'
+}
+
+export type DispatchNoteType = {
+  __typename?: 'DispatchNoteType';
+  createdAt: Scalars['DateTime'];
+  direction: DispatchNoteDirection;
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+  name: Scalars['String'];
+  /**
+   * Notes of this type are numbered from their own counter
+   * instead of the counter shared by the whole data group
+   */
+  ownNumbering: Scalars['Boolean'];
+  /** Title printed on the dispatch note, `{identifier}` is replaced with the notes identifier */
+  printTemplate?: Maybe<Scalars['String']>;
+};
+
+export type DispatchNoteTypeDeleteOptions = {
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+};
+
+export type DispatchNoteTypeFetchOptions = {
+  direction?: InputMaybe<DispatchNoteDirection>;
+  idMbeGroup: Scalars['Int'];
+};
+
+export type DispatchNoteTypeInsertOptions = {
+  direction: DispatchNoteDirection;
+  idMbeGroup: Scalars['Int'];
+  name: Scalars['String'];
+  ownNumbering?: InputMaybe<Scalars['Boolean']>;
+  printTemplate?: InputMaybe<Scalars['String']>;
+};
+
+export type DispatchNoteTypeUpdateOptions = {
+  direction?: InputMaybe<DispatchNoteDirection>;
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+  name?: InputMaybe<Scalars['String']>;
+  ownNumbering?: InputMaybe<Scalars['Boolean']>;
+  printTemplate?: InputMaybe<Scalars['String']>;
+};
+
 export type DispatchNoteUpdateOptions = {
+  /** Empty address removes the override */
+  deliveryAddress?: InputMaybe<Scalars['String']>;
   id: Scalars['Int'];
+  /** Null removes the buyer from the note */
+  idBuyer?: InputMaybe<Scalars['Int']>;
   issuingDate?: InputMaybe<Scalars['DateTime']>;
   noteType?: InputMaybe<Scalars['Int']>;
-  numericalIdentifier?: InputMaybe<Scalars['Int']>;
+};
+
+export type EntityVersion = {
+  __typename?: 'EntityVersion';
+  author?: Maybe<MbeUser>;
+  /** Entity as it was stored in this version */
+  data: Scalars['JSON'];
+  /** Missing for versions that existed before history was kept */
+  idMbeUser?: Maybe<Scalars['Int']>;
+  validFrom: Scalars['DateTime'];
+  /** Missing for the current version */
+  validTo?: Maybe<Scalars['DateTime']>;
+  /** Starts at 1 with the oldest known version */
+  version: Scalars['Int'];
 };
 
 export type Entry = {
@@ -559,11 +964,38 @@ export type Entry = {
   culture: Culture;
   dGroup: DataGroup;
   date: Scalars['NaiveDate'];
+  deletedAt?: Maybe<Scalars['DateTime']>;
   id: Scalars['Int'];
   weight?: Maybe<Scalars['Float']>;
+  weightType: WeightType;
+};
+
+export type EntryConnection = {
+  __typename?: 'EntryConnection';
+  /** A list of edges. */
+  edges: Array<EntryEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<Entry>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
+/** An edge in a connection. */
+export type EntryEdge = {
+  __typename?: 'EntryEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: Entry;
 };
 
 export type EntryFetchOptions = {
+  /** Answers from the state versioned entities had at this time */
+  asOf?: InputMaybe<Scalars['DateTime']>;
   dGroup: Scalars['Int'];
   filters?: InputMaybe<Array<EntryFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -597,6 +1029,8 @@ export type EntryInsertOptions = {
   idCell: Scalars['Int'];
   idCulture: Scalars['Int'];
   weight?: InputMaybe<Scalars['Float']>;
+  /** Unit of `weight`, must be available to the data groups mbe group */
+  weightType: Scalars['Int'];
 };
 
 export type EntryOrderingOptions = {
@@ -606,7 +1040,10 @@ export type EntryOrderingOptions = {
 
 export type EntryResult = {
   __typename?: 'EntryResult';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<Entry>;
   totalItems: Scalars['Int'];
@@ -620,6 +1057,58 @@ export type EntryUpdateOptions = {
   idBuyer?: InputMaybe<Scalars['Int']>;
   pairIds?: InputMaybe<PairIds>;
   weight?: InputMaybe<Scalars['Float']>;
+  weightType?: InputMaybe<Scalars['Int']>;
+};
+
+export enum MassBalanceDimension {
+  Buyer = 'BUYER',
+  Cell = 'CELL',
+  CellCulturePair = 'CELL_CULTURE_PAIR',
+  Culture = 'CULTURE'
+}
+
+export type MassBalanceReport = {
+  __typename?: 'MassBalanceReport';
+  entryCount: Scalars['Int'];
+  rows: Array<MassBalanceReportRow>;
+  totalWeight: Scalars['Float'];
+  unconvertedCount: Scalars['Int'];
+};
+
+export type MassBalanceReportOptions = {
+  dGroup: Scalars['Int'];
+  dateFrom?: InputMaybe<Scalars['DateTime']>;
+  dateTo?: InputMaybe<Scalars['DateTime']>;
+  filters?: InputMaybe<Array<EntryFilterOptions>>;
+  /**
+   * Entries are grouped by every listed dimension,
+   * an empty list returns a single row with the totals of the data group
+   */
+  groupBy: Array<MassBalanceDimension>;
+};
+
+export type MassBalanceReportRow = {
+  __typename?: 'MassBalanceReportRow';
+  /** Entries included in the total weight */
+  entryCount: Scalars['Int'];
+  idBuyer?: Maybe<Scalars['Int']>;
+  idCell?: Maybe<Scalars['Int']>;
+  idCellCulturePair?: Maybe<Scalars['Int']>;
+  idCulture?: Maybe<Scalars['Int']>;
+  maxWeight?: Maybe<Scalars['Float']>;
+  minWeight?: Maybe<Scalars['Float']>;
+  nameBuyer?: Maybe<Scalars['String']>;
+  nameCell?: Maybe<Scalars['String']>;
+  nameCulture?: Maybe<Scalars['String']>;
+  /** Share of the rows total weight in the total weight of all rows, between 0 and 1 */
+  shareOfTotal: Scalars['Float'];
+  /**
+   * Weights are converted to kilograms,
+   * entries in units that can't be converted to kilograms are left out
+   */
+  totalWeight: Scalars['Float'];
+  /** Entries left out because their unit can't be converted to kilograms */
+  unconvertedCount: Scalars['Int'];
 };
 
 export type MbeGroup = {
@@ -634,10 +1123,64 @@ export type MbeGroupInsertOptions = {
   name: Scalars['String'];
 };
 
+export type MbeGroupInvitation = {
+  __typename?: 'MbeGroupInvitation';
+  createdAt: Scalars['DateTime'];
+  email: Scalars['String'];
+  expiresAt: Scalars['DateTime'];
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+  invitedBy: Scalars['Int'];
+  respondedAt?: Maybe<Scalars['DateTime']>;
+  role: MbeGroupRole;
+  status: MbeGroupInvitationStatus;
+};
+
+/** The token is only returned once, when the invitation is created */
+export type MbeGroupInvitationCreated = {
+  __typename?: 'MbeGroupInvitationCreated';
+  invitation: MbeGroupInvitation;
+  token: Scalars['String'];
+};
+
+export type MbeGroupInvitationFetchOptions = {
+  idMbeGroup: Scalars['Int'];
+};
+
+export type MbeGroupInvitationInsertOptions = {
+  email: Scalars['String'];
+  idMbeGroup: Scalars['Int'];
+  /** Defaults to viewer */
+  role?: InputMaybe<MbeGroupRole>;
+};
+
+export type MbeGroupInvitationResponseOptions = {
+  id: Scalars['Int'];
+};
+
+export type MbeGroupInvitationRevokeOptions = {
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+};
+
+export enum MbeGroupInvitationStatus {
+  Accepted = 'ACCEPTED',
+  Declined = 'DECLINED',
+  Pending = 'PENDING',
+  Revoked = 'REVOKED'
+}
+
+export type MbeGroupMemberRoleOptions = {
+  idMbeGroup: Scalars['Int'];
+  memberEmail: Scalars['String'];
+  role: MbeGroupRole;
+};
+
 export type MbeGroupMembers = {
   __typename?: 'MbeGroupMembers';
   idMbeGroup: Scalars['Int'];
   idMbeUser: Scalars['Int'];
+  role: MbeGroupRole;
 };
 
 export type MbeGroupMembersFlattened = {
@@ -646,9 +1189,15 @@ export type MbeGroupMembersFlattened = {
   groupName: Scalars['String'];
   idGroup: Scalars['Int'];
   idUser: Scalars['Int'];
+  role: MbeGroupRole;
 };
 
 export type MbeGroupMembersOptions = {
+  /**
+   * Also revokes every session of the removed member unless they are a member of other groups,
+   * only allowed for the group owner
+   */
+  forceLogout?: InputMaybe<Scalars['Boolean']>;
   idMbeGroup: Scalars['Int'];
   memberEmail: Scalars['String'];
 };
@@ -657,6 +1206,36 @@ export type MbeGroupMembersQueryOptions = {
   idMbeGroup: Scalars['Int'];
 };
 
+/** Header printed on top of every dispatch note of the mbe group */
+export type MbeGroupPrintHeader = {
+  __typename?: 'MbeGroupPrintHeader';
+  address?: Maybe<Scalars['String']>;
+  companyName?: Maybe<Scalars['String']>;
+  createdAt: Scalars['DateTime'];
+  idMbeGroup: Scalars['Int'];
+};
+
+export type MbeGroupPrintHeaderFetchOptions = {
+  idMbeGroup: Scalars['Int'];
+};
+
+export type MbeGroupPrintHeaderSetOptions = {
+  address?: InputMaybe<Scalars['String']>;
+  companyName?: InputMaybe<Scalars['String']>;
+  idMbeGroup: Scalars['Int'];
+  /** Base64 encoded PNG or JPEG image */
+  logo?: InputMaybe<Scalars['String']>;
+  /** Removes the stored logo, ignored if `logo` is set */
+  removeLogo?: InputMaybe<Scalars['Boolean']>;
+};
+
+export enum MbeGroupRole {
+  Admin = 'ADMIN',
+  Editor = 'EDITOR',
+  Owner = 'OWNER',
+  Viewer = 'VIEWER'
+}
+
 export type MbeGroupUpdateOptions = {
   idGroup: Scalars['Int'];
   name?: InputMaybe<Scalars['String']>;
@@ -667,70 +1246,191 @@ export type MbeUser = {
   createdAt: Scalars['DateTime'];
   email: Scalars['String'];
   id: Scalars['Int'];
+  /** Administrators can create users regardless of the registration policy */
+  isAdmin: Scalars['Boolean'];
 };
 
 export type MbeUserInsertOptions = {
   email: Scalars['String'];
 };
 
+export type MbeUserSession = {
+  __typename?: 'MbeUserSession';
+  createdAt: Scalars['DateTime'];
+  /** Session of the current request */
+  current: Scalars['Boolean'];
+  id: Scalars['String'];
+  ip?: Maybe<Scalars['String']>;
+  lastSeenAt: Scalars['DateTime'];
+  platform: Platform;
+  userAgent?: Maybe<Scalars['String']>;
+};
+
+export type MbeUserSessionRevokeOptions = {
+  id: Scalars['String'];
+};
+
 export type Model = {
   __typename?: 'Model';
   createdAt: Scalars['DateTime'];
+  id: Scalars['Int'];
   idDataGroup: Scalars['Int'];
+  /**
+   * Set on counters of dispatch note types with their own numbering,
+   * the data groups shared counter has no type
+   */
+  idDispatchNoteType?: Maybe<Scalars['Int']>;
+  /** Identifier the next dispatch note will receive */
   identifier: Scalars['Int'];
+  /** Year `identifier` belongs to, only kept while `reset_yearly` is enabled */
+  identifierYear?: Maybe<Scalars['Int']>;
+  nextFormattedIdentifier: Scalars['String'];
+  /** Minimal number of digits, shorter identifiers are padded with zeros */
+  numberWidth: Scalars['Int'];
+  /** Numbering starts from 1 in every calendar year */
+  resetYearly: Scalars['Boolean'];
 };
 
 export type MutationRoot = {
   __typename?: 'MutationRoot';
+  acceptGroupInvitation: MbeGroupMembers;
+  cancelDispatchNote: DispatchNote;
+  /** One-time code for the `/password-reset` route, handed to the user by an administrator */
+  createPasswordResetCode: Scalars['String'];
+  createPersonalAccessToken: PersonalAccessTokenCreated;
+  declineGroupInvitation: MbeGroupInvitation;
   deleteArticle: RowsDeleted;
+  deleteArticles: RowsDeleted;
   deleteBuyer: RowsDeleted;
+  deleteBuyers: RowsDeleted;
   deleteCell: RowsDeleted;
   deleteCellCulturePair: RowsDeleted;
+  deleteCellCulturePairs: RowsDeleted;
+  deleteCells: RowsDeleted;
   deleteCulture: RowsDeleted;
+  deleteCultures: RowsDeleted;
   deleteDataGroup: RowsDeleted;
   deleteDispatchNote: RowsDeleted;
   deleteDispatchNoteArticle: RowsDeleted;
+  deleteDispatchNoteArticles: RowsDeleted;
+  deleteDispatchNoteType: RowsDeleted;
+  deleteDispatchNotes: RowsDeleted;
+  deleteEntries: RowsDeleted;
   deleteEntry: RowsDeleted;
   deleteWeightTypes: RowsDeleted;
   insertArticle: Article;
+  insertArticles: Array<Article>;
   insertBuyer: Buyer;
+  insertBuyers: Array<Buyer>;
   insertCell: Cell;
   insertCellCulturePair: CellCulturePair;
+  insertCellCulturePairs: Array<CellCulturePair>;
+  insertCells: Array<Cell>;
   insertCulture: Culture;
+  insertCultures: Array<Culture>;
   insertDataGroup: DataGroup;
   insertDispatchNote: DispatchNote;
   insertDispatchNoteArticle: DispatchNoteArticle;
+  insertDispatchNoteArticles: Array<DispatchNoteArticle>;
+  insertDispatchNoteType: DispatchNoteType;
+  insertDispatchNotes: Array<DispatchNote>;
+  insertEntries: Array<Entry>;
   insertEntry: Entry;
-  insertGroupMember: MbeGroupMembers;
   insertMbeGroup: MbeGroup;
   insertMbeUser: MbeUser;
   insertWeightTypes: WeightType;
+  /** Admins can invite editors and viewers, only the owner can invite admins */
+  inviteGroupMember: MbeGroupInvitationCreated;
+  issueDispatchNote: DispatchNote;
+  /**
+   * Members can leave the group on their own, the owner can not leave
+   * and others can only be removed by members managing their role
+   */
   removeGroupMember: RowsDeleted;
+  restoreArticle: Article;
+  restoreBuyer: Buyer;
+  restoreCell: Cell;
+  restoreCulture: Culture;
+  restoreDispatchNote: DispatchNote;
+  restoreEntry: Entry;
+  revokeGroupInvitation: MbeGroupInvitation;
+  /** Logs out every session except the current one */
+  revokeOtherSessions: Scalars['Int'];
+  revokePersonalAccessToken: PersonalAccessToken;
+  revokeSession: Scalars['Boolean'];
+  setMbeGroupPrintHeader: MbeGroupPrintHeader;
+  setPassword: Scalars['Boolean'];
   updateArticle: Article;
+  updateArticles: Array<Article>;
   updateBuyer: Buyer;
+  updateBuyers: Array<Buyer>;
   updateCell: Cell;
   updateCellCulturePair: CellCulturePair;
+  updateCellCulturePairs: Array<CellCulturePair>;
+  updateCells: Array<Cell>;
   updateCulture: Culture;
+  updateCultures: Array<Culture>;
   updateDataGroup: DataGroup;
   updateDispatchNote: DispatchNote;
   updateDispatchNoteArticle: DispatchNoteArticle;
+  updateDispatchNoteArticles: Array<DispatchNoteArticle>;
   updateDispatchNoteIdent: Model;
+  updateDispatchNoteType: DispatchNoteType;
+  updateDispatchNotes: Array<DispatchNote>;
+  updateEntries: Array<Entry>;
   updateEntry: Entry;
+  /** Ownership can not be transferred by changing roles */
+  updateGroupMemberRole: MbeGroupMembers;
   updateMbeGroup: MbeGroup;
   updateWeightTypes: WeightType;
 };
 
 
+export type MutationRootAcceptGroupInvitationArgs = {
+  options: MbeGroupInvitationResponseOptions;
+};
+
+
+export type MutationRootCancelDispatchNoteArgs = {
+  options: DispatchNoteCancelOptions;
+};
+
+
+export type MutationRootCreatePasswordResetCodeArgs = {
+  options: PasswordResetCodeOptions;
+};
+
+
+export type MutationRootCreatePersonalAccessTokenArgs = {
+  options: PersonalAccessTokenInsertOptions;
+};
+
+
+export type MutationRootDeclineGroupInvitationArgs = {
+  options: MbeGroupInvitationResponseOptions;
+};
+
+
 export type MutationRootDeleteArticleArgs = {
   options: DeleteOptions;
 };
 
 
+export type MutationRootDeleteArticlesArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
 export type MutationRootDeleteBuyerArgs = {
   options: DeleteOptions;
 };
 
 
+export type MutationRootDeleteBuyersArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
 export type MutationRootDeleteCellArgs = {
   options: DeleteOptions;
 };
@@ -741,11 +1441,26 @@ export type MutationRootDeleteCellCulturePairArgs = {
 };
 
 
+export type MutationRootDeleteCellCulturePairsArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
+export type MutationRootDeleteCellsArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
 export type MutationRootDeleteCultureArgs = {
   options: DeleteOptions;
 };
 
 
+export type MutationRootDeleteCulturesArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
 export type MutationRootDeleteDataGroupArgs = {
   options: DeleteOptions;
 };
@@ -761,6 +1476,26 @@ export type MutationRootDeleteDispatchNoteArticleArgs = {
 };
 
 
+export type MutationRootDeleteDispatchNoteArticlesArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
+export type MutationRootDeleteDispatchNoteTypeArgs = {
+  options: DispatchNoteTypeDeleteOptions;
+};
+
+
+export type MutationRootDeleteDispatchNotesArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
+export type MutationRootDeleteEntriesArgs = {
+  options: Array<DeleteOptions>;
+};
+
+
 export type MutationRootDeleteEntryArgs = {
   options: DeleteOptions;
 };
@@ -776,11 +1511,21 @@ export type MutationRootInsertArticleArgs = {
 };
 
 
+export type MutationRootInsertArticlesArgs = {
+  options: Array<ArticleInsertOptions>;
+};
+
+
 export type MutationRootInsertBuyerArgs = {
   options: BuyerInsertOptions;
 };
 
 
+export type MutationRootInsertBuyersArgs = {
+  options: Array<BuyerInsertOptions>;
+};
+
+
 export type MutationRootInsertCellArgs = {
   options: CellInsertOptions;
 };
@@ -791,11 +1536,26 @@ export type MutationRootInsertCellCulturePairArgs = {
 };
 
 
+export type MutationRootInsertCellCulturePairsArgs = {
+  options: Array<CellCulturePairIds>;
+};
+
+
+export type MutationRootInsertCellsArgs = {
+  options: Array<CellInsertOptions>;
+};
+
+
 export type MutationRootInsertCultureArgs = {
   options: CultureInsertOptions;
 };
 
 
+export type MutationRootInsertCulturesArgs = {
+  options: Array<CultureInsertOptions>;
+};
+
+
 export type MutationRootInsertDataGroupArgs = {
   options: DataGroupInsertOptions;
 };
@@ -811,13 +1571,28 @@ export type MutationRootInsertDispatchNoteArticleArgs = {
 };
 
 
-export type MutationRootInsertEntryArgs = {
-  options: EntryInsertOptions;
+export type MutationRootInsertDispatchNoteArticlesArgs = {
+  options: Array<DispatchNoteArticleInsertOptions>;
 };
 
 
-export type MutationRootInsertGroupMemberArgs = {
-  options: MbeGroupMembersOptions;
+export type MutationRootInsertDispatchNoteTypeArgs = {
+  options: DispatchNoteTypeInsertOptions;
+};
+
+
+export type MutationRootInsertDispatchNotesArgs = {
+  options: Array<DispatchNoteInsertOptions>;
+};
+
+
+export type MutationRootInsertEntriesArgs = {
+  options: Array<EntryInsertOptions>;
+};
+
+
+export type MutationRootInsertEntryArgs = {
+  options: EntryInsertOptions;
 };
 
 
@@ -836,21 +1611,96 @@ export type MutationRootInsertWeightTypesArgs = {
 };
 
 
+export type MutationRootInviteGroupMemberArgs = {
+  options: MbeGroupInvitationInsertOptions;
+};
+
+
+export type MutationRootIssueDispatchNoteArgs = {
+  options: DispatchNoteIssueOptions;
+};
+
+
 export type MutationRootRemoveGroupMemberArgs = {
   options: MbeGroupMembersOptions;
 };
 
 
+export type MutationRootRestoreArticleArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRestoreBuyerArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRestoreCellArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRestoreCultureArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRestoreDispatchNoteArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRestoreEntryArgs = {
+  options: RestoreOptions;
+};
+
+
+export type MutationRootRevokeGroupInvitationArgs = {
+  options: MbeGroupInvitationRevokeOptions;
+};
+
+
+export type MutationRootRevokePersonalAccessTokenArgs = {
+  options: PersonalAccessTokenRevokeOptions;
+};
+
+
+export type MutationRootRevokeSessionArgs = {
+  options: MbeUserSessionRevokeOptions;
+};
+
+
+export type MutationRootSetMbeGroupPrintHeaderArgs = {
+  options: MbeGroupPrintHeaderSetOptions;
+};
+
+
+export type MutationRootSetPasswordArgs = {
+  options: SetPasswordOptions;
+};
+
+
 export type MutationRootUpdateArticleArgs = {
   options: ArticleUpdateOptions;
 };
 
 
+export type MutationRootUpdateArticlesArgs = {
+  options: Array<ArticleUpdateOptions>;
+};
+
+
 export type MutationRootUpdateBuyerArgs = {
   options: BuyerUpdateOptions;
 };
 
 
+export type MutationRootUpdateBuyersArgs = {
+  options: Array<BuyerUpdateOptions>;
+};
+
+
 export type MutationRootUpdateCellArgs = {
   options: CellUpdateOptions;
 };
@@ -861,11 +1711,26 @@ export type MutationRootUpdateCellCulturePairArgs = {
 };
 
 
+export type MutationRootUpdateCellCulturePairsArgs = {
+  options: Array<CellCulturePairUpdateOptions>;
+};
+
+
+export type MutationRootUpdateCellsArgs = {
+  options: Array<CellUpdateOptions>;
+};
+
+
 export type MutationRootUpdateCultureArgs = {
   options: CultureUpdateOptions;
 };
 
 
+export type MutationRootUpdateCulturesArgs = {
+  options: Array<CultureUpdateOptions>;
+};
+
+
 export type MutationRootUpdateDataGroupArgs = {
   options: DataGroupUpdateOptions;
 };
@@ -881,16 +1746,41 @@ export type MutationRootUpdateDispatchNoteArticleArgs = {
 };
 
 
+export type MutationRootUpdateDispatchNoteArticlesArgs = {
+  options: Array<DispatchNoteArticleUpdateOptions>;
+};
+
+
 export type MutationRootUpdateDispatchNoteIdentArgs = {
   options: DispatchNoteIdentUpdateOptions;
 };
 
 
+export type MutationRootUpdateDispatchNoteTypeArgs = {
+  options: DispatchNoteTypeUpdateOptions;
+};
+
+
+export type MutationRootUpdateDispatchNotesArgs = {
+  options: Array<DispatchNoteUpdateOptions>;
+};
+
+
+export type MutationRootUpdateEntriesArgs = {
+  options: Array<EntryUpdateOptions>;
+};
+
+
 export type MutationRootUpdateEntryArgs = {
   options: EntryUpdateOptions;
 };
 
 
+export type MutationRootUpdateGroupMemberRoleArgs = {
+  options: MbeGroupMemberRoleOptions;
+};
+
+
 export type MutationRootUpdateMbeGroupArgs = {
   options: MbeGroupUpdateOptions;
 };
@@ -905,47 +1795,148 @@ export enum Ordering {
   Desc = 'DESC'
 }
 
+/** Information about pagination in a connection */
+export type PageInfo = {
+  __typename?: 'PageInfo';
+  /** When paginating forwards, the cursor to continue. */
+  endCursor?: Maybe<Scalars['String']>;
+  /** When paginating forwards, are there more items? */
+  hasNextPage: Scalars['Boolean'];
+  /** When paginating backwards, are there more items? */
+  hasPreviousPage: Scalars['Boolean'];
+  /** When paginating backwards, the cursor to continue. */
+  startCursor?: Maybe<Scalars['String']>;
+};
+
 export type PairIds = {
   idCell: Scalars['Int'];
   idCulture: Scalars['Int'];
 };
 
+export type PasswordResetCodeOptions = {
+  idMbeUser: Scalars['Int'];
+};
+
+export type PendingMbeGroupInvitation = {
+  __typename?: 'PendingMbeGroupInvitation';
+  createdAt: Scalars['DateTime'];
+  expiresAt: Scalars['DateTime'];
+  groupName: Scalars['String'];
+  id: Scalars['Int'];
+  idMbeGroup: Scalars['Int'];
+  invitedByEmail: Scalars['String'];
+  role: MbeGroupRole;
+};
+
+export type PersonalAccessToken = {
+  __typename?: 'PersonalAccessToken';
+  access: TokenAccess;
+  createdAt: Scalars['DateTime'];
+  expiresAt: Scalars['DateTime'];
+  id: Scalars['Int'];
+  idMbeUser: Scalars['Int'];
+  lastUsedAt?: Maybe<Scalars['DateTime']>;
+  mbeGroups: Array<Scalars['Int']>;
+  name: Scalars['String'];
+  revokedAt?: Maybe<Scalars['DateTime']>;
+};
+
+/** The token is only returned once, when it is created */
+export type PersonalAccessTokenCreated = {
+  __typename?: 'PersonalAccessTokenCreated';
+  personalAccessToken: PersonalAccessToken;
+  token: Scalars['String'];
+};
+
+export type PersonalAccessTokenInsertOptions = {
+  access: TokenAccess;
+  /** Defaults to 90 days, tokens can be valid for at most a year */
+  expiresInDays?: InputMaybe<Scalars['Int']>;
+  mbeGroups: Array<Scalars['Int']>;
+  name: Scalars['String'];
+};
+
+export type PersonalAccessTokenRevokeOptions = {
+  id: Scalars['Int'];
+};
+
+export enum Platform {
+  Tauri = 'TAURI',
+  Web = 'WEB'
+}
+
 export type QueryRoot = {
   __typename?: 'QueryRoot';
-  allCellCulturePairs: AllCellCulturePairs;
-  allEntries: AllEntires;
+  activeSessions: Array<MbeUserSession>;
   articles: ArticleResults;
+  /** Cursor paged `articles`, ignores `page` and `pageSize` of the options */
+  articlesConnection: ArticleConnection;
+  /** Newest changes first */
+  auditLog: AuditLogResults;
   buyers: BuyerResult;
+  /** Cursor paged `buyers`, ignores `page` and `pageSize` of the options */
+  buyersConnection: BuyerConnection;
   cellCulturePairs: CellCulturePairResult;
+  /** Cursor paged `cellCulturePairs`, ignores `page` and `pageSize` of the options */
+  cellCulturePairsConnection: CellCulturePairConnection;
   cells: CellResult;
+  /** Cursor paged `cells`, ignores `page` and `pageSize` of the options */
+  cellsConnection: CellConnection;
   cultures: CultureResult;
+  /** Cursor paged `cultures`, ignores `page` and `pageSize` of the options */
+  culturesConnection: CultureConnection;
   dataGroups: Array<DataGroup>;
   dispatchNoteArticles: DispatchNoteArticleResults;
+  /** Cursor paged `dispatchNoteArticles`, ignores `page` and `pageSize` of the options */
+  dispatchNoteArticlesConnection: DispatchNoteArticleConnection;
   dispatchNoteIdent: Model;
+  dispatchNoteTypes: Array<DispatchNoteType>;
   dispatchNotes: DispatchNoteResults;
+  /** Cursor paged `dispatchNotes`, ignores `page` and `pageSize` of the options */
+  dispatchNotesConnection: DispatchNoteConnection;
   entries: EntryResult;
+  /** Cursor paged `entries`, ignores `page` and `pageSize` of the options */
+  entriesConnection: EntryConnection;
+  entryDispatchReconciliation: Reconciliation;
+  /** Every version of the entity, oldest first */
+  history: Array<EntityVersion>;
+  massBalanceReport: MassBalanceReport;
+  mbeGroupInvitations: Array<MbeGroupInvitation>;
   mbeGroupMembers: Array<MbeGroupMembersFlattened>;
+  mbeGroupPrintHeader?: Maybe<MbeGroupPrintHeader>;
   mbeGroups: Array<MbeGroup>;
   pairedCells: CellResult;
   pairedCultures: CultureResult;
+  /** Open invitations sent to the email of the logged in user */
+  pendingGroupInvitations: Array<PendingMbeGroupInvitation>;
+  /** Tokens of the logged in user that have not been revoked */
+  personalAccessTokens: Array<PersonalAccessToken>;
+  /** Deleted entities are kept for `SOFT_DELETE_RETENTION_DAYS` days before they are purged */
+  trash: Trash;
   unpairedCells: CellResult;
   unpairedCultures: CultureResult;
   weightTypes: WeightTypeResults;
+  /** Cursor paged `weightTypes`, ignores `page` and `pageSize` of the options */
+  weightTypesConnection: WeightTypeConnection;
 };
 
 
-export type QueryRootAllCellCulturePairsArgs = {
-  options: CellCultureFetchOptions;
+export type QueryRootArticlesArgs = {
+  options: ArticleFetchOptions;
 };
 
 
-export type QueryRootAllEntriesArgs = {
-  options: EntryFetchOptions;
+export type QueryRootArticlesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: ArticleFetchOptions;
 };
 
 
-export type QueryRootArticlesArgs = {
-  options: ArticleFetchOptions;
+export type QueryRootAuditLogArgs = {
+  options: AuditLogOptions;
 };
 
 
@@ -954,46 +1945,140 @@ export type QueryRootBuyersArgs = {
 };
 
 
+export type QueryRootBuyersConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: BuyerFetchOptions;
+};
+
+
 export type QueryRootCellCulturePairsArgs = {
   options: CellCultureFetchOptions;
 };
 
 
+export type QueryRootCellCulturePairsConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: CellCultureFetchOptions;
+};
+
+
 export type QueryRootCellsArgs = {
   options: CellFetchOptions;
 };
 
 
+export type QueryRootCellsConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: CellFetchOptions;
+};
+
+
 export type QueryRootCulturesArgs = {
   options: CultureFetchOptions;
 };
 
 
+export type QueryRootCulturesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: CultureFetchOptions;
+};
+
+
 export type QueryRootDispatchNoteArticlesArgs = {
   options: DispatchNoteArticleFetchOptions;
 };
 
 
+export type QueryRootDispatchNoteArticlesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: DispatchNoteArticleFetchOptions;
+};
+
+
 export type QueryRootDispatchNoteIdentArgs = {
   options: DispatchNoteIdentFetchOptions;
 };
 
 
+export type QueryRootDispatchNoteTypesArgs = {
+  options: DispatchNoteTypeFetchOptions;
+};
+
+
 export type QueryRootDispatchNotesArgs = {
   options: DispatchNoteFetchOptions;
 };
 
 
+export type QueryRootDispatchNotesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: DispatchNoteFetchOptions;
+};
+
+
 export type QueryRootEntriesArgs = {
   options: EntryFetchOptions;
 };
 
 
+export type QueryRootEntriesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: EntryFetchOptions;
+};
+
+
+export type QueryRootEntryDispatchReconciliationArgs = {
+  options: ReconciliationOptions;
+};
+
+
+export type QueryRootHistoryArgs = {
+  entity: AuditEntityType;
+  id: Scalars['Int'];
+};
+
+
+export type QueryRootMassBalanceReportArgs = {
+  options: MassBalanceReportOptions;
+};
+
+
+export type QueryRootMbeGroupInvitationsArgs = {
+  options: MbeGroupInvitationFetchOptions;
+};
+
+
 export type QueryRootMbeGroupMembersArgs = {
   options: MbeGroupMembersQueryOptions;
 };
 
 
+export type QueryRootMbeGroupPrintHeaderArgs = {
+  options: MbeGroupPrintHeaderFetchOptions;
+};
+
+
 export type QueryRootPairedCellsArgs = {
   options: CellParityFetchOptions;
 };
@@ -1004,6 +2089,11 @@ export type QueryRootPairedCulturesArgs = {
 };
 
 
+export type QueryRootTrashArgs = {
+  dGroup: Scalars['Int'];
+};
+
+
 export type QueryRootUnpairedCellsArgs = {
   options: CellParityFetchOptions;
 };
@@ -1018,24 +2108,183 @@ export type QueryRootWeightTypesArgs = {
   options: WeightTypeFetchOptions;
 };
 
+
+export type QueryRootWeightTypesConnectionArgs = {
+  after?: InputMaybe<Scalars['String']>;
+  before?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
+  last?: InputMaybe<Scalars['Int']>;
+  options: WeightTypeFetchOptions;
+};
+
+export type Reconciliation = {
+  __typename?: 'Reconciliation';
+  difference: Scalars['Float'];
+  dispatchedWeight: Scalars['Float'];
+  entryWeight: Scalars['Float'];
+  isDeficit: Scalars['Boolean'];
+  rows: Array<ReconciliationRow>;
+  unconvertedQuantity: Scalars['Float'];
+};
+
+export type ReconciliationOptions = {
+  dGroup: Scalars['Int'];
+  dateFrom?: InputMaybe<Scalars['DateTime']>;
+  dateTo?: InputMaybe<Scalars['DateTime']>;
+  period: ReconciliationPeriod;
+};
+
+export enum ReconciliationPeriod {
+  Day = 'DAY',
+  Month = 'MONTH',
+  Week = 'WEEK',
+  Year = 'YEAR'
+}
+
+export type ReconciliationRow = {
+  __typename?: 'ReconciliationRow';
+  /** `entryWeight - dispatchedWeight` */
+  difference: Scalars['Float'];
+  /** Summed dispatch note article quantities converted to kilograms */
+  dispatchedWeight: Scalars['Float'];
+  /** Summed entry weight in kilograms, entries in units that can't be converted are left out */
+  entryWeight: Scalars['Float'];
+  /** More was dispatched than was entered */
+  isDeficit: Scalars['Boolean'];
+  periodStart: Scalars['NaiveDate'];
+  /** Quantities whose weight type isn't a convertible mass, not included in `dispatchedWeight` */
+  unconvertedQuantity: Scalars['Float'];
+};
+
+export type RestoreOptions = {
+  id: Scalars['Int'];
+};
+
 export type RowsDeleted = {
   __typename?: 'RowsDeleted';
   numRows: Scalars['Int'];
 };
 
+export type SetPasswordOptions = {
+  /** Required when the user already has a password */
+  currentPassword?: InputMaybe<Scalars['String']>;
+  newPassword: Scalars['String'];
+};
+
+export type SubscriptionRoot = {
+  __typename?: 'SubscriptionRoot';
+  /**
+   * Entries, dispatch notes and master data inserted, updated or deleted in the data group.
+   * Ends once the session is revoked or access to the data group is lost
+   */
+  dataGroupEvents: DataGroupEvent;
+};
+
+
+export type SubscriptionRootDataGroupEventsArgs = {
+  dGroup: Scalars['Int'];
+  entities?: InputMaybe<Array<AuditEntityType>>;
+};
+
+export enum TokenAccess {
+  Read = 'READ',
+  Write = 'WRITE'
+}
+
+export type Trash = {
+  __typename?: 'Trash';
+  articles: ArticleResults;
+  buyers: BuyerResult;
+  cells: CellResult;
+  cultures: CultureResult;
+  dispatchNotes: DispatchNoteResults;
+  entries: EntryResult;
+};
+
+
+export type TrashArticlesArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
+
+export type TrashBuyersArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
+
+export type TrashCellsArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
+
+export type TrashCulturesArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
+
+export type TrashDispatchNotesArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
+
+export type TrashEntriesArgs = {
+  page?: InputMaybe<Scalars['Int']>;
+  pageSize?: InputMaybe<Scalars['Int']>;
+};
+
 export type WeightType = {
   __typename?: 'WeightType';
+  /**
+   * Factor that converts a quantity in this unit into the base unit of its dimension,
+   * units without one can't be converted
+   */
+  conversionFactor?: Maybe<Scalars['Float']>;
   createdAt: Scalars['DateTime'];
+  dimension: WeightTypeDimension;
   id: Scalars['Int'];
   unit: Scalars['String'];
   unitShort: Scalars['String'];
 };
 
+export type WeightTypeConnection = {
+  __typename?: 'WeightTypeConnection';
+  /** A list of edges. */
+  edges: Array<WeightTypeEdge>;
+  /** Requested `first` or `last` after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
+  /** A list of nodes. */
+  nodes: Array<WeightType>;
+  /** Information to aid in pagination. */
+  pageInfo: PageInfo;
+  /** Only counted when selected, counting needs another query over every matching row */
+  totalCount?: Maybe<Scalars['Int']>;
+};
+
 export type WeightTypeDeleteOptions = {
   id: Scalars['Int'];
   mbeGroup: Scalars['Int'];
 };
 
+export enum WeightTypeDimension {
+  Count = 'COUNT',
+  Mass = 'MASS',
+  Volume = 'VOLUME'
+}
+
+/** An edge in a connection. */
+export type WeightTypeEdge = {
+  __typename?: 'WeightTypeEdge';
+  /** A cursor for use in pagination */
+  cursor: Scalars['String'];
+  /** The item at the end of the edge */
+  node: WeightType;
+};
+
 export type WeightTypeFetchOptions = {
   filters?: InputMaybe<Array<WeightTypeFilterOptions>>;
   id?: InputMaybe<Scalars['Int']>;
@@ -1057,6 +2306,10 @@ export type WeightTypeFilterOptions = {
 };
 
 export type WeightTypeInsertOptions = {
+  /** Factor that converts a quantity in this unit into the base unit of its dimension */
+  conversionFactor?: InputMaybe<Scalars['Float']>;
+  /** Defaults to mass */
+  dimension?: InputMaybe<WeightTypeDimension>;
   mbeGroup: Scalars['Int'];
   unit: Scalars['String'];
   unitShort: Scalars['String'];
@@ -1069,7 +2322,10 @@ export type WeightTypeOrderingOptions = {
 
 export type WeightTypeResults = {
   __typename?: 'WeightTypeResults';
+  /** Requested page size after it was clamped to the limits configured on the server */
+  effectivePageSize: Scalars['Int'];
   page: Scalars['Int'];
+  /** @deprecated Use `effectivePageSize` */
   pageSize: Scalars['Int'];
   results: Array<WeightType>;
   totalItems: Scalars['Int'];
@@ -1077,6 +2333,8 @@ export type WeightTypeResults = {
 };
 
 export type WeightTypeUpdateOptions = {
+  conversionFactor?: InputMaybe<Scalars['Float']>;
+  dimension?: InputMaybe<WeightTypeDimension>;
   id: Scalars['Int'];
   mbeGroup: Scalars['Int'];
   unit?: InputMaybe<Scalars['String']>;
@@ -1194,12 +2452,14 @@ export type GetCellCulturesPairsQueryVariables = Exact<{
 
 export type GetCellCulturesPairsQuery = { __typename?: 'QueryRoot', cellCulturePairs: { __typename?: 'CellCulturePairResult', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'CellCulturePair', id: number, createdAt: any, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } }> } };
 
-export type GetAllCellCulturePairsQueryVariables = Exact<{
+export type GetCellCulturePairsConnectionQueryVariables = Exact<{
   options: CellCultureFetchOptions;
+  after?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
 }>;
 
 
-export type GetAllCellCulturePairsQuery = { __typename?: 'QueryRoot', allCellCulturePairs: { __typename?: 'AllCellCulturePairs', total: number, results: Array<{ __typename?: 'CellCulturePair', id: number, createdAt: any, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } }> } };
+export type GetCellCulturePairsConnectionQuery = { __typename?: 'QueryRoot', cellCulturePairsConnection: { __typename?: 'CellCulturePairConnection', pageInfo: { __typename?: 'PageInfo', hasNextPage: boolean, endCursor?: string | null }, nodes: Array<{ __typename?: 'CellCulturePair', id: number, createdAt: any, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } }> } };
 
 export type InsertCellCulturePairMutationVariables = Exact<{
   insertOptions: CellCulturePairIds;
@@ -1299,21 +2559,21 @@ export type GetDispatchNotesQueryVariables = Exact<{
 }>;
 
 
-export type GetDispatchNotesQuery = { __typename?: 'QueryRoot', dispatchNotes: { __typename?: 'DispatchNoteResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, createdAt: any, dGroup: number }> } };
+export type GetDispatchNotesQuery = { __typename?: 'QueryRoot', dispatchNotes: { __typename?: 'DispatchNoteResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, formattedIdentifier?: string | null, status: DispatchNoteStatus, issuingDate?: any | null, createdAt: any, dGroup: number }> } };
 
 export type InsertDispatchNoteMutationVariables = Exact<{
   insertOptions: DispatchNoteInsertOptions;
 }>;
 
 
-export type InsertDispatchNoteMutation = { __typename?: 'MutationRoot', insertDispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, createdAt: any, dGroup: number } };
+export type InsertDispatchNoteMutation = { __typename?: 'MutationRoot', insertDispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, formattedIdentifier?: string | null, status: DispatchNoteStatus, issuingDate?: any | null, createdAt: any, dGroup: number } };
 
 export type UpdateDispatchNoteMutationVariables = Exact<{
   updateOptions: DispatchNoteUpdateOptions;
 }>;
 
 
-export type UpdateDispatchNoteMutation = { __typename?: 'MutationRoot', updateDispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, createdAt: any, dGroup: number } };
+export type UpdateDispatchNoteMutation = { __typename?: 'MutationRoot', updateDispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, formattedIdentifier?: string | null, status: DispatchNoteStatus, issuingDate?: any | null, createdAt: any, dGroup: number } };
 
 export type DeleteDispatchNoteMutationVariables = Exact<{
   deleteOptions: DeleteOptions;
@@ -1322,28 +2582,28 @@ export type DeleteDispatchNoteMutationVariables = Exact<{
 
 export type DeleteDispatchNoteMutation = { __typename?: 'MutationRoot', deleteDispatchNote: { __typename?: 'RowsDeleted', numRows: number } };
 
-export type DispatchNotePartsFragment = { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, createdAt: any, dGroup: number };
+export type DispatchNotePartsFragment = { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, formattedIdentifier?: string | null, status: DispatchNoteStatus, issuingDate?: any | null, createdAt: any, dGroup: number };
 
 export type GetDispatchNotesArticlesQueryVariables = Exact<{
   options: DispatchNoteArticleFetchOptions;
 }>;
 
 
-export type GetDispatchNotesArticlesQuery = { __typename?: 'QueryRoot', dispatchNoteArticles: { __typename?: 'DispatchNoteArticleResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } }> } };
+export type GetDispatchNotesArticlesQuery = { __typename?: 'QueryRoot', dispatchNoteArticles: { __typename?: 'DispatchNoteArticleResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, status: DispatchNoteStatus, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } }> } };
 
 export type InsertDispatchNoteArticleMutationVariables = Exact<{
   insertOptions: DispatchNoteArticleInsertOptions;
 }>;
 
 
-export type InsertDispatchNoteArticleMutation = { __typename?: 'MutationRoot', insertDispatchNoteArticle: { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } } };
+export type InsertDispatchNoteArticleMutation = { __typename?: 'MutationRoot', insertDispatchNoteArticle: { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, status: DispatchNoteStatus, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } } };
 
 export type UpdateDispatchNoteArticleMutationVariables = Exact<{
   updateOptions: DispatchNoteArticleUpdateOptions;
 }>;
 
 
-export type UpdateDispatchNoteArticleMutation = { __typename?: 'MutationRoot', updateDispatchNoteArticle: { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } } };
+export type UpdateDispatchNoteArticleMutation = { __typename?: 'MutationRoot', updateDispatchNoteArticle: { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, status: DispatchNoteStatus, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } } };
 
 export type DeleteDispatchNoteArticleMutationVariables = Exact<{
   deleteOptions: DeleteOptions;
@@ -1352,51 +2612,53 @@ export type DeleteDispatchNoteArticleMutationVariables = Exact<{
 
 export type DeleteDispatchNoteArticleMutation = { __typename?: 'MutationRoot', deleteDispatchNoteArticle: { __typename?: 'RowsDeleted', numRows: number } };
 
-export type WeightTypePartsFragment = { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any };
+export type WeightTypePartsFragment = { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any };
 
-export type DispatchNoteArticlePartsFragment = { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } };
+export type DispatchNoteArticlePartsFragment = { __typename?: 'DispatchNoteArticle', id: number, quantity: number, createdAt: any, dispatchNote: { __typename?: 'DispatchNote', id: number, noteType?: number | null, numericalIdentifier?: number | null, status: DispatchNoteStatus, issuingDate?: any | null, dGroup: number, createdAt: any }, article: { __typename?: 'Article', id: number, name: string, description?: string | null, dGroup: number, createdAt: any }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any, idMbeGroup: number } };
 
 export type GetDispatchNoteIdentTrackerQueryVariables = Exact<{
   options: DispatchNoteIdentFetchOptions;
 }>;
 
 
-export type GetDispatchNoteIdentTrackerQuery = { __typename?: 'QueryRoot', dispatchNoteIdent: { __typename?: 'Model', idDataGroup: number, identifier: number, createdAt: any } };
+export type GetDispatchNoteIdentTrackerQuery = { __typename?: 'QueryRoot', dispatchNoteIdent: { __typename?: 'Model', idDataGroup: number, identifier: number, nextFormattedIdentifier: string, createdAt: any } };
 
 export type UpdateDispatchNoteIdentTrackerMutationVariables = Exact<{
   updateOptions: DispatchNoteIdentUpdateOptions;
 }>;
 
 
-export type UpdateDispatchNoteIdentTrackerMutation = { __typename?: 'MutationRoot', updateDispatchNoteIdent: { __typename?: 'Model', idDataGroup: number, identifier: number, createdAt: any } };
+export type UpdateDispatchNoteIdentTrackerMutation = { __typename?: 'MutationRoot', updateDispatchNoteIdent: { __typename?: 'Model', idDataGroup: number, identifier: number, nextFormattedIdentifier: string, createdAt: any } };
 
 export type GetEntriesQueryVariables = Exact<{
   options: EntryFetchOptions;
 }>;
 
 
-export type GetEntriesQuery = { __typename?: 'QueryRoot', entries: { __typename?: 'EntryResult', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } }> } };
+export type GetEntriesQuery = { __typename?: 'QueryRoot', entries: { __typename?: 'EntryResult', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } }> } };
 
-export type GetAllEntriesQueryVariables = Exact<{
+export type GetEntriesConnectionQueryVariables = Exact<{
   options: EntryFetchOptions;
+  after?: InputMaybe<Scalars['String']>;
+  first?: InputMaybe<Scalars['Int']>;
 }>;
 
 
-export type GetAllEntriesQuery = { __typename?: 'QueryRoot', allEntries: { __typename?: 'AllEntires', total: number, results: Array<{ __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } }> } };
+export type GetEntriesConnectionQuery = { __typename?: 'QueryRoot', entriesConnection: { __typename?: 'EntryConnection', pageInfo: { __typename?: 'PageInfo', hasNextPage: boolean, endCursor?: string | null }, nodes: Array<{ __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } }> } };
 
 export type InsertEntryMutationVariables = Exact<{
   insertOptions: EntryInsertOptions;
 }>;
 
 
-export type InsertEntryMutation = { __typename?: 'MutationRoot', insertEntry: { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } } };
+export type InsertEntryMutation = { __typename?: 'MutationRoot', insertEntry: { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } } };
 
 export type UpdateEntryMutationVariables = Exact<{
   updateOptions: EntryUpdateOptions;
 }>;
 
 
-export type UpdateEntryMutation = { __typename?: 'MutationRoot', updateEntry: { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } } };
+export type UpdateEntryMutation = { __typename?: 'MutationRoot', updateEntry: { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } } };
 
 export type DeleteEntryMutationVariables = Exact<{
   deleteOptions: DeleteOptions;
@@ -1407,7 +2669,7 @@ export type DeleteEntryMutation = { __typename?: 'MutationRoot', deleteEntry: {
 
 export type CellCulturePartsFragment = { __typename?: 'CellCulturePair', createdAt: any, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', id: number, name: string, description?: string | null, createdAt: any } };
 
-export type EntryPartsFragment = { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } };
+export type EntryPartsFragment = { __typename?: 'Entry', id: number, weight?: number | null, date: any, createdAt: any, buyer: { __typename?: 'Buyer', id: number, name?: string | null, address?: string | null, contact?: string | null, createdAt: any, dGroup: number }, cell: { __typename?: 'Cell', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, culture: { __typename?: 'Culture', id: number, name: string, description?: string | null, createdAt: any, dGroup: number }, weightType: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }, dGroup: { __typename?: 'DataGroup', idMbeGroup: number, id: number, name: string, description?: string | null, createdAt: any } };
 
 export type GetMbeGroupsQueryVariables = Exact<{ [key: string]: never; }>;
 
@@ -1435,12 +2697,12 @@ export type UpdateMbeGroupMutationVariables = Exact<{
 
 export type UpdateMbeGroupMutation = { __typename?: 'MutationRoot', updateMbeGroup: { __typename?: 'MbeGroup', id: number, name: string, owner: number, createdAt: any } };
 
-export type InsertMbeGroupMemberMutationVariables = Exact<{
-  options: MbeGroupMembersOptions;
+export type InviteMbeGroupMemberMutationVariables = Exact<{
+  options: MbeGroupInvitationInsertOptions;
 }>;
 
 
-export type InsertMbeGroupMemberMutation = { __typename?: 'MutationRoot', insertGroupMember: { __typename?: 'MbeGroupMembers', idMbeUser: number, idMbeGroup: number } };
+export type InviteMbeGroupMemberMutation = { __typename?: 'MutationRoot', inviteGroupMember: { __typename?: 'MbeGroupInvitationCreated', invitation: { __typename?: 'MbeGroupInvitation', id: number, email: string } } };
 
 export type RemoveMbeGroupMemberMutationVariables = Exact<{
   options: MbeGroupMembersOptions;
@@ -1461,21 +2723,21 @@ export type GetWeightTypesQueryVariables = Exact<{
 }>;
 
 
-export type GetWeightTypesQuery = { __typename?: 'QueryRoot', weightTypes: { __typename?: 'WeightTypeResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any }> } };
+export type GetWeightTypesQuery = { __typename?: 'QueryRoot', weightTypes: { __typename?: 'WeightTypeResults', page: number, pageSize: number, totalItems: number, totalPages: number, results: Array<{ __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any }> } };
 
 export type InsertWeightTypeMutationVariables = Exact<{
   options: WeightTypeInsertOptions;
 }>;
 
 
-export type InsertWeightTypeMutation = { __typename?: 'MutationRoot', insertWeightTypes: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any } };
+export type InsertWeightTypeMutation = { __typename?: 'MutationRoot', insertWeightTypes: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any } };
 
 export type UpdateWeightTypesMutationVariables = Exact<{
   options: WeightTypeUpdateOptions;
 }>;
 
 
-export type UpdateWeightTypesMutation = { __typename?: 'MutationRoot', updateWeightTypes: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, createdAt: any } };
+export type UpdateWeightTypesMutation = { __typename?: 'MutationRoot', updateWeightTypes: { __typename?: 'WeightType', id: number, unitShort: string, unit: string, dimension: WeightTypeDimension, createdAt: any } };
 
 export type DeleteWeightTypesMutationVariables = Exact<{
   options: DeleteOptionsWeightType;
@@ -1498,6 +2760,8 @@ export const DispatchNotePartsFragmentDoc = `
   id
   noteType
   numericalIdentifier
+  formattedIdentifier
+  status
   issuingDate
   createdAt
   dGroup
@@ -1508,6 +2772,7 @@ export const WeightTypePartsFragmentDoc = `
   id
   unitShort
   unit
+  dimension
   createdAt
 }
     `;
@@ -1518,6 +2783,7 @@ export const DispatchNoteArticlePartsFragmentDoc = `
     id
     noteType
     numericalIdentifier
+    status
     issuingDate
     dGroup
     createdAt
@@ -1608,6 +2874,9 @@ export const EntryPartsFragmentDoc = `
   culture {
     ...CultureParts
   }
+  weightType {
+    ...WeightTypeParts
+  }
   dGroup {
     idMbeGroup
     ...DataGroupParts
@@ -1930,11 +3199,14 @@ export const useGetCellCulturesPairsQuery = <
       fetcher<GetCellCulturesPairsQuery, GetCellCulturesPairsQueryVariables>(GetCellCulturesPairsDocument, variables),
       options
     );
-export const GetAllCellCulturePairsDocument = `
-    query GetAllCellCulturePairs($options: CellCultureFetchOptions!) {
-  allCellCulturePairs(options: $options) {
-    total
-    results {
+export const GetCellCulturePairsConnectionDocument = `
+    query GetCellCulturePairsConnection($options: CellCultureFetchOptions!, $after: String, $first: Int) {
+  cellCulturePairsConnection(options: $options, after: $after, first: $first) {
+    pageInfo {
+      hasNextPage
+      endCursor
+    }
+    nodes {
       id
       createdAt
       cell {
@@ -1962,16 +3234,16 @@ export const GetAllCellCulturePairsDocument = `
   }
 }
     `;
-export const useGetAllCellCulturePairsQuery = <
-      TData = GetAllCellCulturePairsQuery,
+export const useGetCellCulturePairsConnectionQuery = <
+      TData = GetCellCulturePairsConnectionQuery,
       TError = unknown
     >(
-      variables: GetAllCellCulturePairsQueryVariables,
-      options?: UseQueryOptions<GetAllCellCulturePairsQuery, TError, TData>
+      variables: GetCellCulturePairsConnectionQueryVariables,
+      options?: UseQueryOptions<GetCellCulturePairsConnectionQuery, TError, TData>
     ) =>
-    useQuery<GetAllCellCulturePairsQuery, TError, TData>(
-      ['GetAllCellCulturePairs', variables],
-      fetcher<GetAllCellCulturePairsQuery, GetAllCellCulturePairsQueryVariables>(GetAllCellCulturePairsDocument, variables),
+    useQuery<GetCellCulturePairsConnectionQuery, TError, TData>(
+      ['GetCellCulturePairsConnection', variables],
+      fetcher<GetCellCulturePairsConnectionQuery, GetCellCulturePairsConnectionQueryVariables>(GetCellCulturePairsConnectionDocument, variables),
       options
     );
 export const InsertCellCulturePairDocument = `
@@ -2413,6 +3685,7 @@ export const GetDispatchNoteIdentTrackerDocument = `
   dispatchNoteIdent(options: $options) {
     idDataGroup
     identifier
+    nextFormattedIdentifier
     createdAt
   }
 }
@@ -2434,6 +3707,7 @@ export const UpdateDispatchNoteIdentTrackerDocument = `
   updateDispatchNoteIdent(options: $updateOptions) {
     idDataGroup
     identifier
+    nextFormattedIdentifier
     createdAt
   }
 }
@@ -2463,6 +3737,7 @@ export const GetEntriesDocument = `
 ${BuyerPartsFragmentDoc}
 ${CellPartsFragmentDoc}
 ${CulturePartsFragmentDoc}
+${WeightTypePartsFragmentDoc}
 ${DataGroupPartsFragmentDoc}`;
 export const useGetEntriesQuery = <
       TData = GetEntriesQuery,
@@ -2476,11 +3751,14 @@ export const useGetEntriesQuery = <
       fetcher<GetEntriesQuery, GetEntriesQueryVariables>(GetEntriesDocument, variables),
       options
     );
-export const GetAllEntriesDocument = `
-    query GetAllEntries($options: EntryFetchOptions!) {
-  allEntries(options: $options) {
-    total
-    results {
+export const GetEntriesConnectionDocument = `
+    query GetEntriesConnection($options: EntryFetchOptions!, $after: String, $first: Int) {
+  entriesConnection(options: $options, after: $after, first: $first) {
+    pageInfo {
+      hasNextPage
+      endCursor
+    }
+    nodes {
       ...EntryParts
     }
   }
@@ -2489,17 +3767,18 @@ export const GetAllEntriesDocument = `
 ${BuyerPartsFragmentDoc}
 ${CellPartsFragmentDoc}
 ${CulturePartsFragmentDoc}
+${WeightTypePartsFragmentDoc}
 ${DataGroupPartsFragmentDoc}`;
-export const useGetAllEntriesQuery = <
-      TData = GetAllEntriesQuery,
+export const useGetEntriesConnectionQuery = <
+      TData = GetEntriesConnectionQuery,
       TError = unknown
     >(
-      variables: GetAllEntriesQueryVariables,
-      options?: UseQueryOptions<GetAllEntriesQuery, TError, TData>
+      variables: GetEntriesConnectionQueryVariables,
+      options?: UseQueryOptions<GetEntriesConnectionQuery, TError, TData>
     ) =>
-    useQuery<GetAllEntriesQuery, TError, TData>(
-      ['GetAllEntries', variables],
-      fetcher<GetAllEntriesQuery, GetAllEntriesQueryVariables>(GetAllEntriesDocument, variables),
+    useQuery<GetEntriesConnectionQuery, TError, TData>(
+      ['GetEntriesConnection', variables],
+      fetcher<GetEntriesConnectionQuery, GetEntriesConnectionQueryVariables>(GetEntriesConnectionDocument, variables),
       options
     );
 export const InsertEntryDocument = `
@@ -2512,6 +3791,7 @@ export const InsertEntryDocument = `
 ${BuyerPartsFragmentDoc}
 ${CellPartsFragmentDoc}
 ${CulturePartsFragmentDoc}
+${WeightTypePartsFragmentDoc}
 ${DataGroupPartsFragmentDoc}`;
 export const useInsertEntryMutation = <
       TError = unknown,
@@ -2532,6 +3812,7 @@ export const UpdateEntryDocument = `
 ${BuyerPartsFragmentDoc}
 ${CellPartsFragmentDoc}
 ${CulturePartsFragmentDoc}
+${WeightTypePartsFragmentDoc}
 ${DataGroupPartsFragmentDoc}`;
 export const useUpdateEntryMutation = <
       TError = unknown,
@@ -2640,21 +3921,23 @@ export const useUpdateMbeGroupMutation = <
       (variables?: UpdateMbeGroupMutationVariables) => fetcher<UpdateMbeGroupMutation, UpdateMbeGroupMutationVariables>(UpdateMbeGroupDocument, variables)(),
       options
     );
-export const InsertMbeGroupMemberDocument = `
-    mutation InsertMbeGroupMember($options: MbeGroupMembersOptions!) {
-  insertGroupMember(options: $options) {
-    idMbeUser
-    idMbeGroup
+export const InviteMbeGroupMemberDocument = `
+    mutation InviteMbeGroupMember($options: MbeGroupInvitationInsertOptions!) {
+  inviteGroupMember(options: $options) {
+    invitation {
+      id
+      email
+    }
   }
 }
     `;
-export const useInsertMbeGroupMemberMutation = <
+export const useInviteMbeGroupMemberMutation = <
       TError = unknown,
       TContext = unknown
-    >(options?: UseMutationOptions<InsertMbeGroupMemberMutation, TError, InsertMbeGroupMemberMutationVariables, TContext>) =>
-    useMutation<InsertMbeGroupMemberMutation, TError, InsertMbeGroupMemberMutationVariables, TContext>(
-      ['InsertMbeGroupMember'],
-      (variables?: InsertMbeGroupMemberMutationVariables) => fetcher<InsertMbeGroupMemberMutation, InsertMbeGroupMemberMutationVariables>(InsertMbeGroupMemberDocument, variables)(),
+    >(options?: UseMutationOptions<InviteMbeGroupMemberMutation, TError, InviteMbeGroupMemberMutationVariables, TContext>) =>
+    useMutation<InviteMbeGroupMemberMutation, TError, InviteMbeGroupMemberMutationVariables, TContext>(
+      ['InviteMbeGroupMember'],
+      (variables?: InviteMbeGroupMemberMutationVariables) => fetcher<InviteMbeGroupMemberMutation, InviteMbeGroupMemberMutationVariables>(InviteMbeGroupMemberDocument, variables)(),
       options
     );
 export const RemoveMbeGroupMemberDocument = `
@@ -0,0 +1,83 @@
+import { useCallback, useMemo, useState } from "react";
+
+// Rows asked for per request, the server clamps it to its own limit
+export const CONNECTION_CHUNK_SIZE = 500;
+
+type ConnectionChunk<T> = {
+    nodes: T[];
+    pageInfo: {
+        hasNextPage: boolean;
+        endCursor?: string | null;
+    };
+};
+
+type FetchedChunk<T> = {
+    after: string | undefined;
+    nodes: T[];
+};
+
+type ChunksState<T, K> = {
+    key: K;
+    after: string | undefined;
+    chunks: FetchedChunk<T>[];
+    reloads: number;
+};
+
+// Fetches every row of a connection one chunk at a time, the query uses `after`
+// and passes each chunk to `addChunk`. Chunks are kept by their cursor so
+// refetching one of them doesn't duplicate its rows. `reloads` belongs in the
+// query key, it makes `reload` fetch every chunk again instead of reading the cache
+export function useConnectionChunks<T, K>(key: K) {
+    const [state, setState] = useState<ChunksState<T, K>>({
+        key,
+        after: undefined,
+        chunks: [],
+        reloads: 0,
+    });
+
+    const isCurrent = state.key === key;
+    const after = isCurrent ? state.after : undefined;
+    const chunks = isCurrent ? state.chunks : undefined;
+
+    const addChunk = useCallback(
+        (chunk: ConnectionChunk<T>) => {
+            setState((state) => {
+                const after = state.key === key ? state.after : undefined;
+                const chunks = state.key === key ? state.chunks : [];
+                const index = chunks.findIndex(
+                    (fetched) => fetched.after === after
+                );
+                const next =
+                    chunk.pageInfo.hasNextPage && chunk.pageInfo.endCursor
+                        ? chunk.pageInfo.endCursor
+                        : after;
+                return {
+                    ...state,
+                    key,
+                    after: next,
+                    chunks: [
+                        ...(index === -1 ? chunks : chunks.slice(0, index)),
+                        { after, nodes: chunk.nodes },
+                    ],
+                };
+            });
+        },
+        [key]
+    );
+
+    const reload = useCallback(() => {
+        setState((state) => ({
+            ...state,
+            key,
+            after: undefined,
+            reloads: state.reloads + 1,
+        }));
+    }, [key]);
+
+    const rows = useMemo(
+        () => (chunks ?? []).flatMap((chunk) => chunk.nodes),
+        [chunks]
+    );
+
+    return { after, reloads: state.reloads, rows, addChunk, reload };
+}